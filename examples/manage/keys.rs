@@ -1,6 +1,9 @@
 use std::env;
 
-use deepgram::{keys::options::Options, Deepgram, DeepgramError};
+use deepgram::{
+    manage::{keys::options::Options, scopes::Scope},
+    Deepgram, DeepgramError,
+};
 
 #[tokio::main]
 async fn main() -> Result<(), DeepgramError> {
@@ -20,7 +23,7 @@ async fn main() -> Result<(), DeepgramError> {
     let key = dg_client.keys().get(&project_id, &key_id).await?;
     println!("{:#?}", key);
 
-    let options = Options::builder("New Key", ["member"]).build();
+    let options = Options::builder("New Key", [Scope::Member]).build();
     let new_key = dg_client.keys().create(&project_id, &options).await?;
     println!("{:#?}", new_key);
 
@@ -1,6 +1,6 @@
 use std::env;
 
-use deepgram::{Deepgram, DeepgramError};
+use deepgram::{manage::scopes::Scope, Deepgram, DeepgramError};
 
 #[tokio::main]
 async fn main() -> Result<(), DeepgramError> {
@@ -23,7 +23,7 @@ async fn main() -> Result<(), DeepgramError> {
 
     let message = dg_client
         .scopes()
-        .update_scope(&project_id, &member_id, "member")
+        .update_scope(&project_id, &member_id, &Scope::Member)
         .await?;
     println!("{}", message.message);
 
@@ -25,13 +25,16 @@ async fn main() -> Result<(), DeepgramError> {
     // Record the start time
     let start_time = Instant::now();
 
-    dg_client
+    let response = dg_client
         .text_to_speech()
         .speak_to_file(text, &options, output_file)
         .await?;
 
     let elapsed_time = start_time.elapsed();
+    println!("Audio saved to {:?}", response.audio);
     println!("Time to download audio: {:.2?}", elapsed_time);
+    println!("Request ID: {:?}", response.metadata.request_id);
+    println!("Characters billed: {:?}", response.metadata.char_count);
 
     Ok(())
 }
@@ -27,7 +27,7 @@ async fn main() -> Result<(), DeepgramError> {
 
     let request_builder = dg_client
         .transcription()
-        .make_prerecorded_request_builder(source, &options);
+        .make_prerecorded_request_builder(source, &options)?;
 
     // Customize the RequestBuilder here
     let customized_request_builder = request_builder
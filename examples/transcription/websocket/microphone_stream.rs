@@ -5,67 +5,56 @@ use bytes::{BufMut, Bytes, BytesMut};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::Sample;
 use crossbeam::channel::RecvError;
-use deepgram::common::options::Encoding;
+use deepgram::listen::audio::{AudioFormat, SampleFormat, Transcoder};
 use futures::channel::mpsc::{self, Receiver as FuturesReceiver};
 use futures::stream::StreamExt;
 use futures::SinkExt;
 
 use deepgram::{Deepgram, DeepgramError};
 
-fn microphone_as_stream() -> FuturesReceiver<Result<Bytes, RecvError>> {
+fn microphone_as_stream() -> (AudioFormat, FuturesReceiver<Result<Bytes, RecvError>>) {
     let (sync_tx, sync_rx) = crossbeam::channel::unbounded();
     let (mut async_tx, async_rx) = mpsc::channel(1);
 
-    thread::spawn(move || {
-        let host = cpal::default_host();
-        let device = host.default_input_device().unwrap();
-
-        // let config = device.supported_input_configs().unwrap();
-        // for config in config {
-        //     dbg!(&config);
-        // }
+    let host = cpal::default_host();
+    let device = host.default_input_device().unwrap();
+    let config = device.default_input_config().unwrap();
 
-        let config = device.default_input_config().unwrap();
+    // Samples are handed over as float32 regardless of what the device produces.
+    let format = AudioFormat::new(
+        SampleFormat::Float32,
+        config.sample_rate().0,
+        config.channels(),
+    );
 
-        // dbg!(&config);
+    thread::spawn(move || {
+        fn to_bytes<T: Sample>(data: &[T]) -> Bytes {
+            let mut bytes = BytesMut::with_capacity(data.len() * 4);
+            for sample in data {
+                bytes.put_f32_le(sample.to_f32());
+            }
+            bytes.freeze()
+        }
 
         let stream = match config.sample_format() {
             cpal::SampleFormat::F32 => device
                 .build_input_stream(
                     &config.into(),
-                    move |data: &[f32], _: &_| {
-                        let mut bytes = BytesMut::with_capacity(data.len() * 2);
-                        for sample in data {
-                            bytes.put_i16_le(sample.to_i16());
-                        }
-                        sync_tx.send(bytes.freeze()).unwrap();
-                    },
+                    move |data: &[f32], _: &_| sync_tx.send(to_bytes(data)).unwrap(),
                     |_| panic!(),
                 )
                 .unwrap(),
             cpal::SampleFormat::I16 => device
                 .build_input_stream(
                     &config.into(),
-                    move |data: &[i16], _: &_| {
-                        let mut bytes = BytesMut::with_capacity(data.len() * 2);
-                        for sample in data {
-                            bytes.put_i16_le(*sample);
-                        }
-                        sync_tx.send(bytes.freeze()).unwrap();
-                    },
+                    move |data: &[i16], _: &_| sync_tx.send(to_bytes(data)).unwrap(),
                     |_| panic!(),
                 )
                 .unwrap(),
             cpal::SampleFormat::U16 => device
                 .build_input_stream(
                     &config.into(),
-                    move |data: &[u16], _: &_| {
-                        let mut bytes = BytesMut::with_capacity(data.len() * 2);
-                        for sample in data {
-                            bytes.put_i16_le(sample.to_i16());
-                        }
-                        sync_tx.send(bytes.freeze()).unwrap();
-                    },
+                    move |data: &[u16], _: &_| sync_tx.send(to_bytes(data)).unwrap(),
                     |_| panic!(),
                 )
                 .unwrap(),
@@ -85,7 +74,7 @@ fn microphone_as_stream() -> FuturesReceiver<Result<Bytes, RecvError>> {
         }
     });
 
-    async_rx
+    (format, async_rx)
 }
 
 #[tokio::main]
@@ -95,16 +84,14 @@ async fn main() -> Result<(), DeepgramError> {
 
     let dg_client = Deepgram::new(&deepgram_api_key)?;
 
+    let (format, microphone) = microphone_as_stream();
+    let transcoder = Transcoder::new(format, AudioFormat::new(SampleFormat::Linear16, 16000, 1))?;
+
     let mut results = dg_client
         .transcription()
         .stream_request()
         .keep_alive()
-        .encoding(Encoding::Linear16)
-        // TODO Specific to my machine, not general enough example.
-        .sample_rate(44100)
-        // TODO Specific to my machine, not general enough example.
-        .channels(2)
-        .stream(microphone_as_stream())
+        .stream_transcoded(microphone, transcoder)
         .await?;
 
     println!("Deepgram Request ID: {}", results.request_id());
@@ -4,20 +4,120 @@
 //!
 //! [api]: https://developers.deepgram.com/api-reference/#transcription-prerecorded
 
-use reqwest::{header::CONTENT_TYPE, RequestBuilder};
+use std::{
+    fmt,
+    io::{self, SeekFrom},
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+
+use bytes::Bytes;
+use futures::{Stream, TryStreamExt};
+use reqwest::{
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+    RequestBuilder,
+};
 use serde::Serialize;
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncReadExt, AsyncSeekExt},
+};
+use tokio_util::io::ReaderStream;
+use url::{Host, Url};
+
+use super::s3::S3Presigner;
+use crate::DeepgramError;
 
 /// Used as a parameter for [`Transcription::prerecorded`](crate::Transcription::prerecorded) and similar functions.
 #[derive(Debug)]
 pub struct AudioSource(InternalAudioSource);
 
-#[derive(Debug)]
 enum InternalAudioSource {
     Url(String),
     Buffer {
         buffer: reqwest::Body,
         mime_type: Option<String>,
     },
+    Reader {
+        stream: ByteStream,
+        content_length: Option<u64>,
+        mime_type: Option<String>,
+        progress: Option<ProgressCallback>,
+    },
+}
+
+type ByteStream = Pin<Box<dyn Stream<Item = io::Result<Bytes>> + Send + Sync>>;
+type ProgressCallback = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+impl fmt::Debug for InternalAudioSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InternalAudioSource::Url(url) => f.debug_tuple("Url").field(url).finish(),
+            InternalAudioSource::Buffer { buffer, mime_type } => f
+                .debug_struct("Buffer")
+                .field("buffer", buffer)
+                .field("mime_type", mime_type)
+                .finish(),
+            InternalAudioSource::Reader {
+                content_length,
+                mime_type,
+                progress,
+                ..
+            } => f
+                .debug_struct("Reader")
+                .field("content_length", content_length)
+                .field("mime_type", mime_type)
+                .field("progress", &progress.is_some())
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Who downloads the audio at a remote URL.
+///
+/// Used with [`AudioSource::from_remote`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum RemoteFetch {
+    /// Send the URL to Deepgram, which downloads the audio itself.
+    #[default]
+    Deepgram,
+
+    /// Download the audio here and upload it to Deepgram, for URLs that
+    /// Deepgram cannot reach.
+    Local,
+
+    /// Download the audio here if the URL points to a loopback, private or
+    /// link-local address or to a host without a public domain, such as
+    /// `localhost` or `minio.internal`, and send the URL to Deepgram otherwise.
+    Auto,
+}
+
+/// How much of an [`AudioSource`] has been uploaded.
+///
+/// Passed to the callback set with [`AudioSource::with_progress`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub struct UploadProgress {
+    /// The number of bytes read from the source so far.
+    pub bytes_sent: u64,
+
+    /// The total number of bytes, if known.
+    pub total_bytes: Option<u64>,
+}
+
+impl UploadProgress {
+    /// The fraction of the source uploaded, from `0.0` to `1.0`, if the
+    /// total is known.
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total_bytes {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.bytes_sent as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
 }
 
 impl AudioSource {
@@ -55,6 +155,206 @@ impl AudioSource {
         })
     }
 
+    /// Constructs an [`AudioSource`] that will upload the file at `path`,
+    /// streaming it rather than reading it into memory.
+    ///
+    /// The `Content-Length` is set from the size of the file, and the
+    /// [MIME type][mime] is detected from the start of the file, falling back
+    /// to the file extension.
+    ///
+    /// ```no_run
+    /// # use deepgram::{common::{audio_source::AudioSource, options::Options}, Deepgram, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let dg = Deepgram::new("token")?;
+    /// let source = AudioSource::from_path("recording.wav")
+    ///     .await?
+    ///     .with_progress(|progress| {
+    ///         if let Some(fraction) = progress.fraction() {
+    ///             println!("{:.0}% uploaded", fraction * 100.0);
+    ///         }
+    ///     });
+    /// let response = dg
+    ///     .transcription()
+    ///     .prerecorded(source, &Options::default())
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [mime]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types#audio_and_video_types
+    pub async fn from_path(path: impl AsRef<Path>) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let mut file = File::open(path).await?;
+        let content_length = file.metadata().await?.len();
+
+        let mut header = [0; 16];
+        let mut header_len = 0;
+        while header_len < header.len() {
+            match file.read(&mut header[header_len..]).await? {
+                0 => break,
+                read => header_len += read,
+            }
+        }
+        file.seek(SeekFrom::Start(0)).await?;
+
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        let mime_type = detect_mime_type(&header[..header_len], extension);
+
+        let source = Self::from_async_read(file, Some(content_length));
+        Ok(match mime_type {
+            Some(mime_type) => source.with_mime_type(mime_type),
+            None => source,
+        })
+    }
+
+    /// Constructs an [`AudioSource`] that will upload everything read from
+    /// `reader`, streaming it rather than reading it into memory.
+    ///
+    /// If `content_length` is known, it is sent as the `Content-Length` and
+    /// used to report the fraction uploaded to the
+    /// [progress callback](Self::with_progress). It must match the number of
+    /// bytes read.
+    pub fn from_async_read(
+        reader: impl AsyncRead + Send + Sync + 'static,
+        content_length: Option<u64>,
+    ) -> Self {
+        Self(InternalAudioSource::Reader {
+            stream: Box::pin(ReaderStream::new(reader)),
+            content_length,
+            mime_type: None,
+            progress: None,
+        })
+    }
+
+    /// Constructs an [`AudioSource`] that will instruct Deepgram to download
+    /// `key` from `bucket` using a URL pre-signed with `presigner`, valid for
+    /// `expires_in`.
+    ///
+    /// The URL must stay valid until Deepgram has downloaded the audio, which
+    /// for [callback requests][callback] may be some time after the request.
+    ///
+    /// [callback]: https://developers.deepgram.com/docs/callback
+    pub fn from_s3(
+        presigner: &S3Presigner,
+        bucket: &str,
+        key: &str,
+        expires_in: Duration,
+    ) -> crate::Result<Self> {
+        Ok(Self::from_url(
+            presigner.presign_get(bucket, key, expires_in)?,
+        ))
+    }
+
+    /// Constructs an [`AudioSource`] for the audio at `url`, which is either
+    /// sent to Deepgram or downloaded and uploaded, depending on `fetch`.
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use deepgram::{common::{audio_source::{AudioSource, RemoteFetch}, s3::{S3Credentials, S3Presigner}}, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// let presigner = S3Presigner::custom(
+    ///     "http://localhost:9000",
+    ///     "us-east-1",
+    ///     S3Credentials::new("minioadmin", "minioadmin"),
+    /// )?;
+    /// let url = presigner.presign_get("recordings", "call.wav", Duration::from_secs(300))?;
+    ///
+    /// // Deepgram cannot reach localhost, so this downloads the audio and uploads it.
+    /// let source = AudioSource::from_remote(url.as_str(), RemoteFetch::Auto).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn from_remote(url: &str, fetch: RemoteFetch) -> crate::Result<Self> {
+        let parsed = Url::parse(url).map_err(|_| DeepgramError::InvalidUrl)?;
+        let local = match fetch {
+            RemoteFetch::Deepgram => false,
+            RemoteFetch::Local => true,
+            RemoteFetch::Auto => is_private_host(&parsed),
+        };
+
+        if local {
+            Self::download(parsed).await
+        } else {
+            Ok(Self::from_url(url))
+        }
+    }
+
+    /// Download the audio at `url` and stream it to Deepgram as it arrives.
+    ///
+    /// The `Content-Length` and `Content-Type` are taken from the download,
+    /// falling back to the extension of the URL for the MIME type.
+    ///
+    /// Errors if the download does not respond with a success status.
+    pub async fn download(url: Url) -> crate::Result<Self> {
+        let response = reqwest::Client::new()
+            .get(url.clone())
+            .send()
+            .await?
+            .error_for_status()?;
+
+        let content_length = response.content_length();
+        let mime_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .filter(|mime_type| !mime_type.starts_with("application/octet-stream"))
+            .map(str::to_owned)
+            .or_else(|| {
+                let extension = url.path().rsplit_once('.')?.1;
+                detect_mime_type(&[], Some(extension)).map(str::to_owned)
+            });
+
+        let stream = response.bytes_stream().map_err(io::Error::other);
+        let source = Self(InternalAudioSource::Reader {
+            stream: Box::pin(stream),
+            content_length,
+            mime_type: None,
+            progress: None,
+        });
+        Ok(match mime_type {
+            Some(mime_type) => source.with_mime_type(mime_type),
+            None => source,
+        })
+    }
+
+    /// Call `callback` each time a chunk of the audio is read to be uploaded.
+    ///
+    /// Only sources constructed with [`AudioSource::from_path`],
+    /// [`AudioSource::from_async_read`] or [`AudioSource::download`] report
+    /// progress. For other sources the callback is never called.
+    pub fn with_progress(
+        mut self,
+        callback: impl Fn(UploadProgress) + Send + Sync + 'static,
+    ) -> Self {
+        if let InternalAudioSource::Reader { progress, .. } = &mut self.0 {
+            *progress = Some(Arc::new(callback));
+        }
+        self
+    }
+
+    /// Set the [MIME type][mime] of a source that is uploaded, replacing any
+    /// detected type. Has no effect for [`AudioSource::from_url`].
+    ///
+    /// [mime]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Basics_of_HTTP/MIME_types#audio_and_video_types
+    pub fn with_mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        match &mut self.0 {
+            InternalAudioSource::Url(_) => {}
+            InternalAudioSource::Buffer {
+                mime_type: source_mime_type,
+                ..
+            }
+            | InternalAudioSource::Reader {
+                mime_type: source_mime_type,
+                ..
+            } => *source_mime_type = Some(mime_type.into()),
+        }
+        self
+    }
+
     #[allow(missing_docs)]
     pub fn fill_body(self, request_builder: RequestBuilder) -> RequestBuilder {
         match self.0 {
@@ -75,6 +375,244 @@ impl AudioSource {
                     request_builder
                 }
             }
+            InternalAudioSource::Reader {
+                stream,
+                content_length,
+                mime_type,
+                progress,
+            } => {
+                let stream = match progress {
+                    Some(progress) => report_progress(stream, content_length, progress),
+                    None => stream,
+                };
+                let mut request_builder = request_builder.body(reqwest::Body::wrap_stream(stream));
+
+                if let Some(content_length) = content_length {
+                    request_builder = request_builder.header(CONTENT_LENGTH, content_length);
+                }
+                if let Some(mime_type) = mime_type {
+                    request_builder = request_builder.header(CONTENT_TYPE, mime_type);
+                }
+                request_builder
+            }
+        }
+    }
+}
+
+fn report_progress(
+    stream: ByteStream,
+    total_bytes: Option<u64>,
+    callback: ProgressCallback,
+) -> ByteStream {
+    let mut bytes_sent = 0;
+    Box::pin(stream.map_ok(move |chunk| {
+        bytes_sent += chunk.len() as u64;
+        callback(UploadProgress {
+            bytes_sent,
+            total_bytes,
+        });
+        chunk
+    }))
+}
+
+/// Whether `url` points somewhere Deepgram is unlikely to be able to reach.
+fn is_private_host(url: &Url) -> bool {
+    match url.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            !domain.contains('.')
+                || [".localhost", ".local", ".internal", ".lan", ".home.arpa"]
+                    .iter()
+                    .any(|suffix| domain.ends_with(suffix))
+        }
+        Some(Host::Ipv4(ip)) => {
+            ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                // Shared address space, used by carrier-grade NAT.
+                || (ip.octets()[0] == 100 && ip.octets()[1] & 0xC0 == 64)
         }
+        Some(Host::Ipv6(ip)) => {
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // Unique local and link-local addresses.
+                || first & 0xFE00 == 0xFC00
+                || first & 0xFFC0 == 0xFE80
+        }
+        None => true,
+    }
+}
+
+/// Detect the MIME type of audio from the first bytes of the file, falling
+/// back to the file extension.
+fn detect_mime_type(header: &[u8], extension: Option<&str>) -> Option<&'static str> {
+    let magic = match header {
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => Some("audio/wav"),
+        [b'f', b'L', b'a', b'C', ..] => Some("audio/flac"),
+        [b'O', b'g', b'g', b'S', ..] => Some("audio/ogg"),
+        [b'I', b'D', b'3', ..] => Some("audio/mpeg"),
+        [0xFF, second, ..] if second & 0xE0 == 0xE0 && second & 0x06 != 0 => Some("audio/mpeg"),
+        [0xFF, second, ..] if second & 0xF6 == 0xF0 => Some("audio/aac"),
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => Some("audio/mp4"),
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => Some("audio/webm"),
+        [b'#', b'!', b'A', b'M', b'R', ..] => Some("audio/amr"),
+        _ => None,
+    };
+
+    magic.or_else(|| {
+        let extension = extension?.to_ascii_lowercase();
+        Some(match extension.as_str() {
+            "wav" | "wave" => "audio/wav",
+            "mp3" => "audio/mpeg",
+            "flac" => "audio/flac",
+            "ogg" | "oga" | "opus" => "audio/ogg",
+            "m4a" | "mp4" => "audio/mp4",
+            "aac" => "audio/aac",
+            "webm" => "audio/webm",
+            "amr" => "audio/amr",
+            _ => return None,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use bytes::Bytes;
+    use futures::{stream, StreamExt};
+    use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use url::Url;
+
+    use super::{
+        detect_mime_type, is_private_host, report_progress, AudioSource, InternalAudioSource,
+        RemoteFetch, UploadProgress,
+    };
+
+    #[test]
+    fn detects_mime_type() {
+        assert_eq!(
+            detect_mime_type(b"RIFF\x24\0\0\0WAVEfmt ", Some("bin")),
+            Some("audio/wav")
+        );
+        assert_eq!(detect_mime_type(b"ID3\x04", None), Some("audio/mpeg"));
+        assert_eq!(
+            detect_mime_type(&[0xFF, 0xFB, 0x90], None),
+            Some("audio/mpeg")
+        );
+        assert_eq!(
+            detect_mime_type(&[0xFF, 0xF1, 0x50], None),
+            Some("audio/aac")
+        );
+        assert_eq!(
+            detect_mime_type(b"\0\0\0\x20ftypM4A ", None),
+            Some("audio/mp4")
+        );
+        assert_eq!(detect_mime_type(b"", Some("FLAC")), Some("audio/flac"));
+        assert_eq!(detect_mime_type(b"plain text", Some("txt")), None);
+    }
+
+    #[test]
+    fn detects_private_hosts() {
+        let private = |url: &str| is_private_host(&Url::parse(url).unwrap());
+        assert!(private("http://localhost:9000/bucket/key"));
+        assert!(private("http://minio:9000/bucket/key"));
+        assert!(private("http://127.0.0.1/key"));
+        assert!(private("http://10.1.2.3/key"));
+        assert!(private("http://[::1]/key"));
+        assert!(private("http://[fd00::1]/key"));
+        assert!(!private("https://bucket.s3.us-east-1.amazonaws.com/key"));
+        assert!(!private("http://8.8.8.8/key"));
+    }
+
+    #[tokio::test]
+    async fn downloads_private_urls() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = [0; 1024];
+            let _ = socket.read(&mut request).await.unwrap();
+            socket
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\n\
+                      Content-Type: application/octet-stream\r\n\
+                      Content-Length: 5\r\n\r\n\
+                      audio",
+                )
+                .await
+                .unwrap();
+        });
+
+        let url = format!("http://{addr}/bucket/call.mp3?X-Amz-Signature=abc");
+        let source = AudioSource::from_remote(&url, RemoteFetch::Auto)
+            .await
+            .unwrap();
+        assert!(matches!(source.0, InternalAudioSource::Reader { .. }));
+
+        let request = source
+            .fill_body(reqwest::Client::new().post("http://localhost"))
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()[CONTENT_TYPE], "audio/mpeg");
+        assert_eq!(request.headers()[CONTENT_LENGTH], "5");
+
+        let source = AudioSource::from_remote(&url, RemoteFetch::Deepgram)
+            .await
+            .unwrap();
+        assert!(matches!(source.0, InternalAudioSource::Url(_)));
+    }
+
+    #[tokio::test]
+    async fn from_path_sets_headers() {
+        let path = std::env::temp_dir().join(format!("deepgram-source-{}.raw", std::process::id()));
+        std::fs::write(&path, b"fLaC and then some audio").unwrap();
+
+        let source = AudioSource::from_path(&path).await.unwrap();
+        let request = source
+            .fill_body(reqwest::Client::new().post("http://localhost"))
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()[CONTENT_TYPE], "audio/flac");
+        assert_eq!(request.headers()[CONTENT_LENGTH], "24");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_progress() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let chunks = stream::iter([
+            Ok(Bytes::from_static(&[0; 3])),
+            Ok(Bytes::from_static(&[0])),
+        ]);
+        let stream = report_progress(Box::pin(chunks), Some(4), {
+            let reports = reports.clone();
+            Arc::new(move |progress| reports.lock().unwrap().push(progress))
+        });
+        assert_eq!(stream.count().await, 2);
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(
+            *reports,
+            vec![
+                UploadProgress {
+                    bytes_sent: 3,
+                    total_bytes: Some(4)
+                },
+                UploadProgress {
+                    bytes_sent: 4,
+                    total_bytes: Some(4)
+                },
+            ]
+        );
+        assert_eq!(reports[0].fraction(), Some(0.75));
     }
 }
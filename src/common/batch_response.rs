@@ -7,6 +7,9 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::stream_response::{Alternatives, StreamResponse};
+use super::timestamp::Timestamp;
+
 /// Returned by [`Transcription::prerecorded`](crate::Transcription::prerecorded).
 ///
 /// See the [Deepgram API Reference][api] for more info.
@@ -22,6 +25,258 @@ pub struct Response {
     pub results: ListenResults,
 }
 
+impl Response {
+    /// Fold the responses of a live transcription into the shape of a
+    /// pre-recorded one, so the same code can handle both.
+    ///
+    /// Only final transcripts are kept. The transcripts and words of each
+    /// alternative are joined in order for each channel, and the confidence
+    /// of an alternative is the mean confidence of its words. The metadata is
+    /// taken from the terminal response where there is one.
+    pub fn from_stream_responses(responses: impl IntoIterator<Item = StreamResponse>) -> Self {
+        let mut request_id = None;
+        let mut created = Timestamp::from_raw(String::new());
+        let mut duration = None;
+        let mut channel_count = 0;
+        let mut end = 0.0_f64;
+        let mut channels: Vec<Vec<Alternatives>> = Vec::new();
+
+        for response in responses {
+            match response {
+                StreamResponse::TranscriptResponse {
+                    start,
+                    duration,
+                    is_final,
+                    channel,
+                    metadata,
+                    channel_index,
+                    ..
+                } => {
+                    request_id = request_id.or_else(|| Uuid::parse_str(&metadata.request_id).ok());
+                    end = end.max(start + duration);
+                    if !is_final {
+                        continue;
+                    }
+
+                    let index = channel_index
+                        .first()
+                        .and_then(|&index| usize::try_from(index).ok())
+                        .unwrap_or(0);
+                    if channels.len() <= index {
+                        channels.resize_with(index + 1, Vec::new);
+                    }
+                    let alternatives = &mut channels[index];
+                    for (i, segment) in channel.alternatives.into_iter().enumerate() {
+                        if alternatives.len() <= i {
+                            alternatives.push(Alternatives {
+                                transcript: String::new(),
+                                words: Vec::new(),
+                                confidence: 0.0,
+                            });
+                        }
+                        let alternative = &mut alternatives[i];
+                        if !segment.transcript.is_empty() {
+                            if !alternative.transcript.is_empty() {
+                                alternative.transcript.push(' ');
+                            }
+                            alternative.transcript.push_str(&segment.transcript);
+                        }
+                        alternative.words.extend(segment.words);
+                    }
+                }
+                StreamResponse::TerminalResponse {
+                    request_id: terminal_request_id,
+                    created: terminal_created,
+                    duration: terminal_duration,
+                    channels,
+                } => {
+                    request_id = Uuid::parse_str(&terminal_request_id).ok().or(request_id);
+                    created = Timestamp::from_raw(terminal_created);
+                    duration = Some(terminal_duration);
+                    channel_count = channels as usize;
+                }
+                _ => {}
+            }
+        }
+
+        if channels.len() < channel_count {
+            channels.resize_with(channel_count, Vec::new);
+        }
+
+        let channels: Vec<ChannelResult> = channels
+            .into_iter()
+            .map(|alternatives| ChannelResult {
+                search: None,
+                alternatives: alternatives
+                    .into_iter()
+                    .map(|mut alternative| {
+                        if !alternative.words.is_empty() {
+                            alternative.confidence = alternative
+                                .words
+                                .iter()
+                                .map(|word| word.confidence)
+                                .sum::<f64>()
+                                / alternative.words.len() as f64;
+                        }
+                        alternative.into()
+                    })
+                    .collect(),
+                detected_language: None,
+            })
+            .collect();
+
+        Response {
+            metadata: ListenMetadata {
+                request_id: request_id.unwrap_or_default(),
+                transaction_key: String::new(),
+                sha256: String::new(),
+                created,
+                duration: duration.unwrap_or(end),
+                channels: channels.len(),
+            },
+            results: ListenResults {
+                channels,
+                utterances: None,
+                intents: None,
+                sentiments: None,
+                topics: None,
+                summary: None,
+            },
+        }
+    }
+
+    /// The alternative with the highest confidence for `channel`, if any.
+    pub fn best_alternative(&self, channel: usize) -> Option<&ResultAlternative> {
+        self.results
+            .channels
+            .get(channel)?
+            .alternatives
+            .iter()
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+    }
+
+    /// The words of the [best alternative](Self::best_alternative) of every
+    /// channel, merged into a single timeline sorted by start time.
+    ///
+    /// Words that start at the same time are ordered by channel.
+    pub fn timeline(&self) -> Vec<ChannelWord<'_>> {
+        let mut words: Vec<_> = (0..self.results.channels.len())
+            .filter_map(|channel| Some((channel, self.best_alternative(channel)?)))
+            .flat_map(|(channel, alternative)| {
+                alternative
+                    .words
+                    .iter()
+                    .map(move |word| ChannelWord { channel, word })
+            })
+            .collect();
+        words.sort_by(|a, b| {
+            a.word
+                .start
+                .total_cmp(&b.word.start)
+                .then(a.channel.cmp(&b.channel))
+        });
+        words
+    }
+
+    /// The words of the [`timeline`](Self::timeline) that overlap the time
+    /// range from `start` to `end`, in seconds.
+    ///
+    /// ```
+    /// # use deepgram::common::batch_response::Response;
+    /// # fn example(response: &Response) {
+    /// for word in response.words_in_range(10.0, 15.0) {
+    ///     println!("[{}] {}: {}", word.channel, word.word.start, word.word.text());
+    /// }
+    /// # }
+    /// ```
+    pub fn words_in_range(&self, start: f64, end: f64) -> Vec<ChannelWord<'_>> {
+        self.timeline()
+            .into_iter()
+            .filter(|word| word.word.end > start && word.word.start < end)
+            .collect()
+    }
+
+    /// The words of the [`timeline`](Self::timeline) transcribed with a
+    /// confidence below `threshold`, such as for highlighting words that
+    /// need to be reviewed.
+    pub fn low_confidence_words(&self, threshold: f64) -> Vec<ChannelWord<'_>> {
+        self.timeline()
+            .into_iter()
+            .filter(|word| word.word.confidence < threshold)
+            .collect()
+    }
+
+    /// Group consecutive words of the [`timeline`](Self::timeline) by
+    /// channel and [speaker](Word::speaker).
+    ///
+    /// Without the [Diarization feature][docs], there is one turn per run of
+    /// words on the same channel.
+    ///
+    /// [docs]: https://developers.deepgram.com/documentation/features/diarize/
+    pub fn speaker_turns(&self) -> Vec<SpeakerTurn> {
+        let mut turns: Vec<SpeakerTurn> = Vec::new();
+        for ChannelWord { channel, word } in self.timeline() {
+            match turns.last_mut() {
+                Some(turn) if turn.channel == channel && turn.speaker == word.speaker => {
+                    turn.end = turn.end.max(word.end);
+                    turn.text.push(' ');
+                    turn.text.push_str(word.text());
+                    turn.words.push(word.clone());
+                }
+                _ => turns.push(SpeakerTurn {
+                    channel,
+                    speaker: word.speaker,
+                    start: word.start,
+                    end: word.end,
+                    text: word.text().to_owned(),
+                    words: vec![word.clone()],
+                }),
+            }
+        }
+        turns
+    }
+}
+
+/// A [`Word`] along with the channel it was transcribed from.
+///
+/// Returned by [`Response::timeline`] and related methods.
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[non_exhaustive]
+pub struct ChannelWord<'a> {
+    #[allow(missing_docs)]
+    pub channel: usize,
+
+    #[allow(missing_docs)]
+    pub word: &'a Word,
+}
+
+/// Consecutive words spoken by the same speaker on the same channel.
+///
+/// Returned by [`Response::speaker_turns`].
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub struct SpeakerTurn {
+    #[allow(missing_docs)]
+    pub channel: usize,
+
+    /// [`None`] unless the [Diarization feature][docs] is set.
+    ///
+    /// [docs]: https://developers.deepgram.com/documentation/features/diarize/
+    pub speaker: Option<usize>,
+
+    /// The start of the first word, in seconds.
+    pub start: f64,
+
+    /// The end of the last word, in seconds.
+    pub end: f64,
+
+    /// The [text](Word::text) of the words, separated by spaces.
+    pub text: String,
+
+    #[allow(missing_docs)]
+    pub words: Vec<Word>,
+}
+
 /// Returned by [`Transcription::prerecorded_callback`](crate::Transcription::prerecorded_callback).
 ///
 /// See the [Deepgram Callback feature docs][docs] for more info.
@@ -52,7 +307,7 @@ pub struct ListenMetadata {
     pub sha256: String,
 
     #[allow(missing_docs)]
-    pub created: String,
+    pub created: Timestamp,
 
     #[allow(missing_docs)]
     pub duration: f64,
@@ -369,6 +624,16 @@ pub struct Word {
     pub punctuated_word: Option<String>,
 }
 
+impl Word {
+    /// The punctuated word if the [Punctuation feature][docs] is set,
+    /// otherwise the bare word.
+    ///
+    /// [docs]: https://developers.deepgram.com/documentation/features/punctuate/
+    pub fn text(&self) -> &str {
+        self.punctuated_word.as_deref().unwrap_or(&self.word)
+    }
+}
+
 /// Search result.
 ///
 /// See the [Deepgram API Reference][api]
@@ -391,3 +656,191 @@ pub struct Hit {
     #[allow(missing_docs)]
     pub snippet: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{ChannelResult, ListenMetadata, ListenResults, Response, ResultAlternative, Word};
+    use crate::common::{
+        stream_response::{Alternatives, Channel, Metadata, ModelInfo, StreamResponse},
+        timestamp::Timestamp,
+    };
+
+    fn word(word: &str, start: f64, confidence: f64, speaker: usize) -> Word {
+        Word {
+            word: word.to_lowercase(),
+            start,
+            end: start + 0.5,
+            confidence,
+            speaker: Some(speaker),
+            punctuated_word: Some(word.to_owned()),
+        }
+    }
+
+    fn alternative(words: Vec<Word>, confidence: f64) -> ResultAlternative {
+        ResultAlternative {
+            transcript: String::new(),
+            confidence,
+            words,
+            paragraphs: None,
+            entities: None,
+        }
+    }
+
+    fn channel(alternatives: Vec<ResultAlternative>) -> ChannelResult {
+        ChannelResult {
+            search: None,
+            alternatives,
+            detected_language: None,
+        }
+    }
+
+    fn response() -> Response {
+        Response {
+            metadata: ListenMetadata {
+                request_id: Uuid::nil(),
+                transaction_key: String::new(),
+                sha256: String::new(),
+                created: Timestamp::from_raw(String::new()),
+                duration: 3.0,
+                channels: 2,
+            },
+            results: ListenResults {
+                channels: vec![
+                    channel(vec![
+                        alternative(
+                            vec![
+                                word("Hello", 0.0, 0.9, 0),
+                                word("there.", 0.5, 0.4, 0),
+                                word("Bye.", 2.5, 0.95, 1),
+                            ],
+                            0.8,
+                        ),
+                        alternative(vec![word("Yellow", 0.0, 0.2, 0)], 0.3),
+                    ]),
+                    channel(vec![alternative(
+                        vec![word("Hi!", 1.0, 0.99, 0), word("Again", 1.5, 0.3, 0)],
+                        0.9,
+                    )]),
+                ],
+                utterances: None,
+                intents: None,
+                sentiments: None,
+                topics: None,
+                summary: None,
+            },
+        }
+    }
+
+    #[test]
+    fn timeline_merges_channels() {
+        let response = response();
+        assert_eq!(response.best_alternative(0).unwrap().confidence, 0.8);
+        assert!(response.best_alternative(2).is_none());
+
+        let timeline: Vec<_> = response
+            .timeline()
+            .iter()
+            .map(|word| (word.channel, word.word.text()))
+            .collect();
+        assert_eq!(
+            timeline,
+            vec![
+                (0, "Hello"),
+                (0, "there."),
+                (1, "Hi!"),
+                (1, "Again"),
+                (0, "Bye.")
+            ]
+        );
+
+        let in_range: Vec<_> = response
+            .words_in_range(0.75, 1.25)
+            .iter()
+            .map(|word| word.word.text())
+            .collect();
+        assert_eq!(in_range, vec!["there.", "Hi!"]);
+
+        let low: Vec<_> = response
+            .low_confidence_words(0.5)
+            .iter()
+            .map(|word| word.word.text())
+            .collect();
+        assert_eq!(low, vec!["there.", "Again"]);
+    }
+
+    #[test]
+    fn folds_stream_responses() {
+        let transcript = |channel: i32, start: f64, is_final: bool, words: Vec<Word>| {
+            StreamResponse::TranscriptResponse {
+                type_field: "Results".to_owned(),
+                start,
+                duration: 1.0,
+                is_final,
+                speech_final: is_final,
+                from_finalize: false,
+                channel: Channel {
+                    alternatives: vec![Alternatives {
+                        transcript: words.iter().map(Word::text).collect::<Vec<_>>().join(" "),
+                        words,
+                        confidence: 0.0,
+                    }],
+                },
+                metadata: Metadata {
+                    request_id: "00000000-0000-0000-0000-000000000001".to_owned(),
+                    model_info: ModelInfo {
+                        name: String::new(),
+                        version: String::new(),
+                        arch: String::new(),
+                    },
+                    model_uuid: String::new(),
+                },
+                channel_index: vec![channel, 2],
+            }
+        };
+
+        let response = Response::from_stream_responses([
+            transcript(0, 0.0, false, vec![word("Hell", 0.0, 0.1, 0)]),
+            transcript(0, 0.0, true, vec![word("Hello", 0.0, 0.9, 0)]),
+            transcript(1, 0.0, true, vec![word("Hi!", 0.5, 1.0, 1)]),
+            transcript(0, 1.0, true, vec![word("there.", 1.0, 0.5, 0)]),
+            transcript(1, 1.0, true, vec![]),
+        ]);
+
+        assert_eq!(response.metadata.request_id.as_u128(), 1);
+        assert_eq!(response.metadata.duration, 2.0);
+        assert_eq!(response.metadata.channels, 2);
+
+        let first = response.best_alternative(0).unwrap();
+        assert_eq!(first.transcript, "Hello there.");
+        assert_eq!(first.words.len(), 2);
+        assert!((first.confidence - 0.7).abs() < 1e-9);
+        assert_eq!(response.best_alternative(1).unwrap().transcript, "Hi!");
+    }
+
+    #[test]
+    fn groups_speaker_turns() {
+        let turns = response().speaker_turns();
+        let turns: Vec<_> = turns
+            .iter()
+            .map(|turn| {
+                (
+                    turn.channel,
+                    turn.speaker,
+                    turn.start,
+                    turn.end,
+                    &*turn.text,
+                )
+            })
+            .collect();
+        assert_eq!(
+            turns,
+            vec![
+                (0, Some(0), 0.0, 1.0, "Hello there."),
+                (1, Some(0), 1.0, 2.0, "Hi! Again"),
+                (0, Some(1), 2.5, 3.0, "Bye."),
+            ]
+        );
+    }
+}
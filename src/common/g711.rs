@@ -0,0 +1,144 @@
+//! G.711 µ-law and A-law companding.
+//!
+//! Shared by the live audio helpers and text-to-speech decoding.
+
+const MULAW_BIAS: i32 = 0x84;
+const MULAW_CLIP: i32 = 32635;
+
+/// Encode a 16-bit sample as G.711 µ-law.
+pub fn mulaw_encode(sample: i16) -> u8 {
+    let mut magnitude = sample as i32;
+    let sign = if magnitude < 0 {
+        magnitude = -magnitude;
+        0x80
+    } else {
+        0
+    };
+    magnitude = magnitude.min(MULAW_CLIP) + MULAW_BIAS;
+
+    let mut exponent = 7;
+    let mut mask = 0x4000;
+    while exponent > 0 && magnitude & mask == 0 {
+        exponent -= 1;
+        mask >>= 1;
+    }
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+
+    !(sign | (exponent << 4) | mantissa) as u8
+}
+
+/// Decode a G.711 µ-law byte into a 16-bit sample.
+pub fn mulaw_decode(byte: u8) -> i16 {
+    let byte = !byte as i32;
+    let exponent = (byte >> 4) & 0x07;
+    let mantissa = byte & 0x0F;
+    let magnitude = (((mantissa << 3) + MULAW_BIAS) << exponent) - MULAW_BIAS;
+
+    if byte & 0x80 != 0 {
+        -magnitude as i16
+    } else {
+        magnitude as i16
+    }
+}
+
+/// Encode a 16-bit sample as G.711 A-law.
+pub fn alaw_encode(sample: i16) -> u8 {
+    const SEGMENT_END: [i32; 8] = [0x1F, 0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF];
+
+    let mut value = (sample as i32) >> 3;
+    let mask = if value >= 0 {
+        0xD5
+    } else {
+        value = -value - 1;
+        0x55
+    };
+
+    let segment = SEGMENT_END
+        .iter()
+        .position(|end| value <= *end)
+        .unwrap_or(8) as i32;
+    if segment >= 8 {
+        return (0x7F ^ mask) as u8;
+    }
+
+    let mantissa = if segment < 2 {
+        (value >> 1) & 0x0F
+    } else {
+        (value >> segment) & 0x0F
+    };
+
+    (((segment << 4) | mantissa) ^ mask) as u8
+}
+
+/// Decode a G.711 A-law byte into a 16-bit sample.
+pub fn alaw_decode(byte: u8) -> i16 {
+    let byte = (byte ^ 0x55) as i32;
+    let segment = (byte & 0x70) >> 4;
+    let mut magnitude = (byte & 0x0F) << 4;
+    match segment {
+        0 => magnitude += 8,
+        1 => magnitude += 0x108,
+        _ => magnitude = (magnitude + 0x108) << (segment - 1),
+    }
+
+    if byte & 0x80 != 0 {
+        magnitude as i16
+    } else {
+        -magnitude as i16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mulaw_round_trip() {
+        for sample in [
+            0i16,
+            1,
+            -1,
+            100,
+            -100,
+            1000,
+            -1000,
+            8000,
+            -8000,
+            i16::MAX,
+            i16::MIN,
+        ] {
+            let decoded = mulaw_decode(mulaw_encode(sample)) as i32;
+            let tolerance = (sample as i32).abs() / 16 + 8;
+            assert!(
+                (decoded - sample as i32).abs() <= tolerance,
+                "{sample} -> {decoded}"
+            );
+        }
+        assert_eq!(mulaw_encode(0), 0xFF);
+    }
+
+    #[test]
+    fn alaw_round_trip() {
+        for sample in [
+            0i16,
+            1,
+            -1,
+            100,
+            -100,
+            1000,
+            -1000,
+            8000,
+            -8000,
+            i16::MAX,
+            i16::MIN,
+        ] {
+            let decoded = alaw_decode(alaw_encode(sample)) as i32;
+            let tolerance = (sample as i32).abs() / 16 + 16;
+            assert!(
+                (decoded - sample as i32).abs() <= tolerance,
+                "{sample} -> {decoded}"
+            );
+        }
+        assert_eq!(alaw_encode(0), 0xD5);
+    }
+}
@@ -2,5 +2,8 @@
 
 pub mod audio_source;
 pub mod batch_response;
+pub mod g711;
 pub mod options;
+pub mod s3;
 pub mod stream_response;
+pub mod timestamp;
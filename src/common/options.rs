@@ -127,6 +127,8 @@ pub enum Encoding {
     Flac,
     /// Mu-law encoded WAV data
     Mulaw,
+    /// A-law encoded WAV data
+    Alaw,
     /// Adaptive Multi-Rate (AMR) narrowband codec
     AmrNb,
     /// Adaptive Multi-Rate (AMR) wideband codec
@@ -149,6 +151,7 @@ impl Encoding {
             Encoding::Linear16 => "linear16",
             Encoding::Flac => "flac",
             Encoding::Mulaw => "mulaw",
+            Encoding::Alaw => "alaw",
             Encoding::AmrNb => "amr-nb",
             Encoding::AmrWb => "amr-wb",
             Encoding::Opus => "opus",
@@ -1039,11 +1042,11 @@ impl OptionsBuilder {
     ///     .build();
     ///
     /// let request1 = dg_transcription
-    ///     .make_prerecorded_request_builder(AudioSource::from_url(AUDIO_URL), &options1)
+    ///     .make_prerecorded_request_builder(AudioSource::from_url(AUDIO_URL), &options1)?
     ///     .build()?;
     ///
     /// let request2 = dg_transcription
-    ///     .make_prerecorded_request_builder(AudioSource::from_url(AUDIO_URL), &options2)
+    ///     .make_prerecorded_request_builder(AudioSource::from_url(AUDIO_URL), &options2)?
     ///     .build()?;
     ///
     /// // Both make the same request to Deepgram with the same features
@@ -2505,7 +2508,7 @@ mod models_to_string_tests {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "listen"))]
 mod serialize_options_tests {
     use std::cmp;
     use std::collections::HashMap;
@@ -2534,6 +2537,7 @@ mod serialize_options_tests {
         let request = dg_client
             .transcription()
             .make_prerecorded_request_builder(AudioSource::from_url(""), options)
+            .unwrap()
             .build()
             .unwrap();
 
@@ -2,38 +2,19 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::batch_response::{ChannelResult, ResultAlternative};
+
 /// A single transcribed word.
 ///
-/// See the [Deepgram API Reference][api] for more info.
-///
-/// [api]: https://developers.deepgram.com/api-reference/#transcription-prerecorded
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Word {
-    #[allow(missing_docs)]
-    pub word: String,
-
-    #[allow(missing_docs)]
-    pub start: f64,
-
-    #[allow(missing_docs)]
-    pub end: f64,
-
-    #[allow(missing_docs)]
-    pub confidence: f64,
-
-    #[allow(missing_docs)]
-    pub speaker: Option<i32>,
-
-    #[allow(missing_docs)]
-    pub punctuated_word: Option<String>,
-}
+/// The same type is used for pre-recorded and live results.
+pub use super::batch_response::Word;
 
 /// Transcript alternatives.
 ///
 /// See the [Deepgram API Reference][api] for more info.
 ///
 /// [api]: https://developers.deepgram.com/api-reference/#transcription-prerecorded
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Alternatives {
     #[allow(missing_docs)]
     pub transcript: String,
@@ -52,14 +33,36 @@ pub struct Alternatives {
 ///
 /// [api]: https://developers.deepgram.com/api-reference/#transcription-prerecorded
 /// [docs]: https://developers.deepgram.com/documentation/features/multichannel/
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Channel {
     #[allow(missing_docs)]
     pub alternatives: Vec<Alternatives>,
 }
 
+impl From<Alternatives> for ResultAlternative {
+    fn from(alternative: Alternatives) -> Self {
+        ResultAlternative {
+            transcript: alternative.transcript,
+            confidence: alternative.confidence,
+            words: alternative.words,
+            paragraphs: None,
+            entities: None,
+        }
+    }
+}
+
+impl From<Channel> for ChannelResult {
+    fn from(channel: Channel) -> Self {
+        ChannelResult {
+            search: None,
+            alternatives: channel.alternatives.into_iter().map(Into::into).collect(),
+            detected_language: None,
+        }
+    }
+}
+
 /// Modle info
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     #[allow(missing_docs)]
     pub name: String,
@@ -76,7 +79,7 @@ pub struct ModelInfo {
 /// See the [Deepgram API Reference][api] for more info.
 ///
 /// [api]: https://developers.deepgram.com/api-reference/#transcription-prerecorded
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Metadata {
     #[allow(missing_docs)]
     pub request_id: String,
@@ -89,7 +92,7 @@ pub struct Metadata {
 }
 
 /// Possible websocket message types
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 #[non_exhaustive]
 pub enum StreamResponse {
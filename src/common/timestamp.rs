@@ -0,0 +1,401 @@
+//! Timestamps sent to and returned by the Deepgram API.
+//!
+//! See [`Timestamp`].
+
+use std::{
+    fmt,
+    ops::Deref,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::DeepgramError;
+
+/// A date and time, such as the creation date of an API key or the start of
+/// a usage query.
+///
+/// A `Timestamp` keeps the text Deepgram sent, so nothing is lost if it is
+/// not valid [RFC 3339][rfc], and derefs to [`str`]. Use
+/// [`Timestamp::to_system_time`] to parse it, or `to_chrono` and
+/// `to_offset_date_time` with the `chrono` and `time` features.
+///
+/// Timestamps can be parsed from strings with [`Timestamp::parse`], which
+/// checks them and sends them as written, or converted from a
+/// [`SystemTime`], [`chrono::DateTime`][chrono] or
+/// [`time::OffsetDateTime`][time], which are formatted as RFC 3339 in UTC.
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// use deepgram::common::timestamp::Timestamp;
+///
+/// let timestamp = Timestamp::from(UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+/// assert_eq!(timestamp.as_str(), "2023-11-14T22:13:20Z");
+/// assert_eq!(
+///     Timestamp::parse("2023-11-14T23:13:20+01:00")?.to_system_time(),
+///     timestamp.to_system_time(),
+/// );
+/// assert!(Timestamp::parse("yesterday").is_err());
+/// # Ok::<(), deepgram::DeepgramError>(())
+/// ```
+///
+/// [rfc]: https://www.rfc-editor.org/rfc/rfc3339
+/// [chrono]: https://docs.rs/chrono/latest/chrono/struct.DateTime.html
+/// [time]: https://docs.rs/time/latest/time/struct.OffsetDateTime.html
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Timestamp(String);
+
+impl Timestamp {
+    /// Check that `timestamp` is [RFC 3339][rfc] or a bare `YYYY-MM-DD`
+    /// date, keeping it as written.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`DeepgramError::InvalidTimestamp`] if it is neither.
+    ///
+    /// [rfc]: https://www.rfc-editor.org/rfc/rfc3339
+    pub fn parse(timestamp: &str) -> crate::Result<Self> {
+        match parse_rfc3339(timestamp) {
+            Some(_) => Ok(Self(timestamp.to_owned())),
+            None => Err(DeepgramError::InvalidTimestamp(timestamp.to_owned())),
+        }
+    }
+
+    /// A timestamp that is not checked, for text Deepgram sent or left out.
+    pub(crate) fn from_raw(timestamp: String) -> Self {
+        Self(timestamp)
+    }
+
+    /// The timestamp as it was sent or received.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Parse the timestamp, or [`None`] if it is not RFC 3339 or a bare
+    /// `YYYY-MM-DD` date, which is taken as midnight UTC.
+    pub fn to_system_time(&self) -> Option<SystemTime> {
+        parse_rfc3339(&self.0)
+    }
+
+    /// Parse the timestamp as a [`chrono::DateTime`] in UTC.
+    ///
+    /// See [`Timestamp::to_system_time`].
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.to_system_time().map(Into::into)
+    }
+
+    /// Parse the timestamp as a [`time::OffsetDateTime`] in UTC.
+    ///
+    /// See [`Timestamp::to_system_time`].
+    #[cfg(feature = "time")]
+    pub fn to_offset_date_time(&self) -> Option<time::OffsetDateTime> {
+        self.to_system_time().map(Into::into)
+    }
+}
+
+impl Deref for Timestamp {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = DeepgramError;
+
+    /// See [`Timestamp::parse`].
+    fn from_str(timestamp: &str) -> crate::Result<Self> {
+        Self::parse(timestamp)
+    }
+}
+
+impl From<Timestamp> for String {
+    fn from(timestamp: Timestamp) -> Self {
+        timestamp.0
+    }
+}
+
+impl From<SystemTime> for Timestamp {
+    /// Format `time` as RFC 3339 in UTC, to the millisecond.
+    ///
+    /// RFC 3339 only covers the years 0000 to 9999, so times outside them
+    /// are clamped to that range.
+    fn from(time: SystemTime) -> Self {
+        let civil = Civil::from(time);
+        let timestamp = match civil.year {
+            ..=-1 => "0000-01-01T00:00:00Z".to_string(),
+            10000.. => "9999-12-31T23:59:59.999Z".to_string(),
+            _ => {
+                let mut timestamp = format!(
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                    civil.year, civil.month, civil.day, civil.hour, civil.minute, civil.second
+                );
+                let millis = civil.nanos / 1_000_000;
+                if millis != 0 {
+                    timestamp.push_str(&format!(".{millis:03}"));
+                }
+                timestamp.push('Z');
+                timestamp
+            }
+        };
+        Self(timestamp)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> From<chrono::DateTime<Tz>> for Timestamp {
+    fn from(time: chrono::DateTime<Tz>) -> Self {
+        SystemTime::from(time).into()
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<time::OffsetDateTime> for Timestamp {
+    fn from(time: time::OffsetDateTime) -> Self {
+        SystemTime::from(time).into()
+    }
+}
+
+/// A UTC date and time broken into its fields.
+pub(crate) struct Civil {
+    pub(crate) year: i64,
+    pub(crate) month: i64,
+    pub(crate) day: i64,
+    pub(crate) hour: i64,
+    pub(crate) minute: i64,
+    pub(crate) second: i64,
+    pub(crate) nanos: u32,
+}
+
+impl From<SystemTime> for Civil {
+    fn from(time: SystemTime) -> Self {
+        // Whole seconds since the epoch, rounded down, and the nanoseconds
+        // after them.
+        let (seconds, nanos) = match time.duration_since(UNIX_EPOCH) {
+            Ok(after) => (
+                i64::try_from(after.as_secs()).unwrap_or(i64::MAX),
+                after.subsec_nanos(),
+            ),
+            Err(before) => {
+                let before = before.duration();
+                let seconds = i64::try_from(before.as_secs()).unwrap_or(i64::MAX);
+                match before.subsec_nanos() {
+                    0 => (-seconds, 0),
+                    nanos => (-seconds - 1, 1_000_000_000 - nanos),
+                }
+            }
+        };
+        let (days, seconds) = (seconds.div_euclid(86400), seconds.rem_euclid(86400));
+
+        // Convert days since the epoch to a civil date.
+        // See http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+
+        Civil {
+            year: year_of_era + era * 400 + i64::from(month <= 2),
+            month,
+            day,
+            hour: seconds / 3600,
+            minute: seconds / 60 % 60,
+            second: seconds % 60,
+            nanos,
+        }
+    }
+}
+
+/// Parse an [RFC 3339][rfc] timestamp, such as `2024-01-31T12:00:00.000Z`,
+/// or a bare date such as `2024-01-31`, which is taken as midnight UTC.
+///
+/// [rfc]: https://www.rfc-editor.org/rfc/rfc3339
+fn parse_rfc3339(timestamp: &str) -> Option<SystemTime> {
+    let timestamp = timestamp.trim();
+    let (date, time) = match timestamp.find(['T', 't', ' ']) {
+        Some(index) => (&timestamp[..index], Some(&timestamp[index + 1..])),
+        None => (timestamp, None),
+    };
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = number(date_parts.next()?, 4)?;
+    let month: i64 = number(date_parts.next()?, 2)?;
+    let day: i64 = number(date_parts.next()?, 2)?;
+    if !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    let mut seconds = days_from_civil(year, month, day) * 86400;
+    let mut nanos = 0;
+
+    if let Some(time) = time {
+        let (time, offset) = match time.find(['Z', 'z', '+', '-']) {
+            Some(index) => time.split_at(index),
+            None => return None,
+        };
+
+        let (time, fraction) = match time.split_once('.') {
+            Some((time, fraction)) => (time, Some(fraction)),
+            None => (time, None),
+        };
+        let mut time_parts = time.splitn(3, ':');
+        let hour: i64 = number(time_parts.next()?, 2)?;
+        let minute: i64 = number(time_parts.next()?, 2)?;
+        let second: i64 = number(time_parts.next()?, 2)?;
+        if hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+        seconds += hour * 3600 + minute * 60 + second;
+
+        if let Some(fraction) = fraction {
+            if fraction.is_empty() || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+                return None;
+            }
+            let digits = &fraction[..fraction.len().min(9)];
+            nanos = digits.parse::<u32>().ok()? * 10_u32.pow(9 - digits.len() as u32);
+        }
+
+        match offset {
+            "Z" | "z" => {}
+            _ => {
+                let sign = if offset.starts_with('-') { -1 } else { 1 };
+                let (hours, minutes) = offset[1..].split_once(':')?;
+                let hours: i64 = number(hours, 2)?;
+                let minutes: i64 = number(minutes, 2)?;
+                seconds -= sign * (hours * 3600 + minutes * 60);
+            }
+        }
+    }
+
+    let whole_seconds = Duration::from_secs(seconds.unsigned_abs());
+    let time = if seconds < 0 {
+        UNIX_EPOCH.checked_sub(whole_seconds)?
+    } else {
+        UNIX_EPOCH.checked_add(whole_seconds)?
+    };
+    time.checked_add(Duration::from_nanos(nanos.into()))
+}
+
+fn number(digits: &str, len: usize) -> Option<i64> {
+    if digits.len() != len || !digits.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since the Unix epoch of a civil date.
+/// See http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let shifted_month = (month + 9) % 12;
+    let day_of_year = (153 * shifted_month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{parse_rfc3339 as parse, Timestamp};
+
+    #[test]
+    fn parses_rfc3339() {
+        let at = |seconds: u64, nanos: u32| Some(UNIX_EPOCH + Duration::new(seconds, nanos));
+
+        assert_eq!(parse("1970-01-01T00:00:00Z"), at(0, 0));
+        assert_eq!(
+            parse("2013-05-24T00:00:00.5Z"),
+            at(1_369_353_600, 500_000_000)
+        );
+        assert_eq!(
+            parse("2024-02-29T23:59:59.123456Z"),
+            at(1_709_251_199, 123_456_000)
+        );
+        assert_eq!(parse("2024-03-01T01:59:59+02:00"), at(1_709_251_199, 0));
+        assert_eq!(parse("2038-01-19"), at(2_147_472_000, 0));
+
+        assert_eq!(parse("2023-02-29"), None);
+        assert_eq!(parse("2024-01-01T00:00:00"), None);
+        assert_eq!(parse("yesterday"), None);
+    }
+
+    #[test]
+    fn formats_system_time() {
+        let time = UNIX_EPOCH + Duration::new(1_709_251_199, 123_456_789);
+        let timestamp = Timestamp::from(time);
+        assert_eq!(timestamp.as_str(), "2024-02-29T23:59:59.123Z");
+        assert_eq!(
+            timestamp.to_system_time(),
+            Some(UNIX_EPOCH + Duration::new(1_709_251_199, 123_000_000))
+        );
+
+        // Times before the epoch are formatted, not clamped to it.
+        let time = UNIX_EPOCH - Duration::new(86_400 * 365, 500_000_000);
+        let timestamp = Timestamp::from(time);
+        assert_eq!(timestamp.as_str(), "1968-12-31T23:59:59.500Z");
+        assert_eq!(timestamp.to_system_time(), Some(time));
+
+        // Strings received that don't parse are kept as-is, but can't be
+        // constructed.
+        let timestamp: Timestamp = serde_json::from_str(r#""not a date""#).unwrap();
+        assert_eq!(&*timestamp, "not a date");
+        assert_eq!(timestamp.to_system_time(), None);
+        assert!(Timestamp::parse("not a date").is_err());
+        assert!("2024-01-31".parse::<Timestamp>().is_ok());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn converts_chrono() {
+        use chrono::{FixedOffset, TimeZone};
+
+        let time = FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 3, 1, 0, 59, 59)
+            .unwrap();
+        let timestamp = Timestamp::from(time);
+        assert_eq!(timestamp.as_str(), "2024-02-29T23:59:59Z");
+        assert_eq!(timestamp.to_chrono(), Some(time.into()));
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn converts_time() {
+        let time = time::OffsetDateTime::from_unix_timestamp(1_709_251_199).unwrap();
+        let timestamp = Timestamp::from(time);
+        assert_eq!(timestamp.as_str(), "2024-02-29T23:59:59Z");
+        assert_eq!(timestamp.to_offset_date_time(), Some(time));
+    }
+}
@@ -0,0 +1,331 @@
+//! Failover between the endpoints of a [`Deepgram`](crate::Deepgram) client.
+//!
+//! Requests are built against the primary base URL and sent to the
+//! endpoints the client's [`RoutingPolicy`] allows, as described in the
+//! [`routing`](crate::routing) module.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use reqwest::{Client, Request, Response};
+use url::Url;
+
+use crate::{
+    instrumentation,
+    routing::{Endpoint, RoutingPolicy},
+    DeepgramError,
+};
+
+/// How long an endpoint is tried last after it fails.
+const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Whether [`Endpoints::execute`] retries a request that got a server error.
+///
+/// Requests that failed to connect were never sent, so are always retried.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum RetryServerErrors {
+    /// Retry every request.
+    Always,
+
+    /// Only retry idempotent requests, since a server error does not mean
+    /// the request was not applied.
+    #[cfg_attr(not(feature = "manage"), allow(dead_code))]
+    IfIdempotent,
+}
+
+/// The endpoints of a client and their health, shared between its clones.
+#[derive(Debug)]
+pub(crate) struct Endpoints {
+    endpoints: Vec<Endpoint>,
+    unhealthy_until: Mutex<Vec<Option<Instant>>>,
+    next: AtomicUsize,
+}
+
+impl Endpoints {
+    /// `endpoints` must not be empty.
+    pub(crate) fn new(endpoints: Vec<Endpoint>) -> Self {
+        assert!(!endpoints.is_empty(), "at least one endpoint is required");
+        Self {
+            unhealthy_until: Mutex::new(vec![None; endpoints.len()]),
+            endpoints,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Construct [`Endpoints`] named after the host of each base URL.
+    pub(crate) fn from_urls(urls: Vec<Url>) -> Self {
+        Self::new(urls.into_iter().map(Endpoint::from_url).collect())
+    }
+
+    pub(crate) fn urls(&self) -> impl Iterator<Item = &Url> {
+        self.endpoints.iter().map(Endpoint::url)
+    }
+
+    pub(crate) fn url(&self, index: usize) -> &Url {
+        self.endpoints[index].url()
+    }
+
+    /// The primary base URL, which requests are built against.
+    pub(crate) fn primary(&self) -> &Url {
+        self.url(0)
+    }
+
+    /// Record whether the endpoint at `url` is healthy.
+    pub(crate) fn mark(&self, url: &Url, healthy: bool) {
+        if let Some(index) = self.urls().position(|candidate| candidate == url) {
+            self.mark_index(index, healthy);
+        }
+    }
+
+    pub(crate) fn mark_index(&self, index: usize, healthy: bool) {
+        let mut unhealthy_until = self.unhealthy_until.lock().unwrap();
+        unhealthy_until[index] = if healthy {
+            None
+        } else {
+            Some(Instant::now() + UNHEALTHY_COOLDOWN)
+        };
+    }
+
+    /// Indices of the endpoints `policy` allows, in the order they should be
+    /// tried. Unhealthy endpoints come last, soonest to recover first.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`DeepgramError::NoRoutableEndpoint`] if the policy allows
+    /// no endpoints.
+    pub(crate) fn order(&self, policy: &RoutingPolicy) -> crate::Result<Vec<usize>> {
+        let mut order: Vec<usize> = match policy {
+            RoutingPolicy::Region(region) => (0..self.endpoints.len())
+                .filter(|&index| self.endpoints[index].region() == Some(region.as_str()))
+                .collect(),
+            RoutingPolicy::Priority | RoutingPolicy::RoundRobin => {
+                (0..self.endpoints.len()).collect()
+            }
+        };
+        if order.is_empty() {
+            return Err(DeepgramError::NoRoutableEndpoint(policy.clone()));
+        }
+
+        if *policy == RoutingPolicy::RoundRobin {
+            let start = self.next.fetch_add(1, Ordering::Relaxed) % order.len();
+            order.rotate_left(start);
+        }
+
+        let now = Instant::now();
+        let unhealthy_until = self.unhealthy_until.lock().unwrap();
+        order.sort_by_key(|&index| unhealthy_until[index].filter(|until| *until > now));
+        Ok(order)
+    }
+
+    /// Execute `request`, failing over between the endpoints `policy`
+    /// allows on connection errors and, depending on `retry_server_errors`,
+    /// server errors.
+    ///
+    /// Requests that time out are not retried, since they may have been
+    /// applied. Requests that are not for the primary base URL are sent as
+    /// they are. Requests whose body is a stream cannot be sent twice, so are
+    /// only sent to the first endpoint.
+    pub(crate) async fn execute(
+        &self,
+        policy: &RoutingPolicy,
+        client: &Client,
+        request: Request,
+        retry_server_errors: RetryServerErrors,
+    ) -> crate::Result<Response> {
+        let Some(path) = relative_path(request.url(), self.primary()) else {
+            return Ok(client.execute(request).await?);
+        };
+        let retry_server_errors = match retry_server_errors {
+            RetryServerErrors::Always => true,
+            RetryServerErrors::IfIdempotent => request.method().is_idempotent(),
+        };
+
+        let order = self.order(policy)?;
+        let mut request = Some(request);
+        for (attempt, &index) in order.iter().enumerate() {
+            let retry = attempt + 1 < order.len();
+            let (mut next, retry) = match request.as_ref().and_then(Request::try_clone) {
+                Some(next) if retry => (next, true),
+                _ => (request.take().expect("request is only taken once"), false),
+            };
+            *next.url_mut() = self
+                .url(index)
+                .join(&path)
+                .map_err(|_| DeepgramError::InvalidUrl)?;
+
+            match client.execute(next).await {
+                Ok(response) => {
+                    let failed = response.status().is_server_error();
+                    self.mark_index(index, !failed);
+                    if !(failed && retry && retry_server_errors) {
+                        return Ok(response);
+                    }
+                    instrumentation::event!(
+                        WARN,
+                        endpoint = self.endpoints[index].name(),
+                        status = response.status().as_u16(),
+                        "endpoint failed, trying the next one"
+                    );
+                }
+                Err(err) if err.is_connect() => {
+                    self.mark_index(index, false);
+                    if !retry {
+                        return Err(err.into());
+                    }
+                    instrumentation::event!(
+                        WARN,
+                        endpoint = self.endpoints[index].name(),
+                        error = %err,
+                        "endpoint failed, trying the next one"
+                    );
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        unreachable!("the last attempt always returns")
+    }
+}
+
+/// The part of `url` after the directory of `base`, which is what was
+/// joined onto `base` to make it.
+fn relative_path(url: &Url, base: &Url) -> Option<String> {
+    let directory = base.join("./").ok()?;
+    url.as_str()
+        .strip_prefix(directory.as_str())
+        .map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use super::{relative_path, Endpoints, RetryServerErrors};
+    use crate::{
+        routing::{Endpoint, RoutingPolicy},
+        test_server::serve,
+        DeepgramError,
+    };
+
+    #[test]
+    fn finds_relative_paths() {
+        let base = Url::parse("http://localhost:8080/deepgram/").unwrap();
+        let url = base.join("v1/listen?model=nova-2").unwrap();
+        assert_eq!(
+            relative_path(&url, &base).as_deref(),
+            Some("v1/listen?model=nova-2")
+        );
+
+        let base = Url::parse("https://api.deepgram.com").unwrap();
+        let manage = Url::parse("https://api.eu.deepgram.com/v1/projects").unwrap();
+        assert_eq!(relative_path(&manage, &base), None);
+    }
+
+    #[test]
+    fn orders_by_policy() {
+        let endpoints = Endpoints::new(vec![
+            Endpoint::new("us", "https://api.deepgram.com")
+                .unwrap()
+                .in_region("us"),
+            Endpoint::new("eu", "https://api.eu.deepgram.com")
+                .unwrap()
+                .in_region("eu"),
+            Endpoint::new("onprem", "http://deepgram.internal:8080")
+                .unwrap()
+                .in_region("eu"),
+        ]);
+
+        let eu = RoutingPolicy::Region("eu".to_owned());
+        assert_eq!(endpoints.order(&eu).unwrap(), vec![1, 2]);
+        endpoints.mark_index(1, false);
+        assert_eq!(endpoints.order(&eu).unwrap(), vec![2, 1]);
+        assert!(matches!(
+            endpoints.order(&RoutingPolicy::Region("ap".to_owned())),
+            Err(DeepgramError::NoRoutableEndpoint(_))
+        ));
+
+        endpoints.mark_index(1, true);
+        let starts: Vec<usize> = (0..4)
+            .map(|_| endpoints.order(&RoutingPolicy::RoundRobin).unwrap()[0])
+            .collect();
+        assert_eq!(starts, vec![0, 1, 2, 0]);
+    }
+
+    #[tokio::test]
+    async fn fails_over() {
+        // Nothing listens on port 1 of the loopback address.
+        let down = Url::parse("http://127.0.0.1:1/").unwrap();
+        let failing = serve("503 Service Unavailable", "").await;
+        let healthy = serve("200 OK", "").await;
+        let endpoints = Endpoints::from_urls(vec![down.clone(), failing, healthy.clone()]);
+        let client = reqwest::Client::new();
+
+        let request = client
+            .post(down.join("v1/listen").unwrap())
+            .body("audio")
+            .build()
+            .unwrap();
+        let response = endpoints
+            .execute(
+                &RoutingPolicy::Priority,
+                &client,
+                request,
+                RetryServerErrors::Always,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.url(), &healthy.join("v1/listen").unwrap());
+
+        // The failed endpoints are now tried last.
+        assert_eq!(
+            endpoints.order(&RoutingPolicy::Priority).unwrap(),
+            vec![2, 0, 1]
+        );
+
+        endpoints.mark(&down, true);
+        assert_eq!(
+            endpoints.order(&RoutingPolicy::Priority).unwrap(),
+            vec![0, 2, 1]
+        );
+    }
+
+    #[tokio::test]
+    async fn only_retries_idempotent_requests_after_server_errors() {
+        let failing = serve("503 Service Unavailable", "").await;
+        let healthy = serve("200 OK", "").await;
+        let endpoints = Endpoints::from_urls(vec![failing.clone(), healthy.clone()]);
+        let client = reqwest::Client::new();
+        let url = failing.join("v1/projects/abc/keys").unwrap();
+
+        let create = client.post(url.clone()).build().unwrap();
+        let response = endpoints
+            .execute(
+                &RoutingPolicy::Priority,
+                &client,
+                create,
+                RetryServerErrors::IfIdempotent,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 503);
+
+        endpoints.mark(&failing, true);
+        let list = client.get(url).build().unwrap();
+        let response = endpoints
+            .execute(
+                &RoutingPolicy::Priority,
+                &client,
+                list,
+                RetryServerErrors::IfIdempotent,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+}
@@ -0,0 +1,170 @@
+//! Optional [`tracing`](https://docs.rs/tracing) instrumentation.
+//!
+//! With the `tracing` feature enabled, requests and websocket sessions are
+//! wrapped in spans and notable events are emitted inside them. Without it,
+//! everything in this module compiles down to nothing and the library stays silent.
+
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::{Instrument, Span};
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use self::noop::{Instrument, Span};
+
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($($arg:tt)*) => {
+        ::tracing::info_span!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($($arg:tt)*) => {
+        $crate::instrumentation::Span::none()
+    };
+}
+
+#[cfg(feature = "tracing")]
+macro_rules! event {
+    ($level:ident, $($arg:tt)*) => {
+        ::tracing::event!(::tracing::Level::$level, $($arg)*)
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! event {
+    ($level:ident, $($arg:tt)*) => {{}};
+}
+
+pub(crate) use event;
+
+/// Create the span for a single REST request.
+pub(crate) fn request_span(request: &reqwest::Request) -> Span {
+    let model = model(request.url());
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = model;
+
+    span!(
+        "deepgram.request",
+        method = %request.method(),
+        endpoint = request.url().path(),
+        model = model.as_deref(),
+        request_id = ::tracing::field::Empty,
+    )
+}
+
+/// Create the span for a websocket session, covering the handshake and every
+/// message sent or received afterwards.
+pub(crate) fn session_span(url: &url::Url) -> Span {
+    let model = model(url);
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = model;
+
+    span!(
+        "deepgram.session",
+        endpoint = url.path(),
+        model = model.as_deref(),
+        request_id = ::tracing::field::Empty,
+    )
+}
+
+fn model(url: &url::Url) -> Option<String> {
+    url.query_pairs()
+        .find(|(key, _)| key == "model")
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Record the response status and request ID on the current request span.
+pub(crate) fn record_response(response: &reqwest::Response, started: tokio::time::Instant) {
+    let span = Span::current();
+    if let Some(request_id) = response
+        .headers()
+        .get("dg-request-id")
+        .and_then(|value| value.to_str().ok())
+    {
+        span.record("request_id", request_id);
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    let _ = started;
+
+    event!(
+        DEBUG,
+        status = response.status().as_u16(),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "response headers received"
+    );
+}
+
+/// Record the time elapsed since `started`, such as time to first byte.
+pub(crate) fn record_latency(started: tokio::time::Instant, what: &'static str) {
+    #[cfg(not(feature = "tracing"))]
+    let _ = (started, what);
+
+    event!(
+        DEBUG,
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "{}",
+        what
+    );
+}
+
+/// Record an error that is about to be returned to the caller.
+pub(crate) fn record_error(error: &dyn std::error::Error, what: &'static str) {
+    #[cfg(not(feature = "tracing"))]
+    let _ = (error, what);
+
+    event!(WARN, error = %error, "{}", what);
+}
+
+/// Record a close frame received from the server. Abnormal closes are warnings.
+pub(crate) fn record_close(code: u16, reason: &str) {
+    #[cfg(not(feature = "tracing"))]
+    let _ = (code, reason);
+
+    // 1000 is a normal closure.
+    #[cfg(feature = "tracing")]
+    if code == 1000 {
+        event!(INFO, code, reason, "websocket closed by server");
+    } else {
+        event!(WARN, code, reason, "websocket closed by server");
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+mod noop {
+    use std::fmt;
+
+    /// Stand-in for [`tracing::Span`].
+    #[derive(Debug, Clone)]
+    pub(crate) struct Span;
+
+    impl Span {
+        pub(crate) fn none() -> Self {
+            Span
+        }
+
+        pub(crate) fn current() -> Self {
+            Span
+        }
+
+        pub(crate) fn record<V: fmt::Debug>(&self, _field: &str, _value: V) -> &Self {
+            self
+        }
+
+        pub(crate) fn in_scope<F: FnOnce() -> T, T>(&self, f: F) -> T {
+            f()
+        }
+    }
+
+    /// Stand-in for [`tracing::Instrument`].
+    pub(crate) trait Instrument: Sized {
+        fn instrument(self, _span: Span) -> Self {
+            self
+        }
+    }
+
+    impl<T> Instrument for T {}
+}
@@ -9,6 +9,7 @@
 use core::fmt;
 use std::io;
 use std::ops::Deref;
+use std::sync::Arc;
 
 use reqwest::{
     header::{HeaderMap, HeaderValue},
@@ -18,14 +19,31 @@ use serde::de::DeserializeOwned;
 use thiserror::Error;
 use url::Url;
 
-#[cfg(feature = "listen")]
+use crate::{
+    endpoints::{Endpoints, RetryServerErrors},
+    instrumentation::Instrument,
+    routing::{Router, RoutingPolicy},
+};
+
+#[cfg(any(feature = "listen", feature = "speak", feature = "manage"))]
 pub mod common;
+#[cfg_attr(not(any(feature = "listen", feature = "speak")), allow(dead_code))]
+mod endpoints;
+// Some helpers are only used by the listen and speak APIs.
+#[cfg_attr(not(all(feature = "listen", feature = "speak")), allow(dead_code))]
+mod instrumentation;
 #[cfg(feature = "listen")]
 pub mod listen;
 #[cfg(feature = "manage")]
 pub mod manage;
+#[cfg_attr(not(any(feature = "listen", feature = "speak")), allow(dead_code))]
+pub mod routing;
+#[cfg(any(feature = "listen", feature = "speak"))]
+pub mod self_hosted;
 #[cfg(feature = "speak")]
 pub mod speak;
+#[cfg(test)]
+mod test_server;
 
 static DEEPGRAM_BASE_URL: &str = "https://api.deepgram.com";
 
@@ -99,15 +117,57 @@ impl Deref for RedactedString {
     }
 }
 
+/// The secret a client authenticates with.
+#[derive(Debug, Clone)]
+pub(crate) struct Credentials {
+    secret: RedactedString,
+    temporary: bool,
+}
+
+impl Credentials {
+    fn api_key(api_key: &str) -> Self {
+        Self {
+            secret: RedactedString(api_key.to_owned()),
+            temporary: false,
+        }
+    }
+
+    fn temporary_token(token: &str) -> Self {
+        Self {
+            secret: RedactedString(token.to_owned()),
+            temporary: true,
+        }
+    }
+
+    /// Whether this is a temporary token rather than an API key.
+    #[cfg(feature = "manage")]
+    pub(crate) fn is_temporary_token(&self) -> bool {
+        self.temporary
+    }
+
+    /// The value of the `Authorization` header for these credentials.
+    ///
+    /// Temporary tokens use the `Bearer` scheme and API keys use `Token`.
+    pub(crate) fn authorization(&self) -> String {
+        if self.temporary {
+            format!("Bearer {}", &*self.secret)
+        } else {
+            format!("Token {}", &*self.secret)
+        }
+    }
+}
+
 /// A client for the Deepgram API.
 ///
 /// Make transcriptions requests using [`Deepgram::transcription`].
 #[derive(Debug, Clone)]
 pub struct Deepgram {
     #[cfg_attr(not(feature = "listen"), allow(unused))]
-    api_key: Option<RedactedString>,
-    #[cfg_attr(not(feature = "listen"), allow(unused))]
-    base_url: Url,
+    api_key: Option<Credentials>,
+    #[cfg_attr(not(any(feature = "listen", feature = "speak")), allow(unused))]
+    endpoints: Arc<Endpoints>,
+    #[cfg_attr(not(any(feature = "listen", feature = "speak")), allow(unused))]
+    policy: RoutingPolicy,
     #[cfg_attr(not(feature = "listen"), allow(unused))]
     client: reqwest::Client,
 }
@@ -117,6 +177,15 @@ pub struct Deepgram {
 #[derive(Debug, Error)]
 #[non_exhaustive]
 pub enum DeepgramError {
+    /// The routing policy does not allow any of the client's endpoints.
+    #[error("no endpoint is allowed by the routing policy {0:?}")]
+    NoRoutableEndpoint(RoutingPolicy),
+
+    /// A client or [`Router`] was constructed without any
+    /// endpoints.
+    #[error("at least one endpoint is required")]
+    NoEndpoints,
+
     /// The Deepgram API returned an error.
     #[error("The Deepgram API returned an error.")]
     DeepgramApiError {
@@ -142,7 +211,7 @@ pub enum DeepgramError {
     #[cfg(feature = "listen")]
     /// Something went wrong with WS.
     #[error("Something went wrong with WS: {0}")]
-    WsError(#[from] tungstenite::Error),
+    WsError(Box<tungstenite::Error>),
 
     /// Something went wrong during serialization/deserialization.
     #[error("Something went wrong during json serialization/deserialization: {0}")]
@@ -169,15 +238,80 @@ pub enum DeepgramError {
         reason: String,
     },
 
+    /// The websocket audio buffer was full.
+    #[error("The websocket audio buffer is full")]
+    AudioBufferFull,
+
+    /// No message was received from Deepgram within the websocket idle timeout.
+    #[error("no message received from the server within {0:?}")]
+    IdleTimeout(std::time::Duration),
+
+    /// No pong was received from Deepgram within the websocket ping timeout.
+    #[error("no pong received from the server within {0:?}")]
+    PongTimeout(std::time::Duration),
+
+    /// A builder option that must be positive was set to zero.
+    #[error("{0} must not be zero")]
+    ZeroOption(&'static str),
+
+    /// A pre-signed URL was requested with an expiry outside of what is allowed.
+    #[error("pre-signed URL expiry must be between 1 second and 7 days, got {0:?}")]
+    InvalidPresignExpiry(std::time::Duration),
+
+    /// A timestamp was not valid RFC 3339 or a `YYYY-MM-DD` date.
+    #[error("invalid timestamp, expected RFC 3339 or YYYY-MM-DD: {0:?}")]
+    InvalidTimestamp(String),
+
+    /// The sink given to [`Keys::rotate`](crate::manage::keys::Keys::rotate)
+    /// failed to store the new API key.
+    #[error("The new API key could not be stored: {0}")]
+    KeySinkError(Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// [`Keys::rotate`](crate::manage::keys::Keys::rotate) created the new
+    /// key but could not delete the old one, so both are still valid.
+    #[cfg(feature = "manage")]
+    #[error("the old API key could not be deleted after rotating it: {source}")]
+    KeyRotationIncomplete {
+        /// The old key, which is still valid, and the ID of the new key
+        rotated: Box<manage::keys::response::RotatedKey>,
+        /// The error from deleting the old key
+        source: Box<DeepgramError>,
+    },
+
+    /// A change made by
+    /// [`Reconcile::apply`](crate::manage::reconcile::Reconcile::apply)
+    /// failed, so later changes were not applied.
+    #[cfg(feature = "manage")]
+    #[error("reconciling stopped after {} changes: {source}", applied.changes.len())]
+    PartiallyApplied {
+        /// The changes that were made and the secrets of any keys that were
+        /// created, including a replacement key whose old key could not be
+        /// deleted
+        applied: Box<manage::reconcile::plan::Applied>,
+        /// The error from the change that failed
+        source: Box<DeepgramError>,
+    },
+
     /// An unexpected error occurred in the client
     #[error("an unepected error occurred in the deepgram client: {0}")]
     InternalClientError(anyhow::Error),
 
+    /// Audio could not be converted between the requested formats.
+    #[error("Unsupported audio format: {0}")]
+    UnsupportedAudioFormat(String),
+
     /// A Deepgram API server response was not in the expected format.
     #[error("The Deepgram API server response was not in the expected format: {0}")]
     UnexpectedServerResponse(anyhow::Error),
 }
 
+#[cfg(feature = "listen")]
+impl From<tungstenite::Error> for DeepgramError {
+    fn from(err: tungstenite::Error) -> Self {
+        Self::WsError(Box::new(err))
+    }
+}
+
 #[cfg_attr(not(feature = "listen"), allow(unused))]
 type Result<T, E = DeepgramError> = std::result::Result<T, E>;
 
@@ -186,7 +320,9 @@ impl Deepgram {
     ///
     /// The client will be pointed at Deepgram's hosted API.
     ///
-    /// Create your first API key on the [Deepgram Console][console].
+    /// Create your first API key on the [Deepgram Console][console]. Use
+    /// [`Deepgram::with_temporary_token`] to authenticate with a temporary
+    /// token instead.
     ///
     /// [console]: https://console.deepgram.com/
     ///
@@ -194,11 +330,27 @@ impl Deepgram {
     ///
     /// Errors under the same conditions as [`reqwest::ClientBuilder::build`].
     pub fn new<K: AsRef<str>>(api_key: K) -> Result<Self> {
-        let api_key = Some(api_key.as_ref().to_owned());
+        let api_key = Some(Credentials::api_key(api_key.as_ref()));
         // This cannot panic because we are converting a static value
         // that is known-good.
         let base_url = DEEPGRAM_BASE_URL.try_into().unwrap();
-        Self::inner_constructor(base_url, api_key)
+        Self::inner_constructor(vec![base_url], api_key)
+    }
+
+    /// Construct a new Deepgram client that authenticates with a temporary
+    /// token, sent with the `Bearer` scheme, rather than an API key.
+    ///
+    /// The client will be pointed at Deepgram's hosted API.
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as [`reqwest::ClientBuilder::build`].
+    pub fn with_temporary_token<T: AsRef<str>>(token: T) -> Result<Self> {
+        let token = Some(Credentials::temporary_token(token.as_ref()));
+        // This cannot panic because we are converting a static value
+        // that is known-good.
+        let base_url = DEEPGRAM_BASE_URL.try_into().unwrap();
+        Self::inner_constructor(vec![base_url], token)
     }
 
     /// Construct a new Deepgram client with the specified base URL.
@@ -208,8 +360,8 @@ impl Deepgram {
     /// query your deepgram instance at `http://deepgram.internal/v1/listen`,
     /// the base_url will be `http://deepgram.internal`.
     ///
-    /// Admin features, such as billing, usage, and key management will
-    /// still go through the hosted site at `https://api.deepgram.com`.
+    /// Admin features, such as billing, usage, and key management, are sent
+    /// to this base URL too.
     ///
     /// Self-hosted instances do not in general authenticate incoming
     /// requests, so unlike in [`Deepgram::new`], so no api key needs to be
@@ -238,7 +390,7 @@ impl Deepgram {
         U::Error: std::fmt::Debug,
     {
         let base_url = base_url.try_into().map_err(|_| DeepgramError::InvalidUrl)?;
-        Self::inner_constructor(base_url, None)
+        Self::inner_constructor(vec![base_url], None)
     }
 
     /// Construct a new Deepgram client with the specified base URL and
@@ -249,8 +401,8 @@ impl Deepgram {
     /// query your deepgram instance at `http://deepgram.internal/v1/listen`,
     /// the base_url will be `http://deepgram.internal`.
     ///
-    /// Admin features, such as billing, usage, and key management will
-    /// still go through the hosted site at `https://api.deepgram.com`.
+    /// Admin features, such as billing, usage, and key management, are sent
+    /// to this base URL too.
     ///
     /// [console]: https://console.deepgram.com/
     ///
@@ -275,10 +427,131 @@ impl Deepgram {
         K: AsRef<str>,
     {
         let base_url = base_url.try_into().map_err(|_| DeepgramError::InvalidUrl)?;
-        Self::inner_constructor(base_url, Some(api_key.as_ref().to_owned()))
+        Self::inner_constructor(vec![base_url], Some(Credentials::api_key(api_key.as_ref())))
+    }
+
+    /// Construct a new Deepgram client for a self-hosted deployment with
+    /// several instances, failing over between them.
+    ///
+    /// Requests go to the first base URL that is healthy and fail over to the
+    /// others in order, as described in the [`routing`] module.
+    ///
+    /// Like [`Deepgram::with_base_url`], no `Authorization` header is sent.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// # use deepgram::Deepgram;
+    /// let deepgram = Deepgram::with_base_urls([
+    ///     "http://deepgram-a.internal:8080",
+    ///     "http://deepgram-b.internal:8080",
+    /// ]).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as [`Deepgram::with_base_url`], or
+    /// with [`DeepgramError::NoEndpoints`] if `base_urls` is empty.
+    pub fn with_base_urls<I, U>(base_urls: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = U>,
+        U: TryInto<Url>,
+        U::Error: std::fmt::Debug,
+    {
+        Self::inner_constructor(parse_base_urls(base_urls)?, None)
+    }
+
+    /// Construct a new Deepgram client for a self-hosted deployment with
+    /// several instances and an API key.
+    ///
+    /// See [`Deepgram::with_base_urls`].
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as [`Deepgram::with_base_urls`].
+    pub fn with_base_urls_and_api_key<I, U, K>(base_urls: I, api_key: K) -> Result<Self>
+    where
+        I: IntoIterator<Item = U>,
+        U: TryInto<Url>,
+        U::Error: std::fmt::Debug,
+        K: AsRef<str>,
+    {
+        Self::inner_constructor(
+            parse_base_urls(base_urls)?,
+            Some(Credentials::api_key(api_key.as_ref())),
+        )
+    }
+
+    /// Construct a new Deepgram client that routes requests between the
+    /// endpoints of a [`Router`].
+    ///
+    /// The client will not pass an API key to the endpoints, as with
+    /// [`Deepgram::with_base_url`].
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as [`reqwest::ClientBuilder::build`].
+    pub fn with_router(router: Router) -> Result<Self> {
+        let (endpoints, policy) = router.into_parts();
+        Self::from_parts(endpoints, policy, None)
+    }
+
+    /// Construct a new Deepgram client that routes requests between the
+    /// endpoints of a [`Router`], authenticating with an API key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use deepgram::{
+    /// #     routing::{Endpoint, Router, RoutingPolicy},
+    /// #     Deepgram,
+    /// # };
+    /// #
+    /// let router = Router::new([
+    ///     Endpoint::new("us", "https://api.deepgram.com").unwrap().in_region("us"),
+    ///     Endpoint::new("eu", "https://api.eu.deepgram.com").unwrap().in_region("eu"),
+    /// ])
+    /// .unwrap()
+    /// .policy(RoutingPolicy::Region("eu".to_string()));
+    ///
+    /// let deepgram = Deepgram::with_router_and_api_key(router, "apikey12345").unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as [`reqwest::ClientBuilder::build`].
+    pub fn with_router_and_api_key<K>(router: Router, api_key: K) -> Result<Self>
+    where
+        K: AsRef<str>,
+    {
+        let (endpoints, policy) = router.into_parts();
+        Self::from_parts(
+            endpoints,
+            policy,
+            Some(Credentials::api_key(api_key.as_ref())),
+        )
+    }
+
+    fn inner_constructor(base_urls: Vec<Url>, api_key: Option<Credentials>) -> Result<Self> {
+        if base_urls.is_empty() {
+            return Err(DeepgramError::NoEndpoints);
+        }
+        if base_urls.iter().any(Url::cannot_be_a_base) {
+            return Err(DeepgramError::InvalidUrl);
+        }
+
+        Self::from_parts(
+            Endpoints::from_urls(base_urls),
+            RoutingPolicy::default(),
+            api_key,
+        )
     }
 
-    fn inner_constructor(base_url: Url, api_key: Option<String>) -> Result<Self> {
+    fn from_parts(
+        endpoints: Endpoints,
+        policy: RoutingPolicy,
+        api_key: Option<Credentials>,
+    ) -> Result<Self> {
         static USER_AGENT: &str = concat!(
             env!("CARGO_PKG_NAME"),
             "/",
@@ -286,13 +559,10 @@ impl Deepgram {
             " rust",
         );
 
-        if base_url.cannot_be_a_base() {
-            return Err(DeepgramError::InvalidUrl);
-        }
         let authorization_header = {
             let mut header = HeaderMap::new();
             if let Some(api_key) = &api_key {
-                if let Ok(value) = HeaderValue::from_str(&format!("Token {}", api_key)) {
+                if let Ok(value) = HeaderValue::from_str(&api_key.authorization()) {
                     header.insert("Authorization", value);
                 }
             }
@@ -300,8 +570,9 @@ impl Deepgram {
         };
 
         Ok(Deepgram {
-            api_key: api_key.map(RedactedString),
-            base_url,
+            api_key,
+            endpoints: Arc::new(endpoints),
+            policy,
             client: reqwest::Client::builder()
                 .user_agent(USER_AGENT)
                 .default_headers(authorization_header)
@@ -310,6 +581,18 @@ impl Deepgram {
     }
 }
 
+fn parse_base_urls<I, U>(base_urls: I) -> Result<Vec<Url>>
+where
+    I: IntoIterator<Item = U>,
+    U: TryInto<Url>,
+    U::Error: std::fmt::Debug,
+{
+    base_urls
+        .into_iter()
+        .map(|base_url| base_url.try_into().map_err(|_| DeepgramError::InvalidUrl))
+        .collect()
+}
+
 /// Sends the request and checks the response for an error.
 ///
 /// If there is an error, it translates it into a [`DeepgramError::DeepgramApiError`].
@@ -318,13 +601,70 @@ impl Deepgram {
 async fn send_and_translate_response<R: DeserializeOwned>(
     request_builder: RequestBuilder,
 ) -> crate::Result<R> {
-    let response = request_builder.send().await?;
-
-    match response.error_for_status_ref() {
-        Ok(_) => Ok(response.json().await?),
-        Err(err) => Err(DeepgramError::DeepgramApiError {
-            body: response.text().await?,
-            err,
-        }),
+    send_and_translate_response_via(None, request_builder).await
+}
+
+/// Like [`send_and_translate_response`], but routes the request between the
+/// endpoints of `deepgram`.
+#[cfg_attr(not(feature = "listen"), allow(unused))]
+async fn send_and_translate_response_routed<R: DeserializeOwned>(
+    deepgram: &Deepgram,
+    request_builder: RequestBuilder,
+) -> crate::Result<R> {
+    send_and_translate_response_via(Some((deepgram, RetryServerErrors::Always)), request_builder)
+        .await
+}
+
+/// Like [`send_and_translate_response_routed`], but only retries a request
+/// that got a server error if it is idempotent, so that management changes
+/// are never applied twice.
+#[cfg(feature = "manage")]
+async fn send_and_translate_management_response<R: DeserializeOwned>(
+    deepgram: &Deepgram,
+    request_builder: RequestBuilder,
+) -> crate::Result<R> {
+    send_and_translate_response_via(
+        Some((deepgram, RetryServerErrors::IfIdempotent)),
+        request_builder,
+    )
+    .await
+}
+
+async fn send_and_translate_response_via<R: DeserializeOwned>(
+    deepgram: Option<(&Deepgram, RetryServerErrors)>,
+    request_builder: RequestBuilder,
+) -> crate::Result<R> {
+    let (client, request) = request_builder.build_split();
+    let request = request?;
+    let span = instrumentation::request_span(&request);
+
+    async move {
+        let started = tokio::time::Instant::now();
+        let response = match deepgram {
+            Some((deepgram, retry_server_errors)) => {
+                deepgram
+                    .execute_routed(request, retry_server_errors)
+                    .await?
+            }
+            None => client.execute(request).await?,
+        };
+        instrumentation::record_response(&response, started);
+
+        match response.error_for_status_ref() {
+            Ok(_) => {
+                let body = response.bytes().await?;
+                serde_json::from_slice(&body).map_err(|err| {
+                    instrumentation::event!(WARN, error = %err, "failed to parse response");
+                    err.into()
+                })
+            }
+            Err(err) => {
+                let body = response.text().await?;
+                instrumentation::event!(WARN, error = %err, body = %body, "request failed");
+                Err(DeepgramError::DeepgramApiError { body, err })
+            }
+        }
     }
+    .instrument(span)
+    .await
 }
@@ -0,0 +1,797 @@
+//! Helpers for preparing live audio before it is sent to Deepgram.
+//!
+//! Live transcription expects raw audio frames in a single, declared
+//! encoding, sample rate and channel count. Capture devices and telephony
+//! systems rarely produce exactly that, so a [`Transcoder`] converts between
+//! sample formats, remaps channels, resamples, and cuts the result into
+//! correctly-sized frames.
+//!
+//! ```
+//! use deepgram::listen::audio::{AudioFormat, SampleFormat, Transcoder};
+//!
+//! // 48 kHz float32 stereo in, 16 kHz linear16 mono out.
+//! let mut transcoder = Transcoder::new(
+//!     AudioFormat::new(SampleFormat::Float32, 48000, 2),
+//!     AudioFormat::new(SampleFormat::Linear16, 16000, 1),
+//! )
+//! .unwrap();
+//!
+//! let frames = transcoder.push_f32(&[0.0; 48000 * 2]);
+//! // One second of audio cut into 100ms frames of 3200 bytes each.
+//! assert!(frames.iter().all(|frame| frame.len() == 3200));
+//! ```
+//!
+//! See the [Deepgram Encoding feature docs][docs] for the formats accepted by
+//! the live API.
+//!
+//! [docs]: https://developers.deepgram.com/docs/encoding
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use bytes::{Bytes, BytesMut};
+use futures::Stream;
+use pin_project::pin_project;
+
+pub use crate::common::g711::{alaw_decode, alaw_encode, mulaw_decode, mulaw_encode};
+use crate::{common::options::Encoding, DeepgramError, Result};
+
+/// The layout of a single sample.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[non_exhaustive]
+pub enum SampleFormat {
+    /// 16-bit, little endian, signed PCM.
+    Linear16,
+
+    /// 32-bit, little endian, IEEE float PCM in the range `-1.0..=1.0`.
+    ///
+    /// Only valid as an input format.
+    Float32,
+
+    /// 8-bit G.711 µ-law.
+    Mulaw,
+
+    /// 8-bit G.711 A-law.
+    Alaw,
+}
+
+impl SampleFormat {
+    /// The number of bytes used by one sample of one channel.
+    pub fn bytes_per_sample(&self) -> usize {
+        match self {
+            SampleFormat::Linear16 => 2,
+            SampleFormat::Float32 => 4,
+            SampleFormat::Mulaw | SampleFormat::Alaw => 1,
+        }
+    }
+
+    /// The byte that encodes a silent sample.
+    fn silence(&self) -> u8 {
+        match self {
+            SampleFormat::Linear16 | SampleFormat::Float32 => 0,
+            SampleFormat::Mulaw => mulaw_encode(0),
+            SampleFormat::Alaw => alaw_encode(0),
+        }
+    }
+
+    /// The matching Deepgram [`Encoding`], if the live API accepts this format.
+    pub fn encoding(&self) -> Option<Encoding> {
+        match self {
+            SampleFormat::Linear16 => Some(Encoding::Linear16),
+            SampleFormat::Mulaw => Some(Encoding::Mulaw),
+            SampleFormat::Alaw => Some(Encoding::Alaw),
+            SampleFormat::Float32 => None,
+        }
+    }
+}
+
+/// A description of raw, interleaved audio.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct AudioFormat {
+    #[allow(missing_docs)]
+    pub sample_format: SampleFormat,
+
+    #[allow(missing_docs)]
+    pub sample_rate: u32,
+
+    #[allow(missing_docs)]
+    pub channels: u16,
+}
+
+impl AudioFormat {
+    /// Construct a new [`AudioFormat`].
+    pub fn new(sample_format: SampleFormat, sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_format,
+            sample_rate,
+            channels,
+        }
+    }
+
+    /// The number of bytes used by one frame, i.e. one sample of every channel.
+    pub fn bytes_per_frame(&self) -> usize {
+        self.sample_format.bytes_per_sample() * self.channels as usize
+    }
+
+    /// The number of bytes needed to hold `duration` of audio, rounded down
+    /// to a whole number of frames.
+    pub fn bytes_for_duration(&self, duration: Duration) -> usize {
+        let frames = (duration.as_secs_f64() * self.sample_rate as f64) as usize;
+        frames * self.bytes_per_frame()
+    }
+}
+
+/// Converts audio from one [`AudioFormat`] to another.
+///
+/// Input may be pushed in arbitrarily-sized pieces; partial samples are
+/// carried over to the next push. Output is returned as frames of a fixed
+/// duration (100ms unless changed with [`Transcoder::frame_duration`]).
+#[derive(Debug)]
+pub struct Transcoder {
+    input: AudioFormat,
+    output: AudioFormat,
+    frame_size: usize,
+    partial_input: Vec<u8>,
+    resampler: Option<Resampler>,
+    pending_output: BytesMut,
+}
+
+impl Transcoder {
+    /// Construct a new [`Transcoder`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeepgramError::UnsupportedAudioFormat`] if either format has a
+    /// zero sample rate or channel count, if the output is
+    /// [`SampleFormat::Float32`], or if the channel counts cannot be mapped
+    /// onto each other. Supported mappings are identity, any number of
+    /// channels downmixed to mono, and mono duplicated to any number of channels.
+    pub fn new(input: AudioFormat, output: AudioFormat) -> Result<Self> {
+        for format in [&input, &output] {
+            if format.sample_rate == 0 || format.channels == 0 {
+                return Err(DeepgramError::UnsupportedAudioFormat(format!(
+                    "sample rate and channels must be non-zero, got {format:?}"
+                )));
+            }
+        }
+        if output.sample_format == SampleFormat::Float32 {
+            return Err(DeepgramError::UnsupportedAudioFormat(
+                "float32 is not accepted by the live API".to_string(),
+            ));
+        }
+        if input.channels != output.channels && input.channels != 1 && output.channels != 1 {
+            return Err(DeepgramError::UnsupportedAudioFormat(format!(
+                "cannot map {} channels onto {} channels",
+                input.channels, output.channels
+            )));
+        }
+
+        let resampler = (input.sample_rate != output.sample_rate)
+            .then(|| Resampler::new(input.sample_rate, output.sample_rate, output.channels));
+
+        Ok(Self {
+            input,
+            output,
+            frame_size: 0,
+            partial_input: Vec::new(),
+            resampler,
+            pending_output: BytesMut::new(),
+        }
+        .frame_duration(Duration::from_millis(100)))
+    }
+
+    /// Set the duration of each output frame.
+    ///
+    /// Durations shorter than one sample produce frames of a single sample.
+    pub fn frame_duration(mut self, duration: Duration) -> Self {
+        self.frame_size = self
+            .output
+            .bytes_for_duration(duration)
+            .max(self.output.bytes_per_frame());
+        self
+    }
+
+    /// The format audio is expected to be pushed in.
+    pub fn input_format(&self) -> AudioFormat {
+        self.input
+    }
+
+    /// The format of the produced frames.
+    pub fn output_format(&self) -> AudioFormat {
+        self.output
+    }
+
+    /// The size in bytes of every frame except possibly the last one
+    /// returned by [`Transcoder::flush`].
+    pub fn frame_size(&self) -> usize {
+        self.frame_size
+    }
+
+    /// Push raw bytes in the input format, returning any completed frames.
+    pub fn push_bytes(&mut self, data: &[u8]) -> Vec<Bytes> {
+        let bytes_per_frame = self.input.bytes_per_frame();
+        self.partial_input.extend_from_slice(data);
+        let complete = self.partial_input.len() - self.partial_input.len() % bytes_per_frame;
+        let samples = decode(self.input.sample_format, &self.partial_input[..complete]);
+        self.partial_input.drain(..complete);
+        self.push_decoded(samples)
+    }
+
+    /// Push interleaved float samples, regardless of the input sample format.
+    pub fn push_f32(&mut self, samples: &[f32]) -> Vec<Bytes> {
+        self.push_decoded(samples.to_vec())
+    }
+
+    /// Push interleaved 16-bit samples, regardless of the input sample format.
+    pub fn push_i16(&mut self, samples: &[i16]) -> Vec<Bytes> {
+        self.push_decoded(samples.iter().copied().map(i16_to_f32).collect())
+    }
+
+    /// Return any buffered audio as a final, possibly short, frame.
+    pub fn flush(&mut self) -> Option<Bytes> {
+        self.partial_input.clear();
+        (!self.pending_output.is_empty()).then(|| self.pending_output.split().freeze())
+    }
+
+    /// Wrap a stream of raw audio in the input format, producing a stream of
+    /// frames in the output format that can be passed to
+    /// [`WebsocketBuilder::stream`](super::websocket::WebsocketBuilder::stream).
+    pub fn transcode_stream<S, E>(self, stream: S) -> TranscodedStream<S>
+    where
+        S: Stream<Item = std::result::Result<Bytes, E>>,
+    {
+        TranscodedStream {
+            stream,
+            transcoder: self,
+            ready: Vec::new().into_iter(),
+            done: false,
+        }
+    }
+
+    fn push_decoded(&mut self, samples: Vec<f32>) -> Vec<Bytes> {
+        let samples = remap_channels(samples, self.input.channels, self.output.channels);
+        let samples = match &mut self.resampler {
+            Some(resampler) => resampler.process(&samples),
+            None => samples,
+        };
+        encode(
+            self.output.sample_format,
+            &samples,
+            &mut self.pending_output,
+        );
+
+        let mut frames = Vec::with_capacity(self.pending_output.len() / self.frame_size);
+        while self.pending_output.len() >= self.frame_size {
+            frames.push(self.pending_output.split_to(self.frame_size).freeze());
+        }
+        frames
+    }
+}
+
+/// A stream of transcoded audio frames.
+///
+/// Constructed using [`Transcoder::transcode_stream`].
+#[derive(Debug)]
+#[pin_project]
+pub struct TranscodedStream<S> {
+    #[pin]
+    stream: S,
+    transcoder: Transcoder,
+    ready: std::vec::IntoIter<Bytes>,
+    done: bool,
+}
+
+impl<S> TranscodedStream<S> {
+    /// The format of the produced frames.
+    pub fn output_format(&self) -> AudioFormat {
+        self.transcoder.output
+    }
+}
+
+impl<S, E> Stream for TranscodedStream<S>
+where
+    S: Stream<Item = std::result::Result<Bytes, E>>,
+{
+    type Item = std::result::Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            if let Some(frame) = this.ready.next() {
+                return Poll::Ready(Some(Ok(frame)));
+            }
+            if *this.done {
+                return Poll::Ready(None);
+            }
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(Some(Ok(data))) => {
+                    *this.ready = this.transcoder.push_bytes(&data).into_iter();
+                }
+                Poll::Ready(None) => {
+                    *this.done = true;
+                    *this.ready = this
+                        .transcoder
+                        .flush()
+                        .into_iter()
+                        .collect::<Vec<_>>()
+                        .into_iter();
+                }
+            }
+        }
+    }
+}
+
+/// Interleave two mono streams of raw audio into a single stereo stream, for
+/// example the agent and customer legs of a call.
+///
+/// Both streams must carry audio in `sample_format` at the same sample rate.
+/// Audio is emitted as soon as both streams have provided it, with `left` as
+/// channel 0 and `right` as channel 1. Once one stream ends, the other is
+/// padded with silence until it ends too.
+///
+/// Stream the result with [`WebsocketBuilder::stream`] after setting
+/// `channels(2)` and the `multichannel` option, then use
+/// [`TranscriptionStream::by_channel`] to read each channel's transcripts.
+///
+/// ```
+/// # use bytes::Bytes;
+/// # use futures::{stream, StreamExt};
+/// use deepgram::listen::audio::{interleave_streams, SampleFormat};
+///
+/// # futures::executor::block_on(async {
+/// let left = stream::iter([Ok::<_, std::io::Error>(Bytes::from_static(&[1, 1, 2, 2]))]);
+/// let right = stream::iter([Ok(Bytes::from_static(&[3, 3]))]);
+/// let stereo: Vec<_> = interleave_streams(SampleFormat::Linear16, left, right)
+///     .map(Result::unwrap)
+///     .collect()
+///     .await;
+/// assert_eq!(stereo.concat(), [1, 1, 3, 3, 2, 2, 0, 0]);
+/// # });
+/// ```
+///
+/// [`WebsocketBuilder::stream`]: super::websocket::WebsocketBuilder::stream
+/// [`TranscriptionStream::by_channel`]: super::websocket::TranscriptionStream::by_channel
+pub fn interleave_streams<L, R, E>(
+    sample_format: SampleFormat,
+    left: L,
+    right: R,
+) -> InterleavedStream<L, R>
+where
+    L: Stream<Item = std::result::Result<Bytes, E>>,
+    R: Stream<Item = std::result::Result<Bytes, E>>,
+{
+    InterleavedStream {
+        left,
+        right,
+        sample_format,
+        left_buf: BytesMut::new(),
+        right_buf: BytesMut::new(),
+        left_done: false,
+        right_done: false,
+    }
+}
+
+/// Two mono streams of raw audio interleaved into one stereo stream.
+///
+/// Constructed using [`interleave_streams`].
+#[derive(Debug)]
+#[pin_project]
+pub struct InterleavedStream<L, R> {
+    #[pin]
+    left: L,
+    #[pin]
+    right: R,
+    sample_format: SampleFormat,
+    left_buf: BytesMut,
+    right_buf: BytesMut,
+    left_done: bool,
+    right_done: bool,
+}
+
+impl<L, R, E> Stream for InterleavedStream<L, R>
+where
+    L: Stream<Item = std::result::Result<Bytes, E>>,
+    R: Stream<Item = std::result::Result<Bytes, E>>,
+{
+    type Item = std::result::Result<Bytes, E>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        let sample_size = this.sample_format.bytes_per_sample();
+        loop {
+            // Only read from a stream once its buffer runs out, so that a
+            // stalled stream doesn't let the other buffer without bound.
+            let mut progress = false;
+            if !*this.left_done && this.left_buf.len() < sample_size {
+                // Any data counts as progress, even short of a whole sample,
+                // as the stream must be polled until it is pending.
+                match this.left.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(Ok(data))) => {
+                        this.left_buf.extend_from_slice(&data);
+                        progress = true;
+                    }
+                    Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                    Poll::Ready(None) => {
+                        *this.left_done = true;
+                        progress = true;
+                    }
+                    Poll::Pending => {}
+                }
+            }
+            if !*this.right_done && this.right_buf.len() < sample_size {
+                match this.right.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(Ok(data))) => {
+                        this.right_buf.extend_from_slice(&data);
+                        progress = true;
+                    }
+                    Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                    Poll::Ready(None) => {
+                        *this.right_done = true;
+                        progress = true;
+                    }
+                    Poll::Pending => {}
+                }
+            }
+
+            let left_samples = this.left_buf.len() / sample_size;
+            let right_samples = this.right_buf.len() / sample_size;
+            let frames = match (*this.left_done, *this.right_done) {
+                (false, false) => left_samples.min(right_samples),
+                (true, false) => right_samples,
+                (false, true) => left_samples,
+                (true, true) => left_samples.max(right_samples),
+            };
+
+            if frames > 0 {
+                let silence = vec![this.sample_format.silence(); sample_size];
+                let mut output = BytesMut::with_capacity(frames * 2 * sample_size);
+                for _ in 0..frames {
+                    for buf in [&mut *this.left_buf, &mut *this.right_buf] {
+                        if buf.len() >= sample_size {
+                            output.extend_from_slice(&buf.split_to(sample_size));
+                        } else {
+                            output.extend_from_slice(&silence);
+                        }
+                    }
+                }
+                return Poll::Ready(Some(Ok(output.freeze())));
+            }
+
+            if *this.left_done && *this.right_done {
+                return Poll::Ready(None);
+            }
+            if !progress {
+                return Poll::Pending;
+            }
+        }
+    }
+}
+
+/// Split interleaved samples into one buffer per channel.
+///
+/// Trailing samples that do not make up a whole frame are dropped.
+pub fn deinterleave<T: Copy>(samples: &[T], channels: u16) -> Vec<Vec<T>> {
+    let channels = channels as usize;
+    let frames = samples.len() / channels;
+    (0..channels)
+        .map(|channel| {
+            (0..frames)
+                .map(|frame| samples[frame * channels + channel])
+                .collect()
+        })
+        .collect()
+}
+
+/// Combine one buffer per channel into interleaved samples.
+///
+/// The output is as long as the shortest channel.
+pub fn interleave<T: Copy, C: AsRef<[T]>>(channels: &[C]) -> Vec<T> {
+    let frames = channels
+        .iter()
+        .map(|channel| channel.as_ref().len())
+        .min()
+        .unwrap_or(0);
+    let mut samples = Vec::with_capacity(frames * channels.len());
+    for frame in 0..frames {
+        samples.extend(channels.iter().map(|channel| channel.as_ref()[frame]));
+    }
+    samples
+}
+
+/// Average interleaved samples down to a single channel.
+pub fn downmix(samples: &[f32], channels: u16) -> Vec<f32> {
+    samples
+        .chunks_exact(channels as usize)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
+/// Convert a float sample in the range `-1.0..=1.0` to a 16-bit sample, clamping out of range values.
+pub fn f32_to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16
+}
+
+/// Convert a 16-bit sample to a float sample in the range `-1.0..=1.0`.
+pub fn i16_to_f32(sample: i16) -> f32 {
+    sample as f32 / -(i16::MIN as f32)
+}
+
+fn decode(format: SampleFormat, data: &[u8]) -> Vec<f32> {
+    match format {
+        SampleFormat::Linear16 => data
+            .chunks_exact(2)
+            .map(|bytes| i16_to_f32(i16::from_le_bytes([bytes[0], bytes[1]])))
+            .collect(),
+        SampleFormat::Float32 => data
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect(),
+        SampleFormat::Mulaw => data
+            .iter()
+            .map(|byte| i16_to_f32(mulaw_decode(*byte)))
+            .collect(),
+        SampleFormat::Alaw => data
+            .iter()
+            .map(|byte| i16_to_f32(alaw_decode(*byte)))
+            .collect(),
+    }
+}
+
+fn encode(format: SampleFormat, samples: &[f32], out: &mut BytesMut) {
+    match format {
+        SampleFormat::Linear16 => {
+            out.reserve(samples.len() * 2);
+            for sample in samples {
+                out.extend_from_slice(&f32_to_i16(*sample).to_le_bytes());
+            }
+        }
+        SampleFormat::Float32 => {
+            out.reserve(samples.len() * 4);
+            for sample in samples {
+                out.extend_from_slice(&sample.to_le_bytes());
+            }
+        }
+        SampleFormat::Mulaw => {
+            out.extend(
+                samples
+                    .iter()
+                    .map(|sample| mulaw_encode(f32_to_i16(*sample))),
+            );
+        }
+        SampleFormat::Alaw => {
+            out.extend(
+                samples
+                    .iter()
+                    .map(|sample| alaw_encode(f32_to_i16(*sample))),
+            );
+        }
+    }
+}
+
+fn remap_channels(samples: Vec<f32>, from: u16, to: u16) -> Vec<f32> {
+    if from == to {
+        samples
+    } else if to == 1 {
+        downmix(&samples, from)
+    } else {
+        samples
+            .iter()
+            .flat_map(|sample| (0..to).map(move |_| *sample))
+            .collect()
+    }
+}
+
+/// A streaming linear-interpolation resampler for interleaved audio.
+#[derive(Debug)]
+struct Resampler {
+    step: f64,
+    channels: usize,
+    position: f64,
+    last_frame: Option<Vec<f32>>,
+}
+
+impl Resampler {
+    fn new(from: u32, to: u32, channels: u16) -> Self {
+        Self {
+            step: from as f64 / to as f64,
+            channels: channels as usize,
+            position: 0.0,
+            last_frame: None,
+        }
+    }
+
+    fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        let channels = self.channels;
+        // Prepend the final frame of the previous call so interpolation is
+        // continuous across chunk boundaries.
+        let carried = self.last_frame.as_deref().unwrap_or(&[]);
+        let frame_count = carried.len() / channels + samples.len() / channels;
+        let frame = |index: usize| -> &[f32] {
+            if index < carried.len() / channels {
+                carried
+            } else {
+                let index = index - carried.len() / channels;
+                &samples[index * channels..(index + 1) * channels]
+            }
+        };
+
+        let mut out = Vec::with_capacity((samples.len() as f64 / self.step) as usize + channels);
+        while frame_count > 0 && self.position + 1.0 < frame_count as f64 {
+            let index = self.position as usize;
+            let fraction = (self.position - index as f64) as f32;
+            let (current, next) = (frame(index), frame(index + 1));
+            out.extend((0..channels).map(|ch| current[ch] + (next[ch] - current[ch]) * fraction));
+            self.position += self.step;
+        }
+
+        if frame_count > 0 {
+            self.position -= (frame_count - 1) as f64;
+            self.last_frame = Some(frame(frame_count - 1).to_vec());
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures::{stream, FutureExt, StreamExt};
+
+    use super::*;
+
+    #[test]
+    fn interleave_round_trip() {
+        let samples = [1, 2, 3, 4, 5, 6];
+        let channels = deinterleave(&samples, 2);
+        assert_eq!(channels, vec![vec![1, 3, 5], vec![2, 4, 6]]);
+        assert_eq!(interleave(&channels), samples);
+    }
+
+    #[test]
+    fn interleave_streams_pads_with_silence() {
+        let left = stream::iter([
+            Ok::<_, DeepgramError>(Bytes::from_static(&[1])),
+            Ok(Bytes::from_static(&[2, 3])),
+        ]);
+        let right = stream::iter([Ok(Bytes::from_static(&[10, 20, 30, 40]))]);
+        let stereo: Vec<Bytes> = futures::executor::block_on(
+            interleave_streams(SampleFormat::Mulaw, left, right)
+                .map(Result::unwrap)
+                .collect(),
+        );
+        assert_eq!(stereo.concat(), [1, 10, 2, 20, 3, 30, mulaw_encode(0), 40]);
+    }
+
+    #[test]
+    fn interleave_streams_polls_until_pending() {
+        let left = stream::iter([
+            Ok::<_, DeepgramError>(Bytes::from_static(&[1, 1])),
+            Ok(Bytes::from_static(&[2])),
+            Ok(Bytes::from_static(&[2])),
+        ])
+        .chain(stream::pending());
+        let right = stream::iter([Ok(Bytes::from_static(&[3, 3, 4, 4]))]).chain(stream::pending());
+        let mut stereo = interleave_streams(SampleFormat::Linear16, left, right);
+
+        // Half a sample arrives while the right channel is already buffered,
+        // so the left must be polled again rather than waiting for a wakeup.
+        let mut next = || {
+            stereo
+                .next()
+                .now_or_never()
+                .map(|item| item.unwrap().unwrap())
+        };
+        assert_eq!(next().as_deref(), Some(&[1, 1, 3, 3][..]));
+        assert_eq!(next().as_deref(), Some(&[2, 2, 4, 4][..]));
+        assert_eq!(next(), None);
+    }
+
+    #[test]
+    fn frames_are_sized_and_aligned() {
+        let mut transcoder = Transcoder::new(
+            AudioFormat::new(SampleFormat::Linear16, 16000, 1),
+            AudioFormat::new(SampleFormat::Linear16, 16000, 1),
+        )
+        .unwrap()
+        .frame_duration(Duration::from_millis(20));
+        assert_eq!(transcoder.frame_size(), 640);
+
+        // An odd number of bytes leaves half a sample behind.
+        let frames = transcoder.push_bytes(&[0; 1001]);
+        assert_eq!(frames.len(), 1);
+        let frames = transcoder.push_bytes(&[0; 279]);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(transcoder.flush(), None);
+    }
+
+    #[test]
+    fn frames_hold_at_least_one_sample() {
+        // 100ms at 8Hz is less than one sample.
+        let mut transcoder = Transcoder::new(
+            AudioFormat::new(SampleFormat::Linear16, 8, 1),
+            AudioFormat::new(SampleFormat::Linear16, 8, 1),
+        )
+        .unwrap();
+        assert_eq!(transcoder.frame_size(), 2);
+
+        let frames = transcoder.push_i16(&[1, 2, 3]);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(transcoder.flush(), None);
+    }
+
+    #[test]
+    fn downmix_and_resample() {
+        let mut transcoder = Transcoder::new(
+            AudioFormat::new(SampleFormat::Float32, 48000, 2),
+            AudioFormat::new(SampleFormat::Mulaw, 8000, 1),
+        )
+        .unwrap();
+
+        let mut total = 0;
+        for _ in 0..10 {
+            total += transcoder
+                .push_f32(&[0.5; 4800 * 2])
+                .iter()
+                .map(Bytes::len)
+                .sum::<usize>();
+        }
+        total += transcoder.flush().map_or(0, |frame| frame.len());
+        // One second of 8 kHz mono µ-law, give or take the final interpolation point.
+        assert!((7990..=8000).contains(&total), "{total}");
+    }
+
+    #[test]
+    fn resampler_is_continuous_across_chunks() {
+        let mut whole = Resampler::new(3, 2, 1);
+        let ramp: Vec<f32> = (0..30).map(|i| i as f32).collect();
+        let expected = whole.process(&ramp);
+
+        let mut chunked = Resampler::new(3, 2, 1);
+        let actual: Vec<f32> = ramp
+            .chunks(7)
+            .flat_map(|chunk| chunked.process(chunk))
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn rejects_unsupported_formats() {
+        assert!(Transcoder::new(
+            AudioFormat::new(SampleFormat::Linear16, 16000, 2),
+            AudioFormat::new(SampleFormat::Float32, 16000, 2),
+        )
+        .is_err());
+        assert!(Transcoder::new(
+            AudioFormat::new(SampleFormat::Linear16, 16000, 3),
+            AudioFormat::new(SampleFormat::Linear16, 16000, 2),
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn transcoded_stream() {
+        let transcoder = Transcoder::new(
+            AudioFormat::new(SampleFormat::Linear16, 8000, 1),
+            AudioFormat::new(SampleFormat::Linear16, 8000, 2),
+        )
+        .unwrap();
+        let input = stream::iter(vec![
+            Ok::<_, std::io::Error>(Bytes::from(vec![0; 1000])),
+            Ok(Bytes::from(vec![0; 1000])),
+        ]);
+        let frames: Vec<_> = transcoder
+            .transcode_stream(input)
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(
+            frames.iter().map(Bytes::len).collect::<Vec<_>>(),
+            vec![3200, 800]
+        );
+    }
+}
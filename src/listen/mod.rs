@@ -1,4 +1,6 @@
 //! Listen module
 
+pub mod audio;
+pub mod recording;
 pub mod rest;
 pub mod websocket;
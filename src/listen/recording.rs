@@ -0,0 +1,504 @@
+//! Recording live sessions and replaying them.
+//!
+//! A [`SessionRecorder`] passed to [`WebsocketBuilder::record`] writes every
+//! audio frame and control message sent, and every response received, to a
+//! directory:
+//!
+//! - `events.jsonl` holds one JSON object per line, each with the time in
+//!   microseconds since the session opened (`elapsed_us`) and a `type` of
+//!   `open`, `audio`, `control`, `response` or `error`. Audio events point
+//!   into the audio file with an `offset` and `len`.
+//! - `audio.raw` holds the audio frames back to back, exactly as sent.
+//!
+//! A [`Recording`] reads the directory back. Its audio can be streamed to
+//! Deepgram again with the original pacing, or its responses served as a
+//! [`TranscriptionStream`] without a connection, for offline tests.
+//!
+//! ```no_run
+//! # use deepgram::{listen::recording::{Recording, SessionRecorder}, Deepgram, DeepgramError};
+//! # use futures::StreamExt;
+//! #
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), DeepgramError> {
+//! # let dg = Deepgram::new("token")?;
+//! // Replay the audio of a recorded session, recording the new session too.
+//! let recording = Recording::open("sessions/broken")?;
+//! let mut results = dg
+//!     .transcription()
+//!     .stream_request()
+//!     .record(SessionRecorder::create("sessions/retry")?)
+//!     .stream(recording.audio_stream())
+//!     .await?;
+//! while let Some(response) = results.next().await {
+//!     println!("{:?}", response?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`WebsocketBuilder::record`]: super::websocket::WebsocketBuilder::record
+
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{mpsc as std_mpsc, Arc},
+    thread,
+    time::Duration,
+};
+
+use anyhow::anyhow;
+use bytes::Bytes;
+use futures::{
+    channel::mpsc,
+    stream::{self, BoxStream},
+    SinkExt, StreamExt,
+};
+use serde::{Deserialize, Serialize};
+use tokio::{sync::oneshot, time::Instant};
+use uuid::Uuid;
+
+use super::websocket::TranscriptionStream;
+use crate::{common::stream_response::StreamResponse, DeepgramError, Result};
+
+static EVENTS_FILE: &str = "events.jsonl";
+static AUDIO_FILE: &str = "audio.raw";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    elapsed_us: u64,
+    #[serde(flatten)]
+    event: Event,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Event {
+    Open { request_id: Uuid, channels: u16 },
+    Audio { offset: u64, len: usize },
+    Control { message: serde_json::Value },
+    Response { response: StreamResponse },
+    Error { message: String },
+}
+
+/// Records a live session to a directory.
+///
+/// See the [module docs](self) for the format. Clones write to the same
+/// recording. Writes happen on a dedicated thread, so a slow disk doesn't
+/// hold up the session. Failing to write doesn't interrupt the session
+/// either; the error is returned by [`finish`](Self::finish).
+#[derive(Clone)]
+pub struct SessionRecorder {
+    dir: Arc<PathBuf>,
+    commands: std_mpsc::Sender<Command>,
+}
+
+enum Command {
+    Open {
+        at: Instant,
+        request_id: Uuid,
+        channels: u16,
+    },
+    Audio {
+        at: Instant,
+        audio: Vec<u8>,
+    },
+    Event {
+        at: Instant,
+        event: Event,
+    },
+    Flush {
+        done: Option<oneshot::Sender<Option<io::Error>>>,
+    },
+}
+
+struct RecordingWriter {
+    events: BufWriter<File>,
+    audio: BufWriter<File>,
+    audio_offset: u64,
+    started: Option<Instant>,
+    error: Option<io::Error>,
+}
+
+impl fmt::Debug for SessionRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionRecorder")
+            .field("dir", &self.dir)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SessionRecorder {
+    /// Create a recorder writing to `dir`, which is created if it doesn't
+    /// exist. A previous recording in the directory is overwritten.
+    pub fn create(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        let mut writer = RecordingWriter {
+            events: BufWriter::new(File::create(dir.join(EVENTS_FILE))?),
+            audio: BufWriter::new(File::create(dir.join(AUDIO_FILE))?),
+            audio_offset: 0,
+            started: None,
+            error: None,
+        };
+
+        // The thread exits once every clone of the recorder is dropped.
+        let (commands, received) = std_mpsc::channel();
+        thread::Builder::new()
+            .name("deepgram-session-recorder".to_string())
+            .spawn(move || writer.run(received))?;
+
+        Ok(SessionRecorder {
+            dir: Arc::new(dir.to_path_buf()),
+            commands,
+        })
+    }
+
+    /// The directory being recorded to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Wait for the recording to be flushed to disk, returning the first
+    /// error that occurred while writing it.
+    ///
+    /// The recording is flushed automatically when the session ends.
+    pub async fn finish(&self) -> Result<()> {
+        let (done, flushed) = oneshot::channel();
+        self.send(Command::Flush { done: Some(done) });
+        match flushed.await {
+            Ok(Some(err)) => Err(err.into()),
+            Ok(None) => Ok(()),
+            Err(_) => Err(DeepgramError::InternalClientError(anyhow!(
+                "the session recorder stopped"
+            ))),
+        }
+    }
+
+    pub(super) fn open(&self, request_id: Uuid, channels: u16) {
+        self.send(Command::Open {
+            at: Instant::now(),
+            request_id,
+            channels,
+        });
+    }
+
+    pub(super) fn audio(&self, audio: &[u8]) {
+        self.send(Command::Audio {
+            at: Instant::now(),
+            audio: audio.to_vec(),
+        });
+    }
+
+    pub(super) fn control(&self, message: &impl Serialize) {
+        let message = serde_json::to_value(message).unwrap_or_default();
+        self.event(Event::Control { message });
+    }
+
+    pub(super) fn response(&self, response: &StreamResponse) {
+        self.event(Event::Response {
+            response: response.clone(),
+        });
+    }
+
+    pub(super) fn error(&self, error: &DeepgramError) {
+        self.event(Event::Error {
+            message: error.to_string(),
+        });
+    }
+
+    pub(super) fn flush(&self) {
+        self.send(Command::Flush { done: None });
+    }
+
+    fn event(&self, event: Event) {
+        self.send(Command::Event {
+            at: Instant::now(),
+            event,
+        });
+    }
+
+    fn send(&self, command: Command) {
+        // The writer thread only exits once every sender is dropped.
+        let _ = self.commands.send(command);
+    }
+}
+
+impl RecordingWriter {
+    fn run(&mut self, commands: std_mpsc::Receiver<Command>) {
+        for command in commands {
+            match command {
+                Command::Open {
+                    at,
+                    request_id,
+                    channels,
+                } => {
+                    self.started = Some(at);
+                    self.write_event(
+                        at,
+                        Event::Open {
+                            request_id,
+                            channels,
+                        },
+                    );
+                }
+                Command::Audio { at, audio } => {
+                    let written = self.audio.write_all(&audio);
+                    self.record_result(written);
+                    let offset = self.audio_offset;
+                    self.audio_offset += audio.len() as u64;
+                    self.write_event(
+                        at,
+                        Event::Audio {
+                            offset,
+                            len: audio.len(),
+                        },
+                    );
+                }
+                Command::Event { at, event } => self.write_event(at, event),
+                Command::Flush { done } => {
+                    let flushed = self.flush();
+                    self.record_result(flushed);
+                    if let Some(done) = done {
+                        let _ = done.send(self.error.take());
+                    }
+                }
+            }
+        }
+
+        let flushed = self.flush();
+        self.record_result(flushed);
+    }
+
+    fn write_event(&mut self, at: Instant, event: Event) {
+        let elapsed = self
+            .started
+            .map(|started| at.saturating_duration_since(started));
+        let entry = Entry {
+            elapsed_us: elapsed.unwrap_or_default().as_micros() as u64,
+            event,
+        };
+        let written = serde_json::to_writer(&mut self.events, &entry)
+            .map_err(io::Error::from)
+            .and_then(|()| self.events.write_all(b"\n"));
+        self.record_result(written);
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.audio.flush()?;
+        self.events.flush()
+    }
+
+    fn record_result(&mut self, result: io::Result<()>) {
+        if let Err(err) = result {
+            crate::instrumentation::record_error(&err, "failed to write session recording");
+            self.error.get_or_insert(err);
+        }
+    }
+}
+
+/// A live session recorded by a [`SessionRecorder`].
+///
+/// See the [module docs](self) for more info.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    entries: Arc<Vec<Entry>>,
+    audio: Bytes,
+    paced: bool,
+}
+
+impl Recording {
+    /// Read the recording in `dir`.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let audio = Bytes::from(fs::read(dir.join(AUDIO_FILE))?);
+
+        let mut entries = Vec::new();
+        for line in BufReader::new(File::open(dir.join(EVENTS_FILE))?).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: Entry = serde_json::from_str(&line)?;
+            if let Event::Audio { offset, len } = entry.event {
+                if offset + len as u64 > audio.len() as u64 {
+                    return Err(DeepgramError::InternalClientError(anyhow!(
+                        "the recorded audio in {} is truncated",
+                        dir.display()
+                    )));
+                }
+            }
+            entries.push(entry);
+        }
+
+        Ok(Recording {
+            entries: Arc::new(entries),
+            audio,
+            paced: true,
+        })
+    }
+
+    /// Whether to replay with the original timing, which is the default.
+    ///
+    /// Without pacing, audio and responses are replayed as fast as they are
+    /// read, which is useful for tests.
+    pub fn paced(mut self, paced: bool) -> Self {
+        self.paced = paced;
+        self
+    }
+
+    /// The request ID of the recorded session.
+    pub fn request_id(&self) -> Option<Uuid> {
+        self.open_event().map(|(request_id, _)| request_id)
+    }
+
+    /// The recorded audio frames, paced as they were originally sent.
+    ///
+    /// Pass the stream to [`WebsocketBuilder::stream`] to send the audio to
+    /// Deepgram again. Recorded control messages are not replayed.
+    ///
+    /// [`WebsocketBuilder::stream`]: super::websocket::WebsocketBuilder::stream
+    pub fn audio_stream(&self) -> BoxStream<'static, Result<Bytes>> {
+        let frames: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|entry| match entry.event {
+                Event::Audio { offset, len } => Some((
+                    entry.elapsed_us,
+                    self.audio.slice(offset as usize..offset as usize + len),
+                )),
+                _ => None,
+            })
+            .collect();
+
+        self.pace(frames, Ok)
+    }
+
+    /// Serve the recorded responses and errors as a [`TranscriptionStream`],
+    /// without connecting to Deepgram.
+    ///
+    /// Errors are replayed as [`DeepgramError::InternalClientError`] with the
+    /// recorded message. Control messages sent on the stream are ignored.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn responses(&self) -> TranscriptionStream {
+        let responses: Vec<_> = self
+            .entries
+            .iter()
+            .filter_map(|entry| match &entry.event {
+                Event::Response { response } => Some((entry.elapsed_us, Ok(response.clone()))),
+                Event::Error { message } => Some((
+                    entry.elapsed_us,
+                    Err(DeepgramError::InternalClientError(anyhow!(message.clone()))),
+                )),
+                _ => None,
+            })
+            .collect();
+
+        let (request_id, channels) = self.open_event().unwrap_or((Uuid::nil(), 1));
+        let (mut tx, rx) = mpsc::channel(1);
+        let mut responses = self.pace(responses, |response| response);
+        tokio::spawn(async move {
+            while let Some(response) = responses.next().await {
+                if tx.send(response).await.is_err() {
+                    break;
+                }
+            }
+        });
+        TranscriptionStream::replayed(rx, request_id, channels)
+    }
+
+    fn open_event(&self) -> Option<(Uuid, u16)> {
+        self.entries.iter().find_map(|entry| match entry.event {
+            Event::Open {
+                request_id,
+                channels,
+            } => Some((request_id, channels)),
+            _ => None,
+        })
+    }
+
+    /// Yield each item at its recorded time, relative to the first item.
+    fn pace<T, U>(
+        &self,
+        items: Vec<(u64, T)>,
+        map: impl Fn(T) -> U + Send + 'static,
+    ) -> BoxStream<'static, U>
+    where
+        T: Send + 'static,
+        U: Send + 'static,
+    {
+        let paced = self.paced;
+        let first = items.first().map_or(0, |(elapsed_us, _)| *elapsed_us);
+        stream::unfold(
+            (items.into_iter(), None),
+            move |(mut items, started): (std::vec::IntoIter<(u64, T)>, Option<Instant>)| {
+                let next = items.next();
+                async move {
+                    let (elapsed_us, item) = next?;
+                    let started = started.unwrap_or_else(Instant::now);
+                    if paced {
+                        let at = Duration::from_micros(elapsed_us.saturating_sub(first));
+                        tokio::time::sleep_until(started + at).await;
+                    }
+                    Some((item, (items, Some(started))))
+                }
+            },
+        )
+        .map(map)
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use uuid::Uuid;
+
+    use super::{Recording, SessionRecorder};
+    use crate::{
+        common::stream_response::StreamResponse, listen::websocket::ControlMessage, DeepgramError,
+    };
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let dir = std::env::temp_dir().join(format!("deepgram-recording-{}", std::process::id()));
+        let recorder = SessionRecorder::create(&dir).unwrap();
+        let request_id = Uuid::from_u128(1);
+        recorder.open(request_id, 2);
+        recorder.audio(&[1, 2, 3]);
+        recorder.control(&ControlMessage::Finalize);
+        recorder.audio(&[4, 5]);
+        recorder.response(&StreamResponse::UtteranceEndResponse {
+            type_field: "UtteranceEnd".to_string(),
+            channel: vec![0, 2],
+            last_word_end: 1.5,
+        });
+        recorder.error(&DeepgramError::AudioBufferFull);
+        recorder.finish().await.unwrap();
+
+        let recording = Recording::open(&dir).unwrap().paced(false);
+        assert_eq!(recording.request_id(), Some(request_id));
+
+        let audio: Vec<_> = recording
+            .audio_stream()
+            .map(|frame| frame.unwrap())
+            .collect()
+            .await;
+        assert_eq!(audio, vec![&[1, 2, 3][..], &[4, 5][..]]);
+
+        let responses = recording.responses();
+        assert_eq!(responses.request_id(), request_id);
+        let responses: Vec<_> = responses.collect().await;
+        assert!(matches!(
+            &responses[..],
+            [
+                Ok(StreamResponse::UtteranceEndResponse { last_word_end, .. }),
+                Err(DeepgramError::InternalClientError(_)),
+            ] if *last_word_end == 1.5
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
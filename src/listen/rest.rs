@@ -8,7 +8,7 @@ use reqwest::RequestBuilder;
 use url::Url;
 
 use crate::common::audio_source::AudioSource;
-use crate::{send_and_translate_response, Transcription};
+use crate::{send_and_translate_response_routed, Transcription};
 
 use crate::common::batch_response::{CallbackResponse, Response};
 use crate::common::options::{Options, SerializableOptions};
@@ -65,9 +65,10 @@ impl Transcription<'_> {
         source: AudioSource,
         options: &Options,
     ) -> crate::Result<Response> {
-        let request_builder = self.make_prerecorded_request_builder(source, options);
+        let request_builder =
+            self.prerecorded_request_builder(self.0.endpoints.primary(), source, options);
 
-        send_and_translate_response(request_builder).await
+        send_and_translate_response_routed(self.0, request_builder).await
     }
 
     /// Sends a request to Deepgram to transcribe pre-recorded audio using the Callback feature.
@@ -123,10 +124,11 @@ impl Transcription<'_> {
         options: &Options,
         callback: &str,
     ) -> crate::Result<CallbackResponse> {
-        let request_builder =
-            self.make_prerecorded_callback_request_builder(source, options, callback);
+        let request_builder = self
+            .prerecorded_request_builder(self.0.endpoints.primary(), source, options)
+            .query(&[("callback", callback)]);
 
-        send_and_translate_response(request_builder).await
+        send_and_translate_response_routed(self.0, request_builder).await
     }
 
     /// Makes a [`reqwest::RequestBuilder`] without actually sending the request.
@@ -136,6 +138,9 @@ impl Transcription<'_> {
     /// By customizing the request, there is less of a guarantee that it will conform to the Deepgram API.
     /// Prefer using [`Transcription::prerecorded`].
     ///
+    /// The request is built against the endpoint the client's routing policy
+    /// would try first, but is not failed over to other endpoints.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -169,7 +174,7 @@ impl Transcription<'_> {
     /// #
     /// let request_builder = dg_client
     ///     .transcription()
-    ///     .make_prerecorded_request_builder(source, &options);
+    ///     .make_prerecorded_request_builder(source, &options)?;
     ///
     /// // Customize the RequestBuilder here
     /// let customized_request_builder = request_builder
@@ -183,18 +188,19 @@ impl Transcription<'_> {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`DeepgramError::NoRoutableEndpoint`](crate::DeepgramError::NoRoutableEndpoint)
+    /// if the routing policy allows none of the client's endpoints.
     pub fn make_prerecorded_request_builder(
         &self,
         source: AudioSource,
         options: &Options,
-    ) -> RequestBuilder {
-        let request_builder = self
-            .0
-            .client
-            .post(self.listen_url())
-            .query(&SerializableOptions(options));
+    ) -> crate::Result<RequestBuilder> {
+        let index = self.0.endpoints.order(&self.0.policy)?[0];
 
-        source.fill_body(request_builder)
+        Ok(self.prerecorded_request_builder(self.0.endpoints.url(index), source, options))
     }
 
     /// Similar to [`Transcription::make_prerecorded_request_builder`],
@@ -240,7 +246,7 @@ impl Transcription<'_> {
     /// #
     /// let request_builder = dg_client
     ///     .transcription()
-    ///     .make_prerecorded_callback_request_builder(source, &options, &callback_url);
+    ///     .make_prerecorded_callback_request_builder(source, &options, &callback_url)?;
     ///
     /// // Customize the RequestBuilder here
     /// let customized_request_builder = request_builder
@@ -254,30 +260,57 @@ impl Transcription<'_> {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as
+    /// [`Transcription::make_prerecorded_request_builder`].
     pub fn make_prerecorded_callback_request_builder(
         &self,
         source: AudioSource,
         options: &Options,
         callback: &str,
-    ) -> RequestBuilder {
-        self.make_prerecorded_request_builder(source, options)
-            .query(&[("callback", callback)])
+    ) -> crate::Result<RequestBuilder> {
+        Ok(self
+            .make_prerecorded_request_builder(source, options)?
+            .query(&[("callback", callback)]))
     }
 
-    fn listen_url(&self) -> Url {
-        self.0.base_url.join(DEEPGRAM_API_URL_LISTEN).unwrap()
+    fn prerecorded_request_builder(
+        &self,
+        base_url: &Url,
+        source: AudioSource,
+        options: &Options,
+    ) -> RequestBuilder {
+        let request_builder = self
+            .0
+            .client
+            .post(listen_url_for(base_url))
+            .query(&SerializableOptions(options));
+
+        source.fill_body(request_builder)
     }
 }
 
+/// The URL of pre-recorded transcription against `base_url`.
+fn listen_url_for(base_url: &Url) -> Url {
+    base_url.join(DEEPGRAM_API_URL_LISTEN).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::Deepgram;
+    use super::listen_url_for;
+    use crate::{
+        common::{audio_source::AudioSource, options::Options},
+        routing::{Endpoint, Router, RoutingPolicy},
+        Deepgram, DeepgramError,
+    };
 
     #[test]
     fn listen_url() {
         let dg = Deepgram::new("token").unwrap();
         assert_eq!(
-            &dg.transcription().listen_url().to_string(),
+            &listen_url_for(dg.endpoints.primary()).to_string(),
             "https://api.deepgram.com/v1/listen"
         );
     }
@@ -286,8 +319,38 @@ mod tests {
     fn listen_url_custom_host() {
         let dg = Deepgram::with_base_url("http://localhost:8888/abc/").unwrap();
         assert_eq!(
-            &dg.transcription().listen_url().to_string(),
+            &listen_url_for(dg.endpoints.primary()).to_string(),
             "http://localhost:8888/abc/v1/listen"
         );
     }
+
+    #[test]
+    fn request_builder_follows_policy() {
+        let router = Router::new([
+            Endpoint::new("us", "https://api.deepgram.com")
+                .unwrap()
+                .in_region("us"),
+            Endpoint::new("eu", "https://api.eu.deepgram.com")
+                .unwrap()
+                .in_region("eu"),
+        ])
+        .unwrap()
+        .policy(RoutingPolicy::Region("eu".to_owned()));
+        let dg = Deepgram::with_router_and_api_key(router, "token").unwrap();
+        let options = Options::builder().build();
+
+        let request = dg
+            .transcription()
+            .make_prerecorded_request_builder(AudioSource::from_url(""), &options)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.url().host_str(), Some("api.eu.deepgram.com"));
+
+        let result = dg
+            .with_policy(RoutingPolicy::Region("ap".to_owned()))
+            .transcription()
+            .make_prerecorded_request_builder(AudioSource::from_url(""), &options);
+        assert!(matches!(result, Err(DeepgramError::NoRoutableEndpoint(_))));
+    }
 }
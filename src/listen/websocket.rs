@@ -14,6 +14,10 @@ use std::{
     ops::Deref,
     path::Path,
     pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
     time::Duration,
 };
@@ -21,7 +25,7 @@ use std::{
 use anyhow::anyhow;
 use bytes::Bytes;
 use futures::{
-    channel::mpsc::{self, Receiver, Sender},
+    channel::mpsc::{self, Receiver, Sender, UnboundedSender},
     future::{pending, FutureExt},
     select_biased,
     stream::StreamExt,
@@ -30,7 +34,12 @@ use futures::{
 use http::Request;
 use pin_project::pin_project;
 use serde_urlencoded;
-use tokio::fs::File;
+use tokio::{
+    fs::File,
+    io::{AsyncRead, AsyncWrite},
+    net::TcpStream,
+    time::Instant,
+};
 use tokio_tungstenite::{tungstenite::protocol::Message, MaybeTlsStream, WebSocketStream};
 use tungstenite::{
     handshake::client,
@@ -39,17 +48,133 @@ use tungstenite::{
 use url::Url;
 use uuid::Uuid;
 
-use self::file_chunker::FileChunker;
+use self::{
+    file_chunker::FileChunker,
+    message_queue::{MessageReceiver, MessageSender},
+};
+use super::{
+    audio::{AudioFormat, Transcoder},
+    recording::SessionRecorder,
+};
 use crate::{
     common::{
+        batch_response::Response,
         options::{Encoding, Endpointing, Options},
         stream_response::StreamResponse,
     },
+    instrumentation::{self, Instrument},
     Deepgram, DeepgramError, Result, Transcription,
 };
 
 static LIVE_LISTEN_URL_PATH: &str = "v1/listen";
 
+const DEFAULT_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(3);
+const DEFAULT_PING_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_BUFFER_SIZE: usize = 256;
+
+/// What [`WebsocketSender::send_data`] does when the audio buffer is full.
+///
+/// See [`WebsocketBuilder::audio_buffer`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+#[non_exhaustive]
+pub enum OverflowPolicy {
+    /// Wait until there is room in the buffer.
+    #[default]
+    Block,
+
+    /// Drop the oldest queued audio frame to make room.
+    DropOldest,
+
+    /// Return [`DeepgramError::AudioBufferFull`].
+    Error,
+}
+
+/// A snapshot of how much audio has been buffered, sent and transcribed.
+///
+/// Returned by [`TranscriptionStream::metrics`] and the `metrics` methods of
+/// [`WebsocketHandle`], [`WebsocketSender`] and [`WebsocketReceiver`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub struct StreamMetrics {
+    /// Bytes of audio waiting in the buffer to be sent.
+    pub bytes_queued: u64,
+
+    /// Bytes of audio sent to Deepgram.
+    pub bytes_sent: u64,
+
+    /// Bytes of audio dropped by [`OverflowPolicy::DropOldest`].
+    pub bytes_dropped: u64,
+
+    /// Audio frames dropped by [`OverflowPolicy::DropOldest`].
+    pub frames_dropped: u64,
+
+    /// The end (`start + duration`) of the latest transcript received, as an
+    /// offset into the audio.
+    pub transcript_cursor: Duration,
+
+    /// The duration of the audio sent to Deepgram.
+    ///
+    /// [`None`] unless the encoding, sample rate and channels set on the
+    /// [`WebsocketBuilder`] describe raw audio with a fixed bitrate.
+    pub audio_sent: Option<Duration>,
+}
+
+impl StreamMetrics {
+    /// How far transcripts are behind the audio sent to Deepgram, if
+    /// [`audio_sent`](Self::audio_sent) is known.
+    pub fn lag(&self) -> Option<Duration> {
+        self.audio_sent
+            .map(|sent| sent.saturating_sub(self.transcript_cursor))
+    }
+}
+
+/// Shared counters behind [`StreamMetrics`].
+#[derive(Debug, Default)]
+struct Metrics {
+    bytes_per_second: Option<u64>,
+    bytes_queued: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_dropped: AtomicU64,
+    frames_dropped: AtomicU64,
+    transcript_cursor_micros: AtomicU64,
+}
+
+impl Metrics {
+    fn new(bytes_per_second: Option<u64>) -> Self {
+        Metrics {
+            bytes_per_second: bytes_per_second.filter(|&bytes| bytes > 0),
+            ..Default::default()
+        }
+    }
+
+    fn record_response(&self, response: &StreamResponse) {
+        if let StreamResponse::TranscriptResponse {
+            start, duration, ..
+        } = response
+        {
+            let end = Duration::try_from_secs_f64(start + duration).unwrap_or_default();
+            self.transcript_cursor_micros
+                .fetch_max(end.as_micros() as u64, Ordering::Relaxed);
+        }
+    }
+
+    fn snapshot(&self) -> StreamMetrics {
+        let bytes_sent = self.bytes_sent.load(Ordering::Relaxed);
+        StreamMetrics {
+            bytes_queued: self.bytes_queued.load(Ordering::Relaxed),
+            bytes_sent,
+            bytes_dropped: self.bytes_dropped.load(Ordering::Relaxed),
+            frames_dropped: self.frames_dropped.load(Ordering::Relaxed),
+            transcript_cursor: Duration::from_micros(
+                self.transcript_cursor_micros.load(Ordering::Relaxed),
+            ),
+            audio_sent: self.bytes_per_second.map(|bytes_per_second| {
+                Duration::from_secs_f64(bytes_sent as f64 / bytes_per_second as f64)
+            }),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct WebsocketBuilder<'a> {
     deepgram: &'a Deepgram,
@@ -63,7 +188,14 @@ pub struct WebsocketBuilder<'a> {
     no_delay: Option<bool>,
     vad_events: Option<bool>,
     stream_url: Url,
-    keep_alive: Option<bool>,
+    keep_alive_interval: Option<Duration>,
+    ping_interval: Option<Duration>,
+    ping_timeout: Option<Duration>,
+    idle_timeout: Option<Duration>,
+    audio_buffer: Option<usize>,
+    overflow_policy: OverflowPolicy,
+    response_buffer: Option<usize>,
+    recorder: Option<SessionRecorder>,
     callback: Option<Url>,
 }
 
@@ -131,7 +263,6 @@ impl Transcription<'_> {
     ///
     /// assert_eq!(&builder.urlencoded().unwrap(), "model=nova-2&detect_language=true&no_delay=true")
     /// ```
-
     pub fn stream_request_with_options(&self, options: Options) -> WebsocketBuilder<'_> {
         WebsocketBuilder {
             deepgram: self.0,
@@ -145,24 +276,85 @@ impl Transcription<'_> {
             no_delay: None,
             vad_events: None,
             stream_url: self.listen_stream_url(),
-            keep_alive: None,
+            keep_alive_interval: None,
+            ping_interval: None,
+            ping_timeout: None,
+            idle_timeout: None,
+            audio_buffer: None,
+            overflow_policy: OverflowPolicy::default(),
+            response_buffer: None,
+            recorder: None,
             callback: None,
         }
     }
 
     fn listen_stream_url(&self) -> Url {
-        // base
-        let mut url =
-            self.0.base_url.join(LIVE_LISTEN_URL_PATH).expect(
-                "base_url is checked to be a valid base_url when constructing Deepgram client",
-            );
+        stream_url_for(self.0.endpoints.primary())
+    }
+}
 
-        match url.scheme() {
-            "http" | "ws" => url.set_scheme("ws").expect("a valid conversion according to the .set_scheme docs"),
-            "https" | "wss" => url.set_scheme("wss").expect("a valid conversion according to the .set_scheme docs"),
-            _ => unreachable!("base_url is validated to have a scheme of http, https, ws, or wss when constructing Deepgram client"),
-        }
-        url
+/// The websocket URL for streaming transcription against `base_url`.
+fn stream_url_for(base_url: &Url) -> Url {
+    let mut url = base_url
+        .join(LIVE_LISTEN_URL_PATH)
+        .expect("base_url is checked to be a valid base_url when constructing Deepgram client");
+
+    match url.scheme() {
+        "http" | "ws" => url.set_scheme("ws").expect("a valid conversion according to the .set_scheme docs"),
+        "https" | "wss" => url.set_scheme("wss").expect("a valid conversion according to the .set_scheme docs"),
+        _ => unreachable!("base_url is validated to have a scheme of http, https, ws, or wss when constructing Deepgram client"),
+    }
+    url
+}
+
+/// Open a websocket to `url`, authenticating with the API key of the
+/// builder's client.
+async fn handshake(
+    builder: &WebsocketBuilder<'_>,
+    url: &Url,
+) -> std::result::Result<
+    (
+        WebSocketStream<MaybeTlsStream<TcpStream>>,
+        tungstenite::handshake::client::Response,
+    ),
+    tungstenite::Error,
+> {
+    let http_builder = Request::builder()
+        .method("GET")
+        .uri(url.to_string())
+        .header("sec-websocket-key", client::generate_key())
+        .header("host", host_header(url))
+        .header("connection", "upgrade")
+        .header("upgrade", "websocket")
+        .header("sec-websocket-version", "13");
+
+    let http_builder = if let Some(api_key) = &builder.deepgram.api_key {
+        http_builder.header("authorization", api_key.authorization())
+    } else {
+        http_builder
+    };
+    let request = http_builder.body(())?;
+
+    tokio_tungstenite::connect_async(request).await
+}
+
+/// Whether a handshake error means the endpoint is down, so the next one
+/// should be tried.
+fn is_endpoint_failure(err: &tungstenite::Error) -> bool {
+    match err {
+        tungstenite::Error::Io(_) => true,
+        tungstenite::Error::Http(response) => response.status().is_server_error(),
+        _ => false,
+    }
+}
+
+/// The `Host` header for a websocket request to `url`, including the port
+/// unless it is the default for the scheme.
+fn host_header(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_owned(),
     }
 }
 
@@ -207,7 +399,14 @@ impl<'a> WebsocketBuilder<'a> {
         // Destructuring ensures we don't miss new fields if they get added
         let Self {
             deepgram: _,
-            keep_alive: _,
+            keep_alive_interval: _,
+            ping_interval: _,
+            ping_timeout: _,
+            idle_timeout: _,
+            audio_buffer: _,
+            overflow_policy: _,
+            response_buffer: _,
+            recorder: _,
             options,
             encoding,
             sample_rate,
@@ -318,17 +517,134 @@ impl<'a> WebsocketBuilder<'a> {
         self
     }
 
-    pub fn keep_alive(mut self) -> Self {
-        self.keep_alive = Some(true);
+    /// Send a KeepAlive message every 3 seconds while no other messages are
+    /// being sent. Equivalent to `keep_alive_interval(Duration::from_secs(3))`.
+    pub fn keep_alive(self) -> Self {
+        self.keep_alive_interval(DEFAULT_KEEP_ALIVE_INTERVAL)
+    }
+
+    /// Send a KeepAlive message whenever nothing has been sent for `interval`.
+    ///
+    /// Deepgram closes connections that receive no audio or KeepAlive
+    /// messages for 10 seconds. Connecting fails with
+    /// [`DeepgramError::ZeroOption`] if `interval` is zero.
+    pub fn keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.keep_alive_interval = Some(interval);
+
+        self
+    }
+
+    /// Send a websocket ping every `interval`.
+    ///
+    /// If the server doesn't answer a ping with a pong within the
+    /// [`ping_timeout`](Self::ping_timeout), the stream yields
+    /// [`DeepgramError::PongTimeout`] and the connection is dropped. This
+    /// detects half-open TCP connections that would otherwise hang forever.
+    /// Connecting fails with [`DeepgramError::ZeroOption`] if `interval` is
+    /// zero.
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
 
         self
     }
 
+    /// How long to wait for a pong after sending a ping. Defaults to 10 seconds.
+    ///
+    /// Has no effect unless [`ping_interval`](Self::ping_interval) is set.
+    /// Connecting fails with [`DeepgramError::ZeroOption`] if `timeout` is
+    /// zero.
+    pub fn ping_timeout(mut self, timeout: Duration) -> Self {
+        self.ping_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Give up on the connection if no message (such as a transcript or
+    /// metadata) is received from Deepgram for `timeout`.
+    ///
+    /// The stream yields [`DeepgramError::IdleTimeout`] and the connection is
+    /// dropped. Deepgram only sends transcripts while receiving speech, so the
+    /// timeout should be longer than any expected silence. Connecting fails
+    /// with [`DeepgramError::ZeroOption`] if `timeout` is zero.
+    pub fn idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+
+        self
+    }
+
+    /// The number of audio frames that can be queued while waiting to be
+    /// sent. Defaults to 256.
+    ///
+    /// What happens when the buffer is full is set by
+    /// [`overflow_policy`](Self::overflow_policy). Control messages are
+    /// always queued. Connecting fails with [`DeepgramError::ZeroOption`] if
+    /// `frames` is zero.
+    pub fn audio_buffer(mut self, frames: usize) -> Self {
+        self.audio_buffer = Some(frames);
+
+        self
+    }
+
+    /// What to do with new audio when the audio buffer is full. Defaults to
+    /// [`OverflowPolicy::Block`].
+    pub fn overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+
+        self
+    }
+
+    /// The number of responses that can be queued while waiting to be
+    /// received. Defaults to 256.
+    ///
+    /// When the buffer is full, the connection stops reading from the
+    /// websocket until responses are received. Connecting fails with
+    /// [`DeepgramError::ZeroOption`] if `responses` is zero.
+    pub fn response_buffer(mut self, responses: usize) -> Self {
+        self.response_buffer = Some(responses);
+
+        self
+    }
+
+    /// Record the session with `recorder`.
+    ///
+    /// See [`recording`](super::recording) for how to replay it.
+    pub fn record(mut self, recorder: SessionRecorder) -> Self {
+        self.recorder = Some(recorder);
+
+        self
+    }
+
+    /// The number of bytes of audio per second, if it can be worked out from
+    /// the encoding, sample rate and channels.
+    fn bytes_per_second(&self) -> Option<u64> {
+        let bytes_per_sample = match self.encoding.as_ref()? {
+            Encoding::Linear16 => 2,
+            Encoding::Mulaw | Encoding::Alaw => 1,
+            _ => return None,
+        };
+        Some(
+            bytes_per_sample * u64::from(self.sample_rate?) * u64::from(self.channels.unwrap_or(1)),
+        )
+    }
+
     pub fn callback(mut self, callback: Url) -> Self {
         self.callback = Some(callback);
 
         self
     }
+
+    /// Set the encoding, sample rate and channels to describe audio in `format`.
+    ///
+    /// A format with no matching [`Encoding`] (such as
+    /// [`SampleFormat::Float32`](super::audio::SampleFormat::Float32)) leaves
+    /// the encoding unset.
+    pub fn audio_format(mut self, format: AudioFormat) -> Self {
+        self.encoding = format.sample_format.encoding();
+        self.sample_rate = Some(format.sample_rate);
+        self.channels = Some(format.channels);
+
+        self
+    }
 }
 
 impl<'a> WebsocketBuilder<'a> {
@@ -361,23 +677,42 @@ impl<'a> WebsocketBuilder<'a> {
         S: Stream<Item = Result<Bytes, E>> + Send + Unpin + 'static,
         E: Error + Send + Sync + 'static,
     {
+        let channels = self.channels.unwrap_or(1);
         let handle = self.handle().await?;
 
         let (tx, rx) = mpsc::channel(1);
+        let (control_tx, mut control_rx) = mpsc::unbounded();
         let mut is_done = false;
         let request_id = handle.request_id();
+        let metrics = handle.sender.metrics.clone();
         tokio::task::spawn(async move {
             let mut handle = handle;
             let mut tx = tx;
             let mut stream = stream;
             loop {
                 select_biased! {
+                    // Receiving control messages from StreamControl
+                    message = control_rx.select_next_some() => {
+                        if is_done {
+                            continue;
+                        }
+                        let is_close = message == ControlMessage::CloseStream;
+                        let result = if is_close {
+                            handle.close_stream().await
+                        } else {
+                            handle.sender.send_control_message(message).await
+                        };
+                        if let Err(err) = result {
+                            if tx.send(Err(err)).await.is_err() {
+                                break;
+                            }
+                        }
+                        is_done = is_close;
+                    }
                     // Receiving messages from WebsocketHandle
                     response = handle.receive().fuse() => {
-                        // eprintln!("<stream> got response");
                         match response {
                             Some(Ok(response)) if matches!(response, StreamResponse::TerminalResponse { .. }) => {
-                               // eprintln!( "<stream> got terminal response");
                                 if tx.send(Ok(response)).await.is_err() {
                                     // Receiver has been dropped.
                                     break;
@@ -390,7 +725,6 @@ impl<'a> WebsocketBuilder<'a> {
                                 }
                             }
                             None => {
-                                // eprintln!("<stream> got none from handle");
                                 tx.close_channel();
                                 // No more responses
                                 break;
@@ -400,14 +734,14 @@ impl<'a> WebsocketBuilder<'a> {
                     // Receiving audio data from stream.
                     chunk = stream.next().fuse() => {
                         match chunk {
+                            // Audio after the stream has been closed is dropped.
+                            Some(Ok(_)) if is_done => {}
                             Some(Ok(audio)) => if let Err(err) = handle.send_data(audio.to_vec()).await {
-                                // eprintln!("<stream> got audio");
                                 if tx.send(Err(err)).await.is_err() {
                                     break;
                                 }
                             },
                             Some(Err(err)) => {
-                                // eprintln!("<stream> got error");
                                 if tx.send(Err(DeepgramError::from(Box::new(err) as Box<dyn Error + Send + Sync + 'static>))).await.is_err() {
                                     break;
                                 }
@@ -440,9 +774,28 @@ impl<'a> WebsocketBuilder<'a> {
             rx,
             done: false,
             request_id,
+            control: StreamControl { control_tx },
+            metrics,
+            channels,
         })
     }
 
+    /// Convert `stream` with `transcoder` and stream the result, declaring the
+    /// transcoder's output format to Deepgram.
+    pub async fn stream_transcoded<S, E>(
+        self,
+        stream: S,
+        transcoder: Transcoder,
+    ) -> Result<TranscriptionStream>
+    where
+        S: Stream<Item = Result<Bytes, E>> + Send + Unpin + 'static,
+        E: Error + Send + Sync + 'static,
+    {
+        self.audio_format(transcoder.output_format())
+            .stream(transcoder.transcode_stream(stream))
+            .await
+    }
+
     /// A low level interface to the Deepgram websocket transcription API.
     pub async fn handle(self) -> Result<WebsocketHandle> {
         WebsocketHandle::new(self).await
@@ -459,44 +812,172 @@ macro_rules! send_message {
         }
     };
 }
-async fn run_worker(
-    ws_stream: WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>,
-    mut message_tx: Sender<WsMessage>,
-    mut message_rx: Receiver<WsMessage>,
-    mut response_tx: Sender<Result<StreamResponse>>,
-    keep_alive: bool,
-) -> Result<()> {
+/// Timers used by the websocket worker, configured on [`WebsocketBuilder`].
+#[derive(Debug, Clone, Copy)]
+struct WorkerConfig {
+    keep_alive_interval: Option<Duration>,
+    ping_interval: Option<Duration>,
+    ping_timeout: Duration,
+    idle_timeout: Option<Duration>,
+}
+
+impl WorkerConfig {
+    /// # Errors
+    ///
+    /// Errors with [`DeepgramError::ZeroOption`] if any of the durations is
+    /// zero.
+    fn new(builder: &WebsocketBuilder<'_>) -> Result<Self> {
+        let config = WorkerConfig {
+            keep_alive_interval: builder.keep_alive_interval,
+            ping_interval: builder.ping_interval,
+            ping_timeout: builder.ping_timeout.unwrap_or(DEFAULT_PING_TIMEOUT),
+            idle_timeout: builder.idle_timeout,
+        };
+        for (option, duration) in [
+            ("keep_alive_interval", config.keep_alive_interval),
+            ("ping_interval", config.ping_interval),
+            ("ping_timeout", Some(config.ping_timeout)),
+            ("idle_timeout", config.idle_timeout),
+        ] {
+            if duration.is_some_and(|duration| duration.is_zero()) {
+                return Err(DeepgramError::ZeroOption(option));
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// The size of the buffer set by the builder option `option`, or the default.
+///
+/// # Errors
+///
+/// Errors with [`DeepgramError::ZeroOption`] if the size is zero.
+fn buffer_size(option: &'static str, size: Option<usize>) -> Result<usize> {
+    match size.unwrap_or(DEFAULT_BUFFER_SIZE) {
+        0 => Err(DeepgramError::ZeroOption(option)),
+        size => Ok(size),
+    }
+}
+
+/// Sleep until `deadline`, or forever if there is none.
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => pending().await,
+    }
+}
+
+/// Forwards responses to the handle, recording them if the session is being recorded.
+struct ResponseSender {
+    tx: Sender<Result<StreamResponse>>,
+    recorder: Option<SessionRecorder>,
+}
+
+impl ResponseSender {
+    async fn send(&mut self, response: Result<StreamResponse>) -> Result<(), mpsc::SendError> {
+        if let Some(recorder) = &self.recorder {
+            match &response {
+                Ok(response) => recorder.response(response),
+                Err(err) => recorder.error(err),
+            }
+        }
+        self.tx.send(response).await
+    }
+
+    fn close_channel(&mut self) {
+        self.tx.close_channel();
+    }
+}
+
+async fn run_worker<S>(
+    ws_stream: WebSocketStream<S>,
+    mut message_rx: MessageReceiver,
+    response_tx: Sender<Result<StreamResponse>>,
+    config: WorkerConfig,
+    metrics: Arc<Metrics>,
+    recorder: Option<SessionRecorder>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut response_tx = ResponseSender {
+        tx: response_tx,
+        recorder: recorder.clone(),
+    };
     // We use Vec<u8> for partial frames because we don't know if a fragment of a string is valid utf-8.
     let mut partial_frame: Vec<u8> = Vec::new();
     let (mut ws_stream_send, mut ws_stream_recv) = ws_stream.split();
     let mut is_open: bool = true;
-    let mut last_sent_message = tokio::time::Instant::now();
+    let mut last_sent_message = Instant::now();
+    let mut last_received_message = Instant::now();
+    let mut last_ping = Instant::now();
+    let mut awaiting_pong: Option<Instant> = None;
+    // Used to report the latency of the first transcript.
+    let mut first_audio_sent = None;
+    let mut received_transcript = false;
     loop {
-        // eprintln!("<worker> loop");
-        let sleep = tokio::time::sleep_until(last_sent_message + Duration::from_secs(3));
+        let keep_alive_deadline = config
+            .keep_alive_interval
+            .filter(|_| is_open)
+            .map(|interval| last_sent_message + interval);
+        let ping_deadline = config.ping_interval.map(|interval| match awaiting_pong {
+            Some(sent) => sent + config.ping_timeout,
+            None => last_ping + interval,
+        });
+        let idle_deadline = config
+            .idle_timeout
+            .map(|timeout| last_received_message + timeout);
         // Primary event loop.
         select_biased! {
-            _ = sleep.fuse() => {
-                // eprintln!("<worker> sleep");
-                if keep_alive && is_open {
-                    message_tx.send(WsMessage::ControlMessage(ControlMessage::KeepAlive)).await.expect("we hold the receiver, so we know it hasn't been dropped");
-                    last_sent_message = tokio::time::Instant::now();
-                } else {
-                    pending::<()>().await;
+            _ = sleep_until(idle_deadline).fuse() => {
+                let timeout = config.idle_timeout.unwrap_or_default();
+                instrumentation::event!(WARN, timeout_ms = timeout.as_millis() as u64, "no message received from server");
+                // The connection is assumed to be dead, so it is dropped without closing.
+                let _ = response_tx.send(Err(DeepgramError::IdleTimeout(timeout))).await;
+                return Ok(());
+            }
+            _ = sleep_until(ping_deadline).fuse() => {
+                if awaiting_pong.is_some() {
+                    instrumentation::event!(WARN, timeout_ms = config.ping_timeout.as_millis() as u64, "no pong received from server");
+                    let _ = response_tx.send(Err(DeepgramError::PongTimeout(config.ping_timeout))).await;
+                    return Ok(());
+                }
+                send_message!(ws_stream_send, response_tx, Message::Ping(Vec::new()));
+                last_ping = Instant::now();
+                awaiting_pong = Some(last_ping);
+            }
+            _ = sleep_until(keep_alive_deadline).fuse() => {
+                instrumentation::event!(DEBUG, "sending keep-alive");
+                if let Some(recorder) = &recorder {
+                    recorder.control(&ControlMessage::KeepAlive);
                 }
+                send_message!(ws_stream_send, response_tx, Message::Text(
+                    serde_json::to_string(&ControlMessage::KeepAlive).unwrap_or_default()
+                ));
+                last_sent_message = Instant::now();
             }
             response = ws_stream_recv.next().fuse() => {
+                if let Some(Ok(Message::Text(_) | Message::Frame(_))) = response {
+                    last_received_message = Instant::now();
+                }
                 match response {
                     Some(Ok(Message::Text(response))) => {
-                        // eprintln!("<worker> received dg response");
                         match serde_json::from_str(&response) {
                             Ok(response) => {
+                                metrics.record_response(&response);
+                                if let (StreamResponse::TranscriptResponse { .. }, false, Some(sent)) =
+                                    (&response, received_transcript, first_audio_sent)
+                                {
+                                    received_transcript = true;
+                                    instrumentation::record_latency(sent, "first transcript received");
+                                }
                                 if (response_tx.send(Ok(response)).await).is_err() {
                                     // Responses are no longer being received; close the stream.
                                     break;
                                 }
                             }
                             Err(err) =>{
+                                instrumentation::event!(WARN, error = %err, "failed to parse response");
                                 if (response_tx.send(Err(err.into())).await).is_err() {
                                     // Responses are no longer being received; close the stream.
                                     break;
@@ -509,11 +990,11 @@ async fn run_worker(
                         let _ = ws_stream_send.send(Message::Pong(value)).await;
                     }
                     Some(Ok(Message::Close(None))) => {
-                        // eprintln!("<worker> received websocket close");
+                        instrumentation::event!(INFO, "websocket closed by server");
                         return Ok(());
                     }
                     Some(Ok(Message::Close(Some(closeframe)))) => {
-                        // eprintln!("<worker> received websocket close");
+                        instrumentation::record_close(closeframe.code.into(), &closeframe.reason);
                         return Err(DeepgramError::WebsocketClose {
                             code: closeframe.code.into(),
                             reason: closeframe.reason.into_owned(),
@@ -526,12 +1007,10 @@ async fn run_worker(
                             OpCode::Data(Data::Text) => {
                                 partial_frame.extend(frame.payload());
                             }
-                            OpCode::Data(Data::Continue) => {
-                                // We know we're continuing a text frame because otherwise
-                                // partial_frame would be empty.
-                                if !partial_frame.is_empty() {
-                                    partial_frame.extend(frame.payload())
-                                }
+                            // We know we're continuing a text frame because otherwise
+                            // partial_frame would be empty.
+                            OpCode::Data(Data::Continue) if !partial_frame.is_empty() => {
+                                partial_frame.extend(frame.payload())
                             }
                             _ => {
                                 // Ignore other partial frames.
@@ -539,19 +1018,29 @@ async fn run_worker(
                         }
                         if frame.header().is_final {
                             let response = std::mem::take(&mut partial_frame);
-                            let response = serde_json::from_slice(&response).map_err(|err| err.into());
+                            let response = serde_json::from_slice(&response).map_err(|err| {
+                                instrumentation::event!(WARN, error = %err, "failed to parse response");
+                                err.into()
+                            });
+                            if let Ok(response) = &response {
+                                metrics.record_response(response);
+                            }
                             if (response_tx.send(response).await).is_err() {
                                 // Responses are no longer being received; close the stream.
                                 break
                             }
                         }
                     }
-                    Some(Ok(Message::Binary(_) | Message::Pong(_))) => {
-                        // We don't expect binary messages or pongs from the API.
+                    Some(Ok(Message::Pong(_))) => {
+                        awaiting_pong = None;
+                    }
+                    Some(Ok(Message::Binary(_))) => {
+                        // We don't expect binary messages from the API.
                         // They can be safely ignored.
                     }
 
                     Some(Err(err)) => {
+                        instrumentation::event!(WARN, error = %err, "websocket error");
                         if (response_tx.send(Err(err.into())).await).is_err() {
                             // Responses are no longer being received; close the stream.
                             break;
@@ -560,31 +1049,47 @@ async fn run_worker(
                     }
                     None => {
                         // Upstream is closed
-                        // eprintln!("<worker> received None");
+                        instrumentation::event!(DEBUG, "websocket stream ended");
                         return Ok(())
                     }
                 }
             }
-            message = message_rx.next().fuse() => {
-                // eprintln!("<worker> received message: {message:?}, {is_open:?}");
+            // Messages after the stream is closed are left in the queue.
+            message = async {
+                if is_open { message_rx.recv().await } else { pending().await }
+            }.fuse() => {
                 if is_open {
                     match message {
                         Some(WsMessage::Audio(audio))=> {
+                            instrumentation::event!(TRACE, bytes = audio.len(), "sending audio");
+                            first_audio_sent.get_or_insert_with(Instant::now);
+                            let len = audio.len();
+                            if let Some(recorder) = &recorder {
+                                recorder.audio(&audio);
+                            }
                             send_message!(ws_stream_send, response_tx, Message::Binary(audio.0));
-                            last_sent_message = tokio::time::Instant::now();
+                            metrics.bytes_sent.fetch_add(len as u64, Ordering::Relaxed);
+                            last_sent_message = Instant::now();
 
                         }
                         Some(WsMessage::ControlMessage(msg)) => {
+                            instrumentation::event!(DEBUG, message = ?msg, "sending control message");
+                            if let Some(recorder) = &recorder {
+                                recorder.control(&msg);
+                            }
                             send_message!(ws_stream_send, response_tx, Message::Text(
                                 serde_json::to_string(&msg).unwrap_or_default()
                             ));
-                            last_sent_message = tokio::time::Instant::now();
+                            last_sent_message = Instant::now();
                             if msg == ControlMessage::CloseStream {
                                 is_open = false;
                             }
                         }
                         None => {
                             // Input stream is shut down.  Keep processing responses.
+                            if let Some(recorder) = &recorder {
+                                recorder.control(&ControlMessage::CloseStream);
+                            }
                             send_message!(ws_stream_send, response_tx, Message::Text(
                                 serde_json::to_string(&ControlMessage::CloseStream).unwrap_or_default()
                             ));
@@ -595,7 +1100,6 @@ async fn run_worker(
             }
         };
     }
-    // eprintln!("<worker> post loop");
     if let Err(err) = ws_stream_send
         .send(Message::Text(
             serde_json::to_string(&ControlMessage::CloseStream).unwrap_or_default(),
@@ -606,11 +1110,11 @@ async fn run_worker(
         let _ = response_tx.send(Err(err.into())).await;
     }
     response_tx.close_channel();
-    // Waiting for message_tx to be dropped before exiting
-    while message_rx.next().await.is_some() {
+    // Waiting for the senders to be dropped before exiting
+    while message_rx.recv().await.is_some() {
         // Receiving messages after closing down. Ignore them.
     }
-    // eprintln!("<worker> exit");
+    instrumentation::event!(DEBUG, "websocket worker exited");
     Ok(())
 }
 
@@ -645,35 +1149,47 @@ impl Deref for Audio {
 
 #[derive(Debug)]
 pub struct WebsocketHandle {
-    message_tx: Sender<WsMessage>,
-    response_rx: Receiver<Result<StreamResponse>>,
-    request_id: Uuid,
+    sender: WebsocketSender,
+    receiver: WebsocketReceiver,
 }
 
 impl<'a> WebsocketHandle {
     async fn new(builder: WebsocketBuilder<'a>) -> Result<WebsocketHandle> {
-        let url = builder.as_url()?;
-
-        let request = {
-            let http_builder = Request::builder()
-                .method("GET")
-                .uri(url.to_string())
-                .header("sec-websocket-key", client::generate_key())
-                .header("host", "api.deepgram.com")
-                .header("connection", "upgrade")
-                .header("upgrade", "websocket")
-                .header("sec-websocket-version", "13");
-
-            let builder = if let Some(api_key) = builder.deepgram.api_key.as_deref() {
-                http_builder.header("authorization", format!("Token {}", api_key))
-            } else {
-                http_builder
-            };
-            builder.body(())?
+        let config = WorkerConfig::new(&builder)?;
+        let audio_buffer = buffer_size("audio_buffer", builder.audio_buffer)?;
+        let response_buffer = buffer_size("response_buffer", builder.response_buffer)?;
+        let query = builder.as_url()?.query().map(str::to_owned);
+        let endpoints = &builder.deepgram.endpoints;
+        let order = endpoints.order(&builder.deepgram.policy)?;
+
+        let mut attempts = order.iter().peekable();
+        let (ws_stream, upgrade_response, span, started) = loop {
+            let index = *attempts.next().expect("order is never empty");
+            let mut url = stream_url_for(endpoints.url(index));
+            url.set_query(query.as_deref());
+
+            let span = instrumentation::session_span(&url);
+            let started = Instant::now();
+            match handshake(&builder, &url).instrument(span.clone()).await {
+                Ok((ws_stream, upgrade_response)) => {
+                    endpoints.mark_index(index, true);
+                    break (ws_stream, upgrade_response, span, started);
+                }
+                Err(err) => {
+                    span.in_scope(|| {
+                        instrumentation::record_error(&err, "websocket handshake failed")
+                    });
+                    if !is_endpoint_failure(&err) {
+                        return Err(err.into());
+                    }
+                    endpoints.mark_index(index, false);
+                    if attempts.peek().is_none() {
+                        return Err(err.into());
+                    }
+                }
+            }
         };
 
-        let (ws_stream, upgrade_response) = tokio_tungstenite::connect_async(request).await?;
-
         let request_id = upgrade_response
             .headers()
             .get("dg-request-id")
@@ -687,36 +1203,141 @@ impl<'a> WebsocketHandle {
                 "Received malformed request ID in websocket upgrade headers"
             )))?;
 
-        let (message_tx, message_rx) = mpsc::channel(256);
-        let (response_tx, response_rx) = mpsc::channel(256);
+        span.record("request_id", request_id.to_string());
+        span.in_scope(|| instrumentation::record_latency(started, "websocket connected"));
 
-        tokio::task::spawn({
-            let message_tx = message_tx.clone();
-            run_worker(
-                ws_stream,
-                message_tx,
-                message_rx,
-                response_tx,
-                builder.keep_alive.unwrap_or(false),
-            )
-        });
+        let metrics = Arc::new(Metrics::new(builder.bytes_per_second()));
+        let (message_tx, message_rx) =
+            message_queue::channel(audio_buffer, builder.overflow_policy, metrics.clone());
+        let (response_tx, response_rx) = mpsc::channel(response_buffer);
+
+        let recorder = builder.recorder.clone();
+        if let Some(recorder) = &recorder {
+            recorder.open(request_id, builder.channels.unwrap_or(1));
+        }
+        let worker = run_worker(
+            ws_stream,
+            message_rx,
+            response_tx,
+            config,
+            metrics.clone(),
+            recorder.clone(),
+        );
+        tokio::task::spawn(
+            async move {
+                let result = worker.await;
+                if let Some(recorder) = recorder {
+                    recorder.flush();
+                }
+                result
+            }
+            .instrument(span),
+        );
 
         Ok(WebsocketHandle {
-            message_tx,
-            response_rx,
-            request_id,
+            sender: WebsocketSender {
+                message_tx,
+                request_id,
+                metrics: metrics.clone(),
+            },
+            receiver: WebsocketReceiver {
+                response_rx,
+                request_id,
+                metrics,
+            },
         })
     }
 
     pub async fn send_data(&mut self, data: Vec<u8>) -> Result<()> {
-        let audio = Audio(data);
-        // eprintln!("<handle> sending audio: {audio:?}");
+        self.sender.send_data(data).await
+    }
 
-        self.message_tx
-            .send(WsMessage::Audio(audio))
-            .await
-            .map_err(|err| DeepgramError::InternalClientError(err.into()))?;
-        Ok(())
+    /// Send a Finalize message to the Deepgram API to force the server to process
+    /// all the audio it has already received.
+    pub async fn finalize(&mut self) -> Result<()> {
+        self.sender.finalize().await
+    }
+
+    /// Send a KeepAlive message to the Deepgram API to ensure the connection
+    /// isn't closed due to long idle times.
+    pub async fn keep_alive(&mut self) -> Result<()> {
+        self.sender.keep_alive().await
+    }
+
+    /// Close the websocket stream. No more data should be sent after this is called.
+    pub async fn close_stream(&mut self) -> Result<()> {
+        self.sender.close_stream().await
+    }
+
+    pub async fn receive(&mut self) -> Option<Result<StreamResponse>> {
+        self.receiver.next().await
+    }
+
+    pub fn request_id(&self) -> Uuid {
+        self.sender.request_id
+    }
+
+    /// Returns a snapshot of the buffering and lag metrics for the stream.
+    pub fn metrics(&self) -> StreamMetrics {
+        self.sender.metrics()
+    }
+
+    /// Split the handle into a sender and a receiver that can be used
+    /// independently, for example from separate tasks.
+    ///
+    /// The [`WebsocketSender`] can be cloned to send audio and control
+    /// messages from several places.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use deepgram::{Deepgram, DeepgramError};
+    /// # use futures::StreamExt;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let dg = Deepgram::new("token")?;
+    /// let handle = dg.transcription().stream_request().handle().await?;
+    /// let (mut sender, mut receiver) = handle.split();
+    ///
+    /// tokio::spawn(async move {
+    ///     # let chunks: Vec<Vec<u8>> = Vec::new();
+    ///     for chunk in chunks {
+    ///         sender.send_data(chunk).await?;
+    ///     }
+    ///     sender.finalize().await?;
+    ///     sender.close_stream().await
+    /// });
+    ///
+    /// while let Some(response) = receiver.next().await {
+    ///     println!("{:?}", response?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn split(self) -> (WebsocketSender, WebsocketReceiver) {
+        (self.sender, self.receiver)
+    }
+}
+
+/// The sending half of a [`WebsocketHandle`], created by [`WebsocketHandle::split`].
+///
+/// Clones send to the same websocket.
+#[derive(Debug, Clone)]
+pub struct WebsocketSender {
+    message_tx: MessageSender,
+    request_id: Uuid,
+    metrics: Arc<Metrics>,
+}
+
+impl WebsocketSender {
+    /// Send a chunk of audio.
+    ///
+    /// If the audio buffer is full, this waits, drops the oldest audio or
+    /// returns [`DeepgramError::AudioBufferFull`], depending on the
+    /// [`OverflowPolicy`].
+    pub async fn send_data(&mut self, data: Vec<u8>) -> Result<()> {
+        self.message_tx.send(WsMessage::Audio(Audio(data))).await
     }
 
     /// Send a Finalize message to the Deepgram API to force the server to process
@@ -732,43 +1353,69 @@ impl<'a> WebsocketHandle {
     }
 
     /// Close the websocket stream. No more data should be sent after this is called.
+    ///
+    /// This closes the stream for every clone of the sender.
     pub async fn close_stream(&mut self) -> Result<()> {
         if !self.message_tx.is_closed() {
             self.send_control_message(ControlMessage::CloseStream)
                 .await?;
-            self.message_tx.close_channel();
+            self.message_tx.close();
         }
         Ok(())
     }
 
     async fn send_control_message(&mut self, message: ControlMessage) -> Result<()> {
-        // eprintln!("<handle> sending control message: {message:?}");
         self.message_tx
-            .send(WsMessage::ControlMessage(message.clone()))
+            .send(WsMessage::ControlMessage(message))
             .await
-            .map_err(|err| {
-                // eprintln!("<handle> error sending control message: {message:?}");
-                DeepgramError::InternalClientError(err.into())
-            })?;
-        // eprintln!("<handle> sent control message");
-        Ok(())
     }
 
-    #[allow(clippy::let_and_return)]
-    pub async fn receive(&mut self) -> Option<Result<StreamResponse>> {
-        let resp = self.response_rx.next().await;
-        // eprintln!("<handle> receiving response: {resp:?}");
-        resp
+    /// Returns the Deepgram request ID for the speech-to-text live request.
+    pub fn request_id(&self) -> Uuid {
+        self.request_id
+    }
+
+    /// Returns a snapshot of the buffering and lag metrics for the stream.
+    pub fn metrics(&self) -> StreamMetrics {
+        self.metrics.snapshot()
+    }
+}
+
+/// The receiving half of a [`WebsocketHandle`], created by [`WebsocketHandle::split`].
+///
+/// Yields responses from Deepgram until the websocket is closed.
+#[derive(Debug)]
+#[pin_project]
+pub struct WebsocketReceiver {
+    #[pin]
+    response_rx: Receiver<Result<StreamResponse>>,
+    request_id: Uuid,
+    metrics: Arc<Metrics>,
+}
+
+impl Stream for WebsocketReceiver {
+    type Item = Result<StreamResponse>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().response_rx.poll_next(cx)
     }
+}
 
+impl WebsocketReceiver {
+    /// Returns the Deepgram request ID for the speech-to-text live request.
     pub fn request_id(&self) -> Uuid {
         self.request_id
     }
+
+    /// Returns a snapshot of the buffering and lag metrics for the stream.
+    pub fn metrics(&self) -> StreamMetrics {
+        self.metrics.snapshot()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
 #[serde(tag = "type")]
-enum ControlMessage {
+pub(super) enum ControlMessage {
     Finalize,
     KeepAlive,
     CloseStream,
@@ -781,6 +1428,9 @@ pub struct TranscriptionStream {
     rx: Receiver<Result<StreamResponse>>,
     done: bool,
     request_id: Uuid,
+    control: StreamControl,
+    metrics: Arc<Metrics>,
+    channels: u16,
 }
 
 impl Stream for TranscriptionStream {
@@ -793,6 +1443,25 @@ impl Stream for TranscriptionStream {
 }
 
 impl TranscriptionStream {
+    /// A stream of `responses` that isn't connected to Deepgram, used to
+    /// replay recordings.
+    pub(super) fn replayed(
+        rx: Receiver<Result<StreamResponse>>,
+        request_id: Uuid,
+        channels: u16,
+    ) -> Self {
+        // Control messages go nowhere.
+        let (control_tx, _) = mpsc::unbounded();
+        TranscriptionStream {
+            rx,
+            done: false,
+            request_id,
+            control: StreamControl { control_tx },
+            metrics: Arc::default(),
+            channels,
+        }
+    }
+
     /// Returns the Deepgram request ID for the speech-to-text live request.
     ///
     /// A request ID needs to be provided to Deepgram as part of any support
@@ -800,6 +1469,546 @@ impl TranscriptionStream {
     pub fn request_id(&self) -> Uuid {
         self.request_id
     }
+
+    /// Returns a handle for sending control messages on this stream.
+    ///
+    /// Errors from sending the messages are reported through the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use bytes::Bytes;
+    /// # use deepgram::{Deepgram, DeepgramError};
+    /// # use futures::{channel::mpsc, StreamExt};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let dg = Deepgram::new("token")?;
+    /// # let (_audio_tx, audio) = mpsc::channel::<Result<Bytes, DeepgramError>>(1);
+    /// let mut results = dg.transcription().stream_request().stream(audio).await?;
+    /// let control = results.control();
+    ///
+    /// // For example, when a push-to-talk button is released:
+    /// control.finalize()?;
+    ///
+    /// while let Some(response) = results.next().await {
+    ///     println!("{:?}", response?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn control(&self) -> StreamControl {
+        self.control.clone()
+    }
+
+    /// Returns a snapshot of the buffering and lag metrics for the stream.
+    pub fn metrics(&self) -> StreamMetrics {
+        self.metrics.snapshot()
+    }
+
+    /// Wait for the stream to finish and fold its final transcripts into the
+    /// shape of a pre-recorded [`Response`].
+    ///
+    /// See [`Response::from_stream_responses`]. Stops at the first error.
+    /// The stream finishes once the audio has ended or
+    /// [`StreamControl::close_stream`] is called.
+    pub async fn into_response(mut self) -> Result<Response> {
+        let mut responses = Vec::new();
+        while let Some(response) = self.next().await {
+            responses.push(response?);
+        }
+
+        let mut response = Response::from_stream_responses(responses);
+        if response.metadata.request_id.is_nil() {
+            response.metadata.request_id = self.request_id;
+        }
+        Ok(response)
+    }
+
+    /// Split the stream into one stream per audio channel, as set with
+    /// [`WebsocketBuilder::channels`].
+    ///
+    /// Transcripts, speech started and utterance end responses are routed to
+    /// the stream for their channel. Other responses are yielded by every
+    /// stream. Errors are also yielded by every stream: the first stream
+    /// receives the original error and the others a
+    /// [`DeepgramError::InternalClientError`] describing it.
+    ///
+    /// Each channel is buffered separately, but a channel that isn't being
+    /// read eventually holds up the others. Dropped streams are skipped.
+    /// Take a [`control`](Self::control) handle first if one is needed.
+    ///
+    /// The `multichannel` option must be set for Deepgram to transcribe
+    /// each channel separately.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use bytes::Bytes;
+    /// # use deepgram::{
+    /// #     common::options::{Encoding, Options},
+    /// #     listen::audio::{interleave_streams, SampleFormat},
+    /// #     Deepgram, DeepgramError,
+    /// # };
+    /// # use futures::{channel::mpsc, StreamExt};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let dg = Deepgram::new("token")?;
+    /// # let (_agent_tx, agent) = mpsc::channel::<Result<Bytes, DeepgramError>>(1);
+    /// # let (_customer_tx, customer) = mpsc::channel::<Result<Bytes, DeepgramError>>(1);
+    /// let options = Options::builder().multichannel(true).build();
+    /// let results = dg
+    ///     .transcription()
+    ///     .stream_request_with_options(options)
+    ///     .encoding(Encoding::Mulaw)
+    ///     .sample_rate(8000)
+    ///     .channels(2)
+    ///     .stream(interleave_streams(SampleFormat::Mulaw, agent, customer))
+    ///     .await?;
+    ///
+    /// let mut channels = results.by_channel().into_iter();
+    /// let (mut agent, mut customer) = (channels.next().unwrap(), channels.next().unwrap());
+    /// tokio::spawn(async move {
+    ///     while let Some(response) = customer.next().await {
+    ///         println!("customer: {:?}", response);
+    ///     }
+    /// });
+    /// while let Some(response) = agent.next().await {
+    ///     println!("agent: {:?}", response?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn by_channel(self) -> Vec<ChannelStream> {
+        let request_id = self.request_id;
+        let (senders, streams) = (0..usize::from(self.channels.max(1)))
+            .map(|channel| {
+                let (tx, rx) = mpsc::channel(DEFAULT_BUFFER_SIZE);
+                (
+                    tx,
+                    ChannelStream {
+                        rx,
+                        channel,
+                        request_id,
+                    },
+                )
+            })
+            .unzip();
+        tokio::task::spawn(demultiplex(self, senders));
+        streams
+    }
+}
+
+/// Route each response to the sender for its channel.
+async fn demultiplex(
+    mut stream: TranscriptionStream,
+    mut channels: Vec<Sender<Result<StreamResponse>>>,
+) {
+    while let Some(response) = stream.next().await {
+        let channel = match &response {
+            Ok(StreamResponse::TranscriptResponse { channel_index, .. }) => channel_index
+                .first()
+                .and_then(|&index| usize::try_from(index).ok()),
+            Ok(
+                StreamResponse::SpeechStartedResponse { channel, .. }
+                | StreamResponse::UtteranceEndResponse { channel, .. },
+            ) => channel.first().map(|&index| usize::from(index)),
+            _ => None,
+        };
+
+        // Send errors only fail when the stream has been dropped, which is fine.
+        match (channel.and_then(|index| channels.get_mut(index)), response) {
+            (Some(tx), response) => {
+                let _ = tx.send(response).await;
+            }
+            (None, Ok(response)) => {
+                for tx in &mut channels[1..] {
+                    let _ = tx.send(Ok(response.clone())).await;
+                }
+                let _ = channels[0].send(Ok(response)).await;
+            }
+            (None, Err(err)) => {
+                for tx in &mut channels[1..] {
+                    let _ = tx
+                        .send(Err(DeepgramError::InternalClientError(anyhow!(
+                            err.to_string()
+                        ))))
+                        .await;
+                }
+                let _ = channels[0].send(Err(err)).await;
+            }
+        }
+
+        if channels.iter().all(Sender::is_closed) {
+            break;
+        }
+    }
+}
+
+/// The responses for a single audio channel of a [`TranscriptionStream`].
+///
+/// Created by [`TranscriptionStream::by_channel`].
+#[derive(Debug)]
+#[pin_project]
+pub struct ChannelStream {
+    #[pin]
+    rx: Receiver<Result<StreamResponse>>,
+    channel: usize,
+    request_id: Uuid,
+}
+
+impl Stream for ChannelStream {
+    type Item = Result<StreamResponse>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().rx.poll_next(cx)
+    }
+}
+
+impl ChannelStream {
+    /// The index of the audio channel, starting from 0.
+    pub fn channel(&self) -> usize {
+        self.channel
+    }
+
+    /// Returns the Deepgram request ID for the speech-to-text live request.
+    pub fn request_id(&self) -> Uuid {
+        self.request_id
+    }
+}
+
+/// Sends control messages on a [`TranscriptionStream`], created by
+/// [`TranscriptionStream::control`].
+///
+/// Messages are sent in order with the audio being streamed. Clones control
+/// the same stream.
+#[derive(Debug, Clone)]
+pub struct StreamControl {
+    control_tx: UnboundedSender<ControlMessage>,
+}
+
+impl StreamControl {
+    /// Send a Finalize message to the Deepgram API to force the server to process
+    /// all the audio it has already received.
+    pub fn finalize(&self) -> Result<()> {
+        self.send(ControlMessage::Finalize)
+    }
+
+    /// Send a KeepAlive message to the Deepgram API to ensure the connection
+    /// isn't closed due to long idle times.
+    pub fn keep_alive(&self) -> Result<()> {
+        self.send(ControlMessage::KeepAlive)
+    }
+
+    /// Close the websocket stream. Any audio streamed after this is dropped,
+    /// and the remaining responses are received as usual.
+    pub fn close_stream(&self) -> Result<()> {
+        self.send(ControlMessage::CloseStream)
+    }
+
+    /// Returns an error if the stream has already finished.
+    fn send(&self, message: ControlMessage) -> Result<()> {
+        self.control_tx
+            .unbounded_send(message)
+            .map_err(|err| DeepgramError::InternalClientError(err.into()))
+    }
+}
+
+mod message_queue {
+    //! The queue of audio and control messages waiting to be sent.
+    //!
+    //! Unlike a channel, the queue can drop its oldest audio when it is full.
+    //! Control messages don't count towards the capacity and are never dropped.
+
+    use std::{
+        collections::VecDeque,
+        pin::pin,
+        sync::{atomic::Ordering, Arc, Mutex, MutexGuard},
+    };
+
+    use anyhow::anyhow;
+    use tokio::sync::Notify;
+
+    use super::{Metrics, OverflowPolicy, WsMessage};
+    use crate::{DeepgramError, Result};
+
+    pub(super) fn channel(
+        capacity: usize,
+        policy: OverflowPolicy,
+        metrics: Arc<Metrics>,
+    ) -> (MessageSender, MessageReceiver) {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                messages: VecDeque::new(),
+                audio_frames: 0,
+                senders: 1,
+                closed: false,
+            }),
+            capacity,
+            policy,
+            readable: Notify::new(),
+            writable: Notify::new(),
+            metrics,
+        });
+        (
+            MessageSender {
+                shared: shared.clone(),
+            },
+            MessageReceiver { shared },
+        )
+    }
+
+    #[derive(Debug)]
+    struct Shared {
+        state: Mutex<State>,
+        capacity: usize,
+        policy: OverflowPolicy,
+        readable: Notify,
+        writable: Notify,
+        metrics: Arc<Metrics>,
+    }
+
+    #[derive(Debug)]
+    struct State {
+        messages: VecDeque<WsMessage>,
+        audio_frames: usize,
+        senders: usize,
+        closed: bool,
+    }
+
+    impl Shared {
+        fn state(&self) -> MutexGuard<'_, State> {
+            // The state is never left inconsistent, so a poisoned lock can be reused.
+            self.state
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+        }
+
+        fn close(&self) {
+            self.state().closed = true;
+            self.readable.notify_one();
+            self.writable.notify_waiters();
+        }
+    }
+
+    #[derive(Debug)]
+    pub(super) struct MessageSender {
+        shared: Arc<Shared>,
+    }
+
+    impl MessageSender {
+        pub(super) async fn send(&self, mut message: WsMessage) -> Result<()> {
+            loop {
+                // Register for notifications before checking the state, so
+                // that room made in between isn't missed.
+                let mut writable = pin!(self.shared.writable.notified());
+                writable.as_mut().enable();
+                match self.try_push(message)? {
+                    None => {
+                        self.shared.readable.notify_one();
+                        return Ok(());
+                    }
+                    Some(full) => {
+                        message = full;
+                        writable.await;
+                    }
+                }
+            }
+        }
+
+        /// Push `message`, or hand it back if it has to wait for room.
+        fn try_push(&self, message: WsMessage) -> Result<Option<WsMessage>> {
+            let shared = &*self.shared;
+            let mut state = shared.state();
+            if state.closed {
+                return Err(DeepgramError::InternalClientError(anyhow!(
+                    "the websocket stream is closed"
+                )));
+            }
+
+            if let WsMessage::Audio(audio) = &message {
+                if state.audio_frames >= shared.capacity {
+                    match shared.policy {
+                        OverflowPolicy::Block => return Ok(Some(message)),
+                        OverflowPolicy::DropOldest => {
+                            let oldest = state
+                                .messages
+                                .iter()
+                                .position(|message| matches!(message, WsMessage::Audio(_)))
+                                .and_then(|index| state.messages.remove(index));
+                            if let Some(WsMessage::Audio(oldest)) = oldest {
+                                state.audio_frames -= 1;
+                                let len = oldest.len() as u64;
+                                let metrics = &shared.metrics;
+                                metrics.bytes_queued.fetch_sub(len, Ordering::Relaxed);
+                                metrics.bytes_dropped.fetch_add(len, Ordering::Relaxed);
+                                metrics.frames_dropped.fetch_add(1, Ordering::Relaxed);
+                            }
+                        }
+                        OverflowPolicy::Error => return Err(DeepgramError::AudioBufferFull),
+                    }
+                }
+                state.audio_frames += 1;
+                shared
+                    .metrics
+                    .bytes_queued
+                    .fetch_add(audio.len() as u64, Ordering::Relaxed);
+            }
+
+            state.messages.push_back(message);
+            Ok(None)
+        }
+
+        /// Close the queue for every sender. Queued messages are still received.
+        pub(super) fn close(&self) {
+            self.shared.close();
+        }
+
+        pub(super) fn is_closed(&self) -> bool {
+            self.shared.state().closed
+        }
+    }
+
+    impl Clone for MessageSender {
+        fn clone(&self) -> Self {
+            self.shared.state().senders += 1;
+            MessageSender {
+                shared: self.shared.clone(),
+            }
+        }
+    }
+
+    impl Drop for MessageSender {
+        fn drop(&mut self) {
+            let mut state = self.shared.state();
+            state.senders -= 1;
+            if state.senders == 0 {
+                drop(state);
+                self.shared.readable.notify_one();
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub(super) struct MessageReceiver {
+        shared: Arc<Shared>,
+    }
+
+    impl MessageReceiver {
+        /// Receive the next message, or [`None`] once the queue is empty and
+        /// closed or every sender has been dropped.
+        pub(super) async fn recv(&mut self) -> Option<WsMessage> {
+            let shared = &*self.shared;
+            loop {
+                let mut readable = pin!(shared.readable.notified());
+                readable.as_mut().enable();
+                {
+                    let mut state = shared.state();
+                    if let Some(message) = state.messages.pop_front() {
+                        if let WsMessage::Audio(audio) = &message {
+                            state.audio_frames -= 1;
+                            shared
+                                .metrics
+                                .bytes_queued
+                                .fetch_sub(audio.len() as u64, Ordering::Relaxed);
+                        }
+                        drop(state);
+                        shared.writable.notify_one();
+                        return Some(message);
+                    }
+                    if state.closed || state.senders == 0 {
+                        return None;
+                    }
+                }
+                readable.await;
+            }
+        }
+    }
+
+    impl Drop for MessageReceiver {
+        fn drop(&mut self) {
+            // Nothing will be received, so waiting senders must give up.
+            self.shared.close();
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::sync::Arc;
+
+        use super::channel;
+        use crate::{
+            listen::websocket::{Audio, ControlMessage, Metrics, OverflowPolicy, WsMessage},
+            DeepgramError,
+        };
+
+        fn audio(byte: u8) -> WsMessage {
+            WsMessage::Audio(Audio(vec![byte; 2]))
+        }
+
+        #[tokio::test]
+        async fn drop_oldest_keeps_control_messages() {
+            let metrics = Arc::new(Metrics::default());
+            let (tx, mut rx) = channel(2, OverflowPolicy::DropOldest, metrics.clone());
+            tx.send(audio(1)).await.unwrap();
+            tx.send(WsMessage::ControlMessage(ControlMessage::Finalize))
+                .await
+                .unwrap();
+            tx.send(audio(2)).await.unwrap();
+            tx.send(audio(3)).await.unwrap();
+
+            let snapshot = metrics.snapshot();
+            assert_eq!(snapshot.bytes_queued, 4);
+            assert_eq!(snapshot.bytes_dropped, 2);
+            assert_eq!(snapshot.frames_dropped, 1);
+
+            drop(tx);
+            let mut received = Vec::new();
+            while let Some(message) = rx.recv().await {
+                received.push(message);
+            }
+            assert_eq!(
+                received,
+                vec![
+                    WsMessage::ControlMessage(ControlMessage::Finalize),
+                    audio(2),
+                    audio(3)
+                ]
+            );
+            assert_eq!(metrics.snapshot().bytes_queued, 0);
+        }
+
+        #[tokio::test]
+        async fn error_when_full() {
+            let (tx, _rx) = channel(1, OverflowPolicy::Error, Arc::default());
+            tx.send(audio(1)).await.unwrap();
+            assert!(matches!(
+                tx.send(audio(2)).await,
+                Err(DeepgramError::AudioBufferFull)
+            ));
+        }
+
+        #[tokio::test]
+        async fn block_until_received() {
+            let (tx, mut rx) = channel(1, OverflowPolicy::Block, Arc::default());
+            tx.send(audio(1)).await.unwrap();
+
+            let sender = tokio::spawn({
+                let tx = tx.clone();
+                async move { tx.send(audio(2)).await }
+            });
+            tokio::task::yield_now().await;
+            assert!(!sender.is_finished());
+
+            assert_eq!(rx.recv().await, Some(audio(1)));
+            sender.await.unwrap().unwrap();
+            assert_eq!(rx.recv().await, Some(audio(2)));
+
+            tx.close();
+            assert_eq!(rx.recv().await, None);
+        }
+    }
 }
 
 mod file_chunker {
@@ -868,8 +2077,22 @@ mod file_chunker {
 
 #[cfg(test)]
 mod tests {
-    use super::ControlMessage;
-    use crate::common::options::Options;
+    use futures::{channel::mpsc, SinkExt, StreamExt};
+    use uuid::Uuid;
+
+    use std::time::Duration;
+
+    use tokio_tungstenite::{tungstenite::protocol::Role, WebSocketStream};
+
+    use std::sync::{atomic::Ordering, Arc};
+
+    use super::{
+        message_queue, run_worker, Audio, ControlMessage, Metrics, OverflowPolicy, StreamControl,
+        TranscriptionStream, WebsocketHandle, WebsocketReceiver, WebsocketSender, WorkerConfig,
+        WsMessage,
+    };
+    use crate::common::options::{Encoding, Options};
+    use crate::common::stream_response::StreamResponse;
 
     #[test]
     fn test_stream_url() {
@@ -890,6 +2113,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn host_header() {
+        let dg = crate::Deepgram::new("token").unwrap();
+        assert_eq!(
+            super::host_header(&dg.transcription().listen_stream_url()),
+            "api.deepgram.com"
+        );
+
+        let dg = crate::Deepgram::with_base_url("http://localhost:8080").unwrap();
+        assert_eq!(
+            super::host_header(&dg.transcription().listen_stream_url()),
+            "localhost:8080"
+        );
+    }
+
+    #[tokio::test]
+    #[allow(clippy::result_large_err)]
+    async fn handshake_fails_over() {
+        use tokio::net::TcpListener;
+        use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let add_request_id = |_: &Request, mut response: Response| {
+                response
+                    .headers_mut()
+                    .insert("dg-request-id", Uuid::nil().to_string().parse().unwrap());
+                Ok(response)
+            };
+            let _ws = tokio_tungstenite::accept_hdr_async(socket, add_request_id)
+                .await
+                .unwrap();
+        });
+
+        let dg = crate::Deepgram::with_base_urls(["http://127.0.0.1:1", &format!("http://{addr}")])
+            .unwrap();
+        let handle = dg.transcription().stream_request().handle().await.unwrap();
+        assert_eq!(handle.request_id(), Uuid::nil());
+
+        // The endpoint that was down is tried last until it recovers.
+        assert_eq!(dg.endpoints.order(&dg.policy).unwrap(), vec![1, 0]);
+    }
+
     #[test]
     fn query_escaping() {
         let dg = crate::Deepgram::new("token").unwrap();
@@ -906,4 +2174,233 @@ mod tests {
             r#"{"type":"CloseStream"}"#
         );
     }
+
+    #[tokio::test]
+    async fn split_halves_are_independent() {
+        let metrics = Arc::new(Metrics::default());
+        let (message_tx, mut message_rx) =
+            message_queue::channel(8, OverflowPolicy::Block, metrics.clone());
+        let (mut response_tx, response_rx) = mpsc::channel(8);
+        let request_id = Uuid::nil();
+        let handle = WebsocketHandle {
+            sender: WebsocketSender {
+                message_tx,
+                request_id,
+                metrics: metrics.clone(),
+            },
+            receiver: WebsocketReceiver {
+                response_rx,
+                request_id,
+                metrics,
+            },
+        };
+
+        let (mut sender, mut receiver) = handle.split();
+        let mut other = sender.clone();
+        sender.send_data(vec![1]).await.unwrap();
+        other.send_data(vec![2]).await.unwrap();
+        other.close_stream().await.unwrap();
+        assert!(sender.send_data(vec![3]).await.is_err());
+
+        let mut messages = Vec::new();
+        while let Some(message) = message_rx.recv().await {
+            messages.push(message);
+        }
+        assert_eq!(
+            messages,
+            vec![
+                WsMessage::Audio(Audio(vec![1])),
+                WsMessage::Audio(Audio(vec![2])),
+                WsMessage::ControlMessage(ControlMessage::CloseStream),
+            ]
+        );
+
+        response_tx
+            .send(Err(crate::DeepgramError::InternalClientError(
+                anyhow::anyhow!("closed"),
+            )))
+            .await
+            .unwrap();
+        drop(response_tx);
+        assert!(matches!(receiver.next().await, Some(Err(_))));
+        assert!(receiver.next().await.is_none());
+        assert_eq!(receiver.request_id(), request_id);
+    }
+
+    #[test]
+    fn stream_control_sends_in_order() {
+        let (control_tx, mut control_rx) = mpsc::unbounded();
+        let control = StreamControl { control_tx };
+
+        control.keep_alive().unwrap();
+        control.clone().finalize().unwrap();
+        control.close_stream().unwrap();
+        assert_eq!(
+            std::iter::from_fn(|| control_rx.try_recv().ok()).collect::<Vec<_>>(),
+            vec![
+                ControlMessage::KeepAlive,
+                ControlMessage::Finalize,
+                ControlMessage::CloseStream
+            ]
+        );
+
+        drop(control_rx);
+        assert!(control.finalize().is_err());
+    }
+
+    /// Run a worker against a server that accepts the connection but never responds.
+    async fn silent_server_error(config: WorkerConfig) -> crate::DeepgramError {
+        let (client, _server) = tokio::io::duplex(1024);
+        let ws_stream = WebSocketStream::from_raw_socket(client, Role::Client, None).await;
+        let (_message_tx, message_rx) =
+            message_queue::channel(8, OverflowPolicy::Block, Arc::default());
+        let (response_tx, mut response_rx) = mpsc::channel(8);
+        tokio::spawn(run_worker(
+            ws_stream,
+            message_rx,
+            response_tx,
+            config,
+            Arc::default(),
+            None,
+        ));
+
+        let response = tokio::time::timeout(Duration::from_secs(5), response_rx.next())
+            .await
+            .expect("worker should time out first");
+        match response {
+            Some(Err(err)) => err,
+            other => panic!("expected an error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn idle_timeout() {
+        let err = silent_server_error(WorkerConfig {
+            keep_alive_interval: None,
+            ping_interval: None,
+            ping_timeout: Duration::from_secs(10),
+            idle_timeout: Some(Duration::from_millis(50)),
+        })
+        .await;
+        assert!(
+            matches!(err, crate::DeepgramError::IdleTimeout(timeout) if timeout == Duration::from_millis(50))
+        );
+    }
+
+    #[tokio::test]
+    async fn pong_timeout() {
+        let err = silent_server_error(WorkerConfig {
+            keep_alive_interval: Some(Duration::from_millis(10)),
+            ping_interval: Some(Duration::from_millis(20)),
+            ping_timeout: Duration::from_millis(50),
+            idle_timeout: None,
+        })
+        .await;
+        assert!(
+            matches!(err, crate::DeepgramError::PongTimeout(timeout) if timeout == Duration::from_millis(50))
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_zero_options() {
+        // Nothing listens on port 1 of the loopback address, so only a
+        // rejected option fails before connecting.
+        let dg = crate::Deepgram::with_base_url("http://127.0.0.1:1").unwrap();
+        let transcription = dg.transcription();
+        let builders = [
+            (
+                "keep_alive_interval",
+                transcription
+                    .stream_request()
+                    .keep_alive_interval(Duration::ZERO),
+            ),
+            (
+                "ping_interval",
+                transcription.stream_request().ping_interval(Duration::ZERO),
+            ),
+            (
+                "ping_timeout",
+                transcription.stream_request().ping_timeout(Duration::ZERO),
+            ),
+            (
+                "idle_timeout",
+                transcription.stream_request().idle_timeout(Duration::ZERO),
+            ),
+        ];
+        for (option, builder) in builders {
+            assert!(matches!(
+                builder.handle().await,
+                Err(crate::DeepgramError::ZeroOption(name)) if name == option
+            ));
+        }
+    }
+
+    #[test]
+    fn lag_from_bytes_sent() {
+        let dg = crate::Deepgram::new("token").unwrap();
+        let transcription = dg.transcription();
+        let builder = transcription
+            .stream_request()
+            .encoding(Encoding::Linear16)
+            .sample_rate(16000)
+            .channels(2);
+        let metrics = Metrics::new(builder.bytes_per_second());
+        metrics.bytes_sent.store(64000 * 3, Ordering::Relaxed);
+        metrics
+            .transcript_cursor_micros
+            .store(2_500_000, Ordering::Relaxed);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.audio_sent, Some(Duration::from_secs(3)));
+        assert_eq!(snapshot.lag(), Some(Duration::from_millis(500)));
+
+        let unknown = Metrics::new(transcription.stream_request().bytes_per_second());
+        assert_eq!(unknown.snapshot().lag(), None);
+    }
+
+    #[tokio::test]
+    async fn by_channel_routes_responses() {
+        let (mut tx, rx) = mpsc::channel(8);
+        let (control_tx, _control_rx) = mpsc::unbounded();
+        let stream = TranscriptionStream {
+            rx,
+            done: false,
+            request_id: Uuid::nil(),
+            control: StreamControl { control_tx },
+            metrics: Arc::default(),
+            channels: 2,
+        };
+        let utterance_end = |channel| StreamResponse::UtteranceEndResponse {
+            type_field: "UtteranceEnd".to_string(),
+            channel: vec![channel, 2],
+            last_word_end: 1.0,
+        };
+
+        tx.send(Ok(utterance_end(1))).await.unwrap();
+        tx.send(Ok(utterance_end(0))).await.unwrap();
+        tx.send(Err(crate::DeepgramError::AudioBufferFull))
+            .await
+            .unwrap();
+        drop(tx);
+
+        let mut channels = stream.by_channel();
+        assert_eq!(channels.len(), 2);
+        let first: Vec<_> = channels.remove(0).collect().await;
+        let second: Vec<_> = channels.remove(0).collect().await;
+
+        assert!(matches!(
+            &first[..],
+            [
+                Ok(StreamResponse::UtteranceEndResponse { channel, .. }),
+                Err(crate::DeepgramError::AudioBufferFull)
+            ] if channel[0] == 0
+        ));
+        assert!(matches!(
+            &second[..],
+            [
+                Ok(StreamResponse::UtteranceEndResponse { channel, .. }),
+                Err(crate::DeepgramError::InternalClientError(_))
+            ] if channel[0] == 1
+        ));
+    }
 }
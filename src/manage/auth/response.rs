@@ -0,0 +1,43 @@
+//! Deepgram auth API response types.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{common::timestamp::Timestamp, manage::scopes::Scope};
+
+/// Returned by [`Auth::token_details`](super::Auth::token_details).
+///
+/// See the [Deepgram API Reference][api] for more info.
+///
+/// [api]: https://developers.deepgram.com/reference/get-token-details
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct TokenDetails {
+    #[allow(missing_docs)]
+    pub uuid: Uuid,
+
+    #[allow(missing_docs)]
+    pub project_uuid: Uuid,
+
+    #[allow(missing_docs)]
+    #[serde(default)]
+    pub username: Option<String>,
+
+    #[allow(missing_docs)]
+    pub scopes: Vec<Scope>,
+
+    #[allow(missing_docs)]
+    #[serde(default)]
+    pub created: Option<Timestamp>,
+
+    #[allow(missing_docs)]
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+
+    /// Whether the client authenticated with a temporary token rather than an
+    /// API key.
+    ///
+    /// This is determined by the client and is not part of the API response.
+    #[serde(skip)]
+    pub temporary: bool,
+}
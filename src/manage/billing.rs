@@ -5,11 +5,15 @@
 //! [api]: https://developers.deepgram.com/api-reference/#billing
 
 use crate::{
-    manage::billing::response::{Balance, Balances},
-    send_and_translate_response, Deepgram,
+    manage::billing::{
+        response::{Balance, Balances},
+        watcher::BalanceWatcherBuilder,
+    },
+    send_and_translate_management_response, Deepgram,
 };
 
 pub mod response;
+pub mod watcher;
 
 /// Get the outstanding balances for a Deepgram Project.
 ///
@@ -67,12 +71,9 @@ impl Billing<'_> {
     /// # }
     /// ```
     pub async fn list_balance(&self, project_id: &str) -> crate::Result<Balances> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/balances",
-            project_id,
-        );
+        let url = self.0.manage_url(["projects", project_id, "balances"]);
 
-        send_and_translate_response(self.0.client.get(url)).await
+        send_and_translate_management_response(self.0, self.0.client.get(url)).await
     }
 
     /// Get the details of a specific balance.
@@ -111,12 +112,69 @@ impl Billing<'_> {
     /// # }
     /// ```
     pub async fn get_balance(&self, project_id: &str, balance_id: &str) -> crate::Result<Balance> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/balances/{}",
-            project_id, balance_id,
-        );
+        let url = self
+            .0
+            .manage_url(["projects", project_id, "balances", balance_id]);
+
+        send_and_translate_management_response(self.0, self.0.client.get(url)).await
+    }
 
-        send_and_translate_response(self.0.client.get(url)).await
+    /// Watch the balances of the specified projects and raise alerts when
+    /// they run low or are projected to run out.
+    ///
+    /// The burn rate is estimated from recent usage with
+    /// [`Usage::get_usage`](crate::manage::usage::Usage::get_usage).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{env, time::Duration};
+    /// #
+    /// # use deepgram::{
+    /// #     manage::billing::watcher::{BalanceEvent, Threshold},
+    /// #     Deepgram, DeepgramError,
+    /// # };
+    /// # use futures::StreamExt;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// # let project_id =
+    /// #     env::var("DEEPGRAM_PROJECT_ID").expect("DEEPGRAM_PROJECT_ID environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let mut events = dg_client
+    ///     .billing()
+    ///     .watch([project_id])
+    ///     .low_balance(Threshold::usd(50.0))
+    ///     .depletion_warning(Duration::from_secs(3 * 24 * 60 * 60))
+    ///     .usd_per_hour(0.258)
+    ///     .start()?;
+    ///
+    /// while let Some(event) = events.next().await {
+    ///     match event? {
+    ///         BalanceEvent::LowBalance { project_id, balance, .. } => {
+    ///             eprintln!("{project_id}: only {} {:?} left", balance.amount, balance.units);
+    ///         }
+    ///         event => eprintln!("{event:?}"),
+    ///     }
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch<I>(&self, project_ids: I) -> BalanceWatcherBuilder
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        BalanceWatcherBuilder::new(
+            self.0.clone(),
+            project_ids.into_iter().map(Into::into).collect(),
+        )
     }
 }
 
@@ -0,0 +1,550 @@
+//! Watch project balances and raise alerts before credit runs out.
+//!
+//! See [`Billing::watch`](super::Billing::watch).
+
+use std::{
+    collections::HashMap,
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use futures::{stream, Stream, StreamExt};
+use tokio::time::{self, Interval, MissedTickBehavior};
+use uuid::Uuid;
+
+use super::response::{Balance, BillingUnits};
+use crate::{manage::usage::get_usage_options, Deepgram, DeepgramError};
+
+/// How often balances are polled unless set with
+/// [`BalanceWatcherBuilder::interval`].
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// How much recent usage the burn rate is estimated from unless set with
+/// [`BalanceWatcherBuilder::usage_window`].
+const DEFAULT_USAGE_WINDOW: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// A balance amount in either of the [`BillingUnits`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[non_exhaustive]
+pub struct Threshold {
+    #[allow(missing_docs)]
+    pub amount: f64,
+
+    #[allow(missing_docs)]
+    pub units: BillingUnits,
+}
+
+impl Threshold {
+    /// A threshold in US dollars.
+    pub fn usd(amount: f64) -> Self {
+        Self {
+            amount,
+            units: BillingUnits::Usd,
+        }
+    }
+
+    /// A threshold in hours of audio.
+    pub fn hours(amount: f64) -> Self {
+        Self {
+            amount,
+            units: BillingUnits::Hour,
+        }
+    }
+}
+
+/// An alert raised by a [`BalanceWatcher`].
+///
+/// Each alert is raised once when a balance crosses into it, and again only
+/// after the balance has [recovered](BalanceEvent::Recovered).
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum BalanceEvent {
+    /// The balance is at or below the threshold set with
+    /// [`BalanceWatcherBuilder::low_balance`].
+    LowBalance {
+        #[allow(missing_docs)]
+        project_id: String,
+
+        #[allow(missing_docs)]
+        balance: Balance,
+
+        #[allow(missing_docs)]
+        threshold: Threshold,
+    },
+
+    /// At the current burn rate, the balance will run out within the warning
+    /// period set with [`BalanceWatcherBuilder::depletion_warning`].
+    DepletionProjected {
+        #[allow(missing_docs)]
+        project_id: String,
+
+        #[allow(missing_docs)]
+        balance: Balance,
+
+        /// The average hours of audio processed per day over the usage
+        /// window.
+        hours_per_day: f64,
+
+        /// When the balance is projected to run out.
+        depleted_at: SystemTime,
+    },
+
+    /// A balance that previously raised an alert no longer meets any of
+    /// the alert conditions.
+    Recovered {
+        #[allow(missing_docs)]
+        project_id: String,
+
+        #[allow(missing_docs)]
+        balance: Balance,
+    },
+}
+
+/// Builds a [`BalanceWatcher`] using [the Builder pattern][builder].
+///
+/// Constructed using [`Billing::watch`](super::Billing::watch).
+///
+/// [builder]: https://rust-unofficial.github.io/patterns/patterns/creational/builder.html
+#[derive(Debug, Clone)]
+pub struct BalanceWatcherBuilder {
+    deepgram: Deepgram,
+    project_ids: Vec<String>,
+    interval: Duration,
+    usage_window: Duration,
+    rules: Rules,
+}
+
+/// A stream of [`BalanceEvent`]s for a set of projects.
+///
+/// Balances are polled immediately and then on every interval. Errors from
+/// the API are yielded as they occur and polling carries on with the next
+/// project. The stream never ends on its own; drop it to stop watching.
+///
+/// Constructed using [`BalanceWatcherBuilder::start`].
+pub struct BalanceWatcher(Pin<Box<dyn Stream<Item = crate::Result<BalanceEvent>> + Send>>);
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+struct Rules {
+    low_balance: Option<Threshold>,
+    depletion_warning: Option<Duration>,
+    usd_per_hour: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+struct Alerts {
+    low: bool,
+    depleting: bool,
+}
+
+/// Remembers which alerts have already been raised for each balance.
+#[derive(Debug, Default)]
+struct Tracker {
+    rules: Rules,
+    alerts: HashMap<Uuid, Alerts>,
+}
+
+struct State {
+    deepgram: Deepgram,
+    project_ids: Vec<String>,
+    interval: Interval,
+    usage_window: Duration,
+    tracker: Tracker,
+}
+
+impl BalanceWatcherBuilder {
+    pub(super) fn new(deepgram: Deepgram, project_ids: Vec<String>) -> Self {
+        Self {
+            deepgram,
+            project_ids,
+            interval: DEFAULT_INTERVAL,
+            usage_window: DEFAULT_USAGE_WINDOW,
+            rules: Rules::default(),
+        }
+    }
+
+    /// How often to poll balances. Defaults to 15 minutes.
+    ///
+    /// [`start`](Self::start) fails if `interval` is zero.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// How much recent usage to estimate the burn rate from. Defaults to 7
+    /// days.
+    pub fn usage_window(mut self, usage_window: Duration) -> Self {
+        self.usage_window = usage_window;
+        self
+    }
+
+    /// Raise [`BalanceEvent::LowBalance`] when a balance falls to or below
+    /// `threshold`.
+    ///
+    /// Balances in the other units are compared after conversion with the
+    /// rate set by [`BalanceWatcherBuilder::usd_per_hour`], and are not
+    /// compared at all if no rate is set.
+    pub fn low_balance(mut self, threshold: Threshold) -> Self {
+        self.rules.low_balance = Some(threshold);
+        self
+    }
+
+    /// Raise [`BalanceEvent::DepletionProjected`] when a balance is projected
+    /// to run out within `warning` at the current burn rate.
+    ///
+    /// The burn rate is in hours, so projections for balances in US dollars
+    /// need the rate set by [`BalanceWatcherBuilder::usd_per_hour`].
+    pub fn depletion_warning(mut self, warning: Duration) -> Self {
+        self.rules.depletion_warning = Some(warning);
+        self
+    }
+
+    /// The price of an hour of audio in US dollars, used to convert between
+    /// [`BillingUnits::Hour`] and [`BillingUnits::Usd`].
+    pub fn usd_per_hour(mut self, usd_per_hour: f64) -> Self {
+        self.rules.usd_per_hour = Some(usd_per_hour);
+        self
+    }
+
+    /// Start watching.
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`DeepgramError::ZeroOption`] if the
+    /// [`interval`](Self::interval) is zero.
+    pub fn start(self) -> crate::Result<BalanceWatcher> {
+        if self.interval.is_zero() {
+            return Err(DeepgramError::ZeroOption("interval"));
+        }
+        let mut interval = time::interval(self.interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let state = State {
+            deepgram: self.deepgram,
+            project_ids: self.project_ids,
+            interval,
+            usage_window: self.usage_window,
+            tracker: Tracker {
+                rules: self.rules,
+                alerts: HashMap::new(),
+            },
+        };
+
+        let events = stream::unfold(state, |mut state| async move {
+            state.interval.tick().await;
+            let events = state.poll().await;
+            Some((stream::iter(events), state))
+        })
+        .flatten();
+
+        Ok(BalanceWatcher(Box::pin(events)))
+    }
+}
+
+impl Stream for BalanceWatcher {
+    type Item = crate::Result<BalanceEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().poll_next(cx)
+    }
+}
+
+impl fmt::Debug for BalanceWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BalanceWatcher").finish_non_exhaustive()
+    }
+}
+
+impl State {
+    async fn poll(&mut self) -> Vec<crate::Result<BalanceEvent>> {
+        let now = SystemTime::now();
+        let mut events = Vec::new();
+        for project_id in self.project_ids.clone() {
+            match self.poll_project(&project_id, now).await {
+                Ok(project_events) => events.extend(project_events.into_iter().map(Ok)),
+                Err(err) => events.push(Err(err)),
+            }
+        }
+        events
+    }
+
+    async fn poll_project(
+        &mut self,
+        project_id: &str,
+        now: SystemTime,
+    ) -> crate::Result<Vec<BalanceEvent>> {
+        let balances = self
+            .deepgram
+            .billing()
+            .list_balance(project_id)
+            .await?
+            .balances;
+
+        let hours_per_day = if self.tracker.rules.depletion_warning.is_some() {
+            Some(self.hours_per_day(project_id, now).await?)
+        } else {
+            None
+        };
+
+        Ok(balances
+            .into_iter()
+            .flat_map(|balance| {
+                self.tracker
+                    .evaluate(project_id, balance, hours_per_day, now)
+            })
+            .collect())
+    }
+
+    /// The average hours of audio processed per day over the usage window.
+    async fn hours_per_day(&self, project_id: &str, now: SystemTime) -> crate::Result<f64> {
+        let start = now.checked_sub(self.usage_window).unwrap_or(UNIX_EPOCH);
+        let options = get_usage_options::Options::builder()
+            .start(start)
+            .end(now)
+            .build();
+        let summary = self
+            .deepgram
+            .usage()
+            .get_usage(project_id, &options)
+            .await?;
+
+        let hours: f64 = summary
+            .results
+            .iter()
+            .map(|result| result.total_hours)
+            .sum();
+        let days = self.usage_window.as_secs_f64() / SECONDS_PER_DAY;
+        Ok(if days > 0.0 { hours / days } else { 0.0 })
+    }
+}
+
+impl Tracker {
+    fn evaluate(
+        &mut self,
+        project_id: &str,
+        balance: Balance,
+        hours_per_day: Option<f64>,
+        now: SystemTime,
+    ) -> Vec<BalanceEvent> {
+        let low = self.rules.low_threshold(&balance);
+        let depleted_at = hours_per_day.and_then(|hours| {
+            self.rules
+                .depleted_at(&balance, hours, now)
+                .map(|at| (hours, at))
+        });
+
+        let alerts = self.alerts.entry(balance.balance_id).or_default();
+        let mut events = Vec::new();
+        if let Some(threshold) = low.filter(|_| !alerts.low) {
+            events.push(BalanceEvent::LowBalance {
+                project_id: project_id.to_owned(),
+                balance: balance.clone(),
+                threshold,
+            });
+        }
+        if let Some((hours_per_day, depleted_at)) = depleted_at.filter(|_| !alerts.depleting) {
+            events.push(BalanceEvent::DepletionProjected {
+                project_id: project_id.to_owned(),
+                balance: balance.clone(),
+                hours_per_day,
+                depleted_at,
+            });
+        }
+        if low.is_none() && depleted_at.is_none() && (alerts.low || alerts.depleting) {
+            events.push(BalanceEvent::Recovered {
+                project_id: project_id.to_owned(),
+                balance,
+            });
+        }
+
+        *alerts = Alerts {
+            low: low.is_some(),
+            depleting: depleted_at.is_some(),
+        };
+        events
+    }
+}
+
+impl Rules {
+    /// The low balance threshold, if `balance` is at or below it.
+    fn low_threshold(&self, balance: &Balance) -> Option<Threshold> {
+        let threshold = self.low_balance?;
+        let limit = convert(
+            threshold.amount,
+            threshold.units,
+            balance.units,
+            self.usd_per_hour,
+        )?;
+        (balance.amount <= limit).then_some(threshold)
+    }
+
+    /// When `balance` will run out, if that is within the depletion warning.
+    fn depleted_at(
+        &self,
+        balance: &Balance,
+        hours_per_day: f64,
+        now: SystemTime,
+    ) -> Option<SystemTime> {
+        let warning = self.depletion_warning?;
+        let per_day = convert(
+            hours_per_day,
+            BillingUnits::Hour,
+            balance.units,
+            self.usd_per_hour,
+        )?;
+        if per_day <= 0.0 {
+            return None;
+        }
+
+        let days = balance.amount.max(0.0) / per_day;
+        let remaining = Duration::try_from_secs_f64(days * SECONDS_PER_DAY).ok()?;
+        if remaining <= warning {
+            now.checked_add(remaining)
+        } else {
+            None
+        }
+    }
+}
+
+/// Convert an amount between units, or `None` if that needs a rate and
+/// none is set.
+fn convert(
+    amount: f64,
+    from: BillingUnits,
+    to: BillingUnits,
+    usd_per_hour: Option<f64>,
+) -> Option<f64> {
+    match (from, to) {
+        (BillingUnits::Usd, BillingUnits::Usd) | (BillingUnits::Hour, BillingUnits::Hour) => {
+            Some(amount)
+        }
+        (BillingUnits::Hour, BillingUnits::Usd) => usd_per_hour.map(|rate| amount * rate),
+        (BillingUnits::Usd, BillingUnits::Hour) => usd_per_hour
+            .filter(|rate| *rate > 0.0)
+            .map(|rate| amount / rate),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use uuid::Uuid;
+
+    use super::{BalanceEvent, Rules, Threshold, Tracker};
+    use crate::manage::billing::response::{Balance, BillingUnits};
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn balance(amount: f64, units: BillingUnits) -> Balance {
+        Balance {
+            balance_id: Uuid::from_u128(1),
+            amount,
+            units,
+            purchase_order_id: Uuid::nil(),
+        }
+    }
+
+    fn tracker(rules: Rules) -> Tracker {
+        Tracker {
+            rules,
+            alerts: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_a_zero_interval() {
+        let dg = crate::Deepgram::new("token").unwrap();
+        let watcher = dg.billing().watch(["p"]).interval(Duration::ZERO).start();
+        assert!(matches!(
+            watcher,
+            Err(crate::DeepgramError::ZeroOption("interval"))
+        ));
+    }
+
+    #[test]
+    fn raises_low_balance_once() {
+        let mut tracker = tracker(Rules {
+            low_balance: Some(Threshold::usd(10.0)),
+            ..Rules::default()
+        });
+        let now = UNIX_EPOCH;
+
+        let events = tracker.evaluate("p", balance(8.0, BillingUnits::Usd), None, now);
+        assert!(matches!(
+            events.as_slice(),
+            [BalanceEvent::LowBalance { threshold, .. }] if *threshold == Threshold::usd(10.0)
+        ));
+        assert!(tracker
+            .evaluate("p", balance(5.0, BillingUnits::Usd), None, now)
+            .is_empty());
+        assert!(matches!(
+            tracker
+                .evaluate("p", balance(50.0, BillingUnits::Usd), None, now)
+                .as_slice(),
+            [BalanceEvent::Recovered { .. }]
+        ));
+    }
+
+    #[test]
+    fn converts_units() {
+        let mut without_rate = tracker(Rules {
+            low_balance: Some(Threshold::usd(10.0)),
+            ..Rules::default()
+        });
+        assert!(without_rate
+            .evaluate("p", balance(1.0, BillingUnits::Hour), None, UNIX_EPOCH)
+            .is_empty());
+
+        let mut with_rate = tracker(Rules {
+            low_balance: Some(Threshold::usd(10.0)),
+            usd_per_hour: Some(0.25),
+            ..Rules::default()
+        });
+        assert_eq!(
+            with_rate
+                .evaluate("p", balance(40.0, BillingUnits::Hour), None, UNIX_EPOCH)
+                .len(),
+            1
+        );
+        assert!(with_rate
+            .evaluate("p", balance(41.0, BillingUnits::Hour), None, UNIX_EPOCH)
+            .iter()
+            .all(|event| matches!(event, BalanceEvent::Recovered { .. })));
+    }
+
+    #[test]
+    fn projects_depletion() {
+        let rules = Rules {
+            depletion_warning: Some(DAY * 7),
+            usd_per_hour: Some(0.5),
+            ..Rules::default()
+        };
+        let now = UNIX_EPOCH + DAY;
+
+        // 30 USD at 12 hours (6 USD) per day lasts 5 days.
+        assert_eq!(
+            tracker(rules).evaluate("p", balance(30.0, BillingUnits::Usd), Some(12.0), now),
+            vec![BalanceEvent::DepletionProjected {
+                project_id: "p".to_owned(),
+                balance: balance(30.0, BillingUnits::Usd),
+                hours_per_day: 12.0,
+                depleted_at: now + DAY * 5,
+            }]
+        );
+
+        // 100 hours at 12 hours per day lasts more than 7 days.
+        let mut hours = tracker(rules);
+        assert!(hours
+            .evaluate("p", balance(100.0, BillingUnits::Hour), Some(12.0), now)
+            .is_empty());
+        assert!(hours
+            .evaluate("p", balance(100.0, BillingUnits::Hour), Some(0.0), now)
+            .is_empty());
+    }
+}
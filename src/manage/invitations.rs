@@ -4,10 +4,17 @@
 //!
 //! [api]: https://developers.deepgram.com/api-reference/#invitations
 
-use crate::{send_and_translate_response, Deepgram};
+use crate::{
+    manage::invitations::{
+        options::{Options, SerializableOptions},
+        response::Invites,
+    },
+    send_and_translate_management_response, Deepgram,
+};
 
 use response::Message;
 
+pub mod options;
 pub mod response;
 
 /// Manage the invitations to a Deepgram Project.
@@ -35,6 +42,129 @@ impl<'a> From<&'a Deepgram> for Invitations<'a> {
 }
 
 impl Invitations<'_> {
+    /// Get the pending invitations to the specified project.
+    ///
+    /// See the [Deepgram API Reference][api] for more info.
+    ///
+    /// [api]: https://developers.deepgram.com/reference/list-invites
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::env;
+    /// #
+    /// # use deepgram::{Deepgram, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// # let project_id =
+    /// #     env::var("DEEPGRAM_PROJECT_ID").expect("DEEPGRAM_PROJECT_ID environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let invites = dg_client
+    ///     .invitations()
+    ///     .list_invitations(&project_id)
+    ///     .await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_invitations(&self, project_id: &str) -> crate::Result<Invites> {
+        let url = self.0.manage_url(["projects", project_id, "invites"]);
+
+        send_and_translate_management_response(self.0, self.0.client.get(url)).await
+    }
+
+    /// Invite someone to the specified project by email.
+    ///
+    /// See the [Deepgram API Reference][api] for more info.
+    ///
+    /// [api]: https://developers.deepgram.com/reference/send-invites
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::env;
+    /// #
+    /// # use deepgram::{manage::{invitations::options::Options, scopes::Scope}, Deepgram, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// # let project_id =
+    /// #     env::var("DEEPGRAM_PROJECT_ID").expect("DEEPGRAM_PROJECT_ID environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let options = Options::builder("jane@example.com", Scope::Member).build();
+    /// dg_client
+    ///     .invitations()
+    ///     .send_invitation(&project_id, &options)
+    ///     .await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_invitation(
+        &self,
+        project_id: &str,
+        options: &Options,
+    ) -> crate::Result<Message> {
+        let url = self.0.manage_url(["projects", project_id, "invites"]);
+        let request = self
+            .0
+            .client
+            .post(url)
+            .json(&SerializableOptions::from(options));
+
+        send_and_translate_management_response(self.0, request).await
+    }
+
+    /// Revoke the pending invitation sent to `email` for the specified project.
+    ///
+    /// See the [Deepgram API Reference][api] for more info.
+    ///
+    /// [api]: https://developers.deepgram.com/reference/delete-invite
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::env;
+    /// #
+    /// # use deepgram::{Deepgram, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// # let project_id =
+    /// #     env::var("DEEPGRAM_PROJECT_ID").expect("DEEPGRAM_PROJECT_ID environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// dg_client
+    ///     .invitations()
+    ///     .delete_invitation(&project_id, "jane@example.com")
+    ///     .await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn delete_invitation(&self, project_id: &str, email: &str) -> crate::Result<Message> {
+        let url = self
+            .0
+            .manage_url(["projects", project_id, "invites", email]);
+
+        send_and_translate_management_response(self.0, self.0.client.delete(url)).await
+    }
+
     /// Remove the authenticated account from the specified project.
     ///
     /// See the [Deepgram API Reference][api] for more info.
@@ -67,8 +197,23 @@ impl Invitations<'_> {
     /// # }
     /// ```
     pub async fn leave_project(&self, project_id: &str) -> crate::Result<Message> {
-        let url = format!("https://api.deepgram.com/v1/projects/{}/leave", project_id,);
+        let url = self.0.manage_url(["projects", project_id, "leave"]);
+
+        send_and_translate_management_response(self.0, self.0.client.delete(url)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Deepgram;
 
-        send_and_translate_response(self.0.client.delete(url)).await
+    #[test]
+    fn encodes_email_in_invitation_url() {
+        let dg = Deepgram::new("token").unwrap();
+        assert_eq!(
+            dg.manage_url(["projects", "project", "invites", "jane/doe?#@example.com"])
+                .as_str(),
+            "https://api.deepgram.com/v1/projects/project/invites/jane%2Fdoe%3F%23@example.com"
+        );
     }
 }
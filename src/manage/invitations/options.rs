@@ -0,0 +1,124 @@
+//! Set options for [`Invitations::send_invitation`](super::Invitations::send_invitation).
+//!
+//! See the [Deepgram API Reference][api] for more info.
+//!
+//! [api]: https://developers.deepgram.com/reference/send-invites
+
+use serde::Serialize;
+
+use crate::manage::scopes::Scope;
+
+/// Used as a parameter for [`Invitations::send_invitation`](super::Invitations::send_invitation).
+///
+/// See the [Deepgram API Reference][api] for more info.
+///
+/// [api]: https://developers.deepgram.com/reference/send-invites
+#[derive(Debug, PartialEq, Clone)]
+pub struct Options {
+    email: String,
+    scope: Scope,
+}
+
+/// Builds an [`Options`] object using [the Builder pattern][builder].
+///
+/// [builder]: https://rust-unofficial.github.io/patterns/patterns/creational/builder.html
+#[derive(Debug, PartialEq, Clone)]
+pub struct OptionsBuilder(Options);
+
+#[derive(Serialize)]
+pub(crate) struct SerializableOptions<'a> {
+    email: &'a String,
+    scope: &'a Scope,
+}
+
+impl Options {
+    /// Construct a new [`OptionsBuilder`].
+    pub fn builder(email: impl Into<String>, scope: Scope) -> OptionsBuilder {
+        OptionsBuilder::new(email, scope)
+    }
+
+    /// Return the Options in json format. If serialization would
+    /// fail, this will also return an error.
+    ///
+    /// This is intended primarily to help with debugging API requests.
+    ///
+    /// ```
+    /// use deepgram::manage::{invitations::options::Options, scopes::Scope};
+    /// let options = Options::builder("jane@example.com", Scope::Member).build();
+    /// assert_eq!(
+    ///     &options.json().unwrap(),
+    ///     r#"{"email":"jane@example.com","scope":"member"}"#)
+    /// ```
+    ///
+    pub fn json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&SerializableOptions::from(self))
+    }
+}
+
+impl OptionsBuilder {
+    /// Construct a new [`OptionsBuilder`].
+    pub fn new(email: impl Into<String>, scope: Scope) -> Self {
+        Self(Options {
+            email: email.into(),
+            scope,
+        })
+    }
+
+    /// Set the email address to send the invitation to.
+    ///
+    /// This will overwrite the one set in [`OptionsBuilder::new`] for [`Options::builder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use deepgram::manage::{invitations::options::Options, scopes::Scope};
+    /// #
+    /// let options1 = Options::builder("old@example.com", Scope::Member)
+    ///     .email("new@example.com")
+    ///     .build();
+    ///
+    /// let options2 = Options::builder("new@example.com", Scope::Member).build();
+    ///
+    /// assert_eq!(options1, options2);
+    /// ```
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.0.email = email.into();
+        self
+    }
+
+    /// Set the scope the invited member will have in the project.
+    ///
+    /// This will overwrite the one set in [`OptionsBuilder::new`] for [`Options::builder`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use deepgram::manage::{invitations::options::Options, scopes::Scope};
+    /// #
+    /// let options1 = Options::builder("jane@example.com", Scope::Member)
+    ///     .scope(Scope::Admin)
+    ///     .build();
+    ///
+    /// let options2 = Options::builder("jane@example.com", Scope::Admin).build();
+    ///
+    /// assert_eq!(options1, options2);
+    /// ```
+    pub fn scope(mut self, scope: Scope) -> Self {
+        self.0.scope = scope;
+        self
+    }
+
+    /// Finish building the [`Options`] object.
+    pub fn build(self) -> Options {
+        self.0
+    }
+}
+
+impl<'a> From<&'a Options> for SerializableOptions<'a> {
+    fn from(options: &'a Options) -> Self {
+        // Destructuring it makes sure that we don't forget to use any of it
+        let Options { email, scope } = options;
+
+        Self { email, scope }
+    }
+}
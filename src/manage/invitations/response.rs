@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::manage::scopes::Scope;
+
 /// Success message.
 ///
 /// See the [Deepgram API Reference][api] for more info.
@@ -13,3 +15,30 @@ pub struct Message {
     #[allow(missing_docs)]
     pub message: String,
 }
+
+/// Returned by [`Invitations::list_invitations`](super::Invitations::list_invitations).
+///
+/// See the [Deepgram API Reference][api] for more info.
+///
+/// [api]: https://developers.deepgram.com/reference/list-invites
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Invites {
+    #[allow(missing_docs)]
+    pub invites: Vec<Invite>,
+}
+
+/// A pending invitation to a project.
+///
+/// See the [Deepgram API Reference][api] for more info.
+///
+/// [api]: https://developers.deepgram.com/reference/list-invites
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Invite {
+    /// The email address the invitation was sent to.
+    pub email: String,
+
+    /// The scope the invited member will have in the project.
+    pub scope: Scope,
+}
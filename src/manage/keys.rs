@@ -4,12 +4,18 @@
 //!
 //! [api]: https://developers.deepgram.com/api-reference/#keys
 
+use std::{
+    error::Error,
+    future::Future,
+    time::{Duration, SystemTime},
+};
+
 use crate::{
     manage::keys::{
         options::{Options, SerializableOptions},
-        response::{MemberAndApiKey, MembersAndApiKeys, NewApiKey},
+        response::{ApiKey, MemberAndApiKey, MembersAndApiKeys, NewApiKey, RotatedKey},
     },
-    send_and_translate_response, Deepgram,
+    send_and_translate_management_response, Deepgram, DeepgramError,
 };
 
 use response::Message;
@@ -74,9 +80,9 @@ impl Keys<'_> {
     /// # }
     /// ```
     pub async fn list(&self, project_id: &str) -> crate::Result<MembersAndApiKeys> {
-        let url = format!("https://api.deepgram.com/v1/projects/{}/keys", project_id);
+        let url = self.0.manage_url(["projects", project_id, "keys"]);
 
-        send_and_translate_response(self.0.client.get(url)).await
+        send_and_translate_management_response(self.0, self.0.client.get(url)).await
     }
 
     /// Get details of the specified key.
@@ -113,12 +119,9 @@ impl Keys<'_> {
     /// # }
     /// ```
     pub async fn get(&self, project_id: &str, key_id: &str) -> crate::Result<MemberAndApiKey> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/keys/{}",
-            project_id, key_id,
-        );
+        let url = self.0.manage_url(["projects", project_id, "keys", key_id]);
 
-        send_and_translate_response(self.0.client.get(url)).await
+        send_and_translate_management_response(self.0, self.0.client.get(url)).await
     }
 
     /// Create a new key in the specified project.
@@ -132,7 +135,7 @@ impl Keys<'_> {
     /// ```no_run
     /// # use std::env;
     /// #
-    /// # use deepgram::{manage::keys::options::Options, Deepgram, DeepgramError};
+    /// # use deepgram::{manage::{keys::options::Options, scopes::Scope}, Deepgram, DeepgramError};
     /// #
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), DeepgramError> {
@@ -146,7 +149,7 @@ impl Keys<'_> {
     /// #
     /// let dg_client = Deepgram::new(&deepgram_api_key)?;
     ///
-    /// let options = Options::builder("New Key", ["member"]).build();
+    /// let options = Options::builder("New Key", [Scope::Member]).build();
     /// let new_key = dg_client
     ///     .keys()
     ///     .create(&project_id, &options)
@@ -156,14 +159,14 @@ impl Keys<'_> {
     /// # }
     /// ```
     pub async fn create(&self, project_id: &str, options: &Options) -> crate::Result<NewApiKey> {
-        let url = format!("https://api.deepgram.com/v1/projects/{}/keys", project_id);
+        let url = self.0.manage_url(["projects", project_id, "keys"]);
         let request = self
             .0
             .client
             .post(url)
             .json(&SerializableOptions::from(options));
 
-        send_and_translate_response(request).await
+        send_and_translate_management_response(self.0, request).await
     }
 
     /// Delete the specified key in the specified project.
@@ -200,11 +203,278 @@ impl Keys<'_> {
     /// # }
     /// ```
     pub async fn delete(&self, project_id: &str, key_id: &str) -> crate::Result<Message> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/keys/{}",
-            project_id, key_id,
+        let url = self.0.manage_url(["projects", project_id, "keys", key_id]);
+
+        send_and_translate_management_response(self.0, self.0.client.delete(url)).await
+    }
+
+    /// Replace the specified key with a new one and delete it after
+    /// `grace_period`.
+    ///
+    /// The new key has the same comment, tags and scopes as the old one. If
+    /// the old key expires, the new key is valid for the same length of time.
+    /// Its secret is handed to `sink`, for example to store it in a secret
+    /// manager, and the old key is only deleted once `sink` succeeds and
+    /// `grace_period` has passed, giving running services time to pick up the
+    /// new key. If `sink` fails, the new key is deleted again and the error is
+    /// returned as [`DeepgramError::KeySinkError`]. If the old key cannot be
+    /// deleted, [`DeepgramError::KeyRotationIncomplete`] carries the ID of the
+    /// new key, which stays valid alongside the old one.
+    ///
+    /// This waits for `grace_period`, so spawn it as a task to rotate many
+    /// keys concurrently.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{env, time::Duration};
+    /// #
+    /// # use deepgram::{Deepgram, DeepgramError};
+    /// #
+    /// # async fn store_secret(key: &str) -> std::io::Result<()> { Ok(()) }
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// # let project_id =
+    /// #     env::var("DEEPGRAM_PROJECT_ID").expect("DEEPGRAM_PROJECT_ID environmental variable");
+    /// #
+    /// # let key_id = env::var("DEEPGRAM_KEY_ID").expect("DEEPGRAM_KEY_ID environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let rotated = dg_client
+    ///     .keys()
+    ///     .rotate(&project_id, &key_id, Duration::from_secs(60 * 60), |new_key| async move {
+    ///         store_secret(&new_key.key).await
+    ///     })
+    ///     .await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn rotate<S, F, E>(
+        &self,
+        project_id: &str,
+        key_id: &str,
+        grace_period: Duration,
+        sink: S,
+    ) -> crate::Result<RotatedKey>
+    where
+        S: FnOnce(NewApiKey) -> F,
+        F: Future<Output = Result<(), E>>,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        let old_api_key = self.get(project_id, key_id).await?.api_key;
+        let new_key = self
+            .create(project_id, &replacement_options(&old_api_key))
+            .await?;
+        let new_api_key_id = new_key.api_key_id;
+
+        if let Err(err) = sink(new_key).await {
+            // Nobody has the new secret, so don't leave the key behind.
+            let _ = self.delete(project_id, &new_api_key_id.to_string()).await;
+            return Err(DeepgramError::KeySinkError(err.into()));
+        }
+
+        tokio::time::sleep(grace_period).await;
+        let rotated = RotatedKey {
+            old_api_key,
+            new_api_key_id,
+        };
+        if let Err(err) = self.delete(project_id, key_id).await {
+            return Err(DeepgramError::KeyRotationIncomplete {
+                rotated: Box::new(rotated),
+                source: Box::new(err),
+            });
+        }
+
+        Ok(rotated)
+    }
+
+    /// Get the keys in the specified project that expire within `within`
+    /// from now, including keys that have already expired, soonest first.
+    ///
+    /// Keys without an expiration date are never included.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{env, time::Duration};
+    /// #
+    /// # use deepgram::{Deepgram, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// # let project_id =
+    /// #     env::var("DEEPGRAM_PROJECT_ID").expect("DEEPGRAM_PROJECT_ID environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let expiring = dg_client
+    ///     .keys()
+    ///     .expiring_within(&project_id, Duration::from_secs(7 * 24 * 60 * 60))
+    ///     .await?;
+    /// for key in expiring {
+    ///     println!("{} expires {:?}", key.api_key.comment, key.api_key.expiration_date);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn expiring_within(
+        &self,
+        project_id: &str,
+        within: Duration,
+    ) -> crate::Result<Vec<MemberAndApiKey>> {
+        let keys = self.list(project_id).await?.api_keys;
+
+        Ok(expiring_before(keys, SystemTime::now() + within))
+    }
+}
+
+/// Options for a key with the same comment, tags, scopes and lifetime as `api_key`.
+fn replacement_options(api_key: &ApiKey) -> Options {
+    let mut options = Options::builder(api_key.comment.clone(), api_key.scopes.iter().cloned())
+        .tag(api_key.tags.iter().flatten().map(String::as_str));
+
+    if let (Some(created), Some(expires)) = (api_key.created_at(), api_key.expires_at()) {
+        match expires.duration_since(created) {
+            Ok(lifetime) if lifetime.as_secs() > 0 => {
+                options = options.time_to_live_in_seconds(lifetime.as_secs() as usize);
+            }
+            _ => {}
+        }
+    }
+
+    options.build()
+}
+
+fn expiring_before(keys: Vec<MemberAndApiKey>, deadline: SystemTime) -> Vec<MemberAndApiKey> {
+    let mut expiring: Vec<_> = keys
+        .into_iter()
+        .filter_map(|key| Some((key.api_key.expires_at()?, key)))
+        .filter(|(expires, _)| *expires <= deadline)
+        .collect();
+    expiring.sort_by_key(|(expires, _)| *expires);
+    expiring.into_iter().map(|(_, key)| key).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use uuid::Uuid;
+
+    use super::{expiring_before, replacement_options};
+    use crate::common::timestamp::Timestamp;
+    use crate::manage::keys::{
+        options::Options,
+        response::{ApiKey, Member, MemberAndApiKey},
+    };
+    use crate::manage::scopes::Scope;
+    use crate::{test_server::serve_with, Deepgram, DeepgramError};
+
+    fn key(comment: &str, expiration_date: Option<&str>) -> MemberAndApiKey {
+        MemberAndApiKey {
+            member: Member {
+                member_id: Uuid::nil(),
+                first_name: None,
+                last_name: None,
+                email: "jane@example.com".to_owned(),
+            },
+            api_key: ApiKey {
+                api_key_id: Uuid::nil(),
+                comment: comment.to_owned(),
+                scopes: vec![Scope::Member, Scope::UsageRead],
+                tags: Some(vec!["ci".to_owned()]),
+                created: Timestamp::from_raw("2024-01-01T00:00:00.000Z".to_owned()),
+                expiration_date: expiration_date
+                    .map(|expiration_date| Timestamp::from_raw(expiration_date.to_owned())),
+            },
+        }
+    }
+
+    #[test]
+    fn replacement_keeps_scopes_tags_and_lifetime() {
+        let api_key: ApiKey = key("CI", Some("2024-01-31T00:00:00Z")).api_key;
+        assert_eq!(
+            replacement_options(&api_key),
+            Options::builder("CI", [Scope::Member, Scope::UsageRead])
+                .tag(["ci"])
+                .time_to_live_in_seconds(30 * 24 * 60 * 60)
+                .build()
         );
 
-        send_and_translate_response(self.0.client.delete(url)).await
+        let unlimited = Options::builder("CI", [Scope::Member, Scope::UsageRead])
+            .tag(["ci"])
+            .build();
+        let api_key = key("CI", None).api_key;
+        assert_eq!(replacement_options(&api_key), unlimited);
+
+        // A zero time to live would be rejected.
+        let api_key = key("CI", Some("2024-01-01T00:00:00Z")).api_key;
+        assert_eq!(replacement_options(&api_key), unlimited);
+    }
+
+    #[test]
+    fn finds_expiring_keys() {
+        let keys = vec![
+            key("never", None),
+            key("later", Some("2024-03-01T00:00:00Z")),
+            key("soon", Some("2024-01-20")),
+            key("expired", Some("2024-01-02T00:00:00Z")),
+        ];
+        // 2024-02-01T00:00:00Z
+        let deadline = UNIX_EPOCH + Duration::from_secs(1_706_745_600);
+
+        let expiring: Vec<_> = expiring_before(keys, deadline)
+            .into_iter()
+            .map(|key| key.api_key.comment)
+            .collect();
+        assert_eq!(expiring, vec!["expired", "soon"]);
+    }
+
+    #[tokio::test]
+    async fn rotation_keeps_the_new_key_id_when_the_old_key_survives() {
+        const OLD: &str = "11111111-1111-1111-1111-111111111111";
+        const NEW: &str = "22222222-2222-2222-2222-222222222222";
+        let url = serve_with(|request| match request.split(' ').next() {
+            Some("GET") => (
+                "200 OK",
+                format!(
+                    r#"{{"member":{{"member_id":"{OLD}","email":"jane@example.com"}},
+                    "api_key":{{"api_key_id":"{OLD}","comment":"CI","scopes":["member"],
+                    "created":"2024-01-01T00:00:00Z"}}}}"#
+                ),
+            ),
+            Some("POST") => (
+                "200 OK",
+                format!(
+                    r#"{{"api_key_id":"{NEW}","key":"secret","comment":"CI",
+                    "scopes":["member"],"created":"2024-02-01T00:00:00Z"}}"#
+                ),
+            ),
+            _ => ("500 Internal Server Error", String::new()),
+        })
+        .await;
+        let dg = Deepgram::with_base_url_and_api_key(url, "token").unwrap();
+
+        let result = dg
+            .keys()
+            .rotate("project", OLD, Duration::ZERO, |_| async {
+                Ok::<_, std::io::Error>(())
+            })
+            .await;
+        let Err(DeepgramError::KeyRotationIncomplete { rotated, .. }) = result else {
+            panic!("expected an incomplete rotation, got {result:?}");
+        };
+        assert_eq!(rotated.old_api_key.api_key_id.to_string(), OLD);
+        assert_eq!(rotated.new_api_key_id.to_string(), NEW);
     }
 }
@@ -6,6 +6,8 @@
 
 use serde::Serialize;
 
+use crate::{common::timestamp::Timestamp, manage::scopes::Scope};
+
 /// Used as a parameter for [`Keys::create`](super::Keys::create).
 ///
 /// See the [Deepgram API Reference][api] for more info.
@@ -15,13 +17,13 @@ use serde::Serialize;
 pub struct Options {
     comment: String,
     tags: Vec<String>,
-    scopes: Vec<String>,
+    scopes: Vec<Scope>,
     expiration: Option<Expiration>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 enum Expiration {
-    ExpirationDate(String),
+    ExpirationDate(Timestamp),
     TimeToLiveInSeconds(usize),
 }
 
@@ -38,10 +40,10 @@ pub(crate) struct SerializableOptions<'a> {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tags: &'a Vec<String>,
 
-    scopes: &'a Vec<String>,
+    scopes: &'a Vec<Scope>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    expiration_date: Option<&'a String>,
+    expiration_date: Option<&'a Timestamp>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     time_to_live_in_seconds: Option<usize>,
@@ -49,9 +51,9 @@ pub(crate) struct SerializableOptions<'a> {
 
 impl Options {
     /// Construct a new [`OptionsBuilder`].
-    pub fn builder<'a>(
+    pub fn builder(
         comment: impl Into<String>,
-        scopes: impl IntoIterator<Item = &'a str>,
+        scopes: impl IntoIterator<Item = Scope>,
     ) -> OptionsBuilder {
         OptionsBuilder::new(comment, scopes)
     }
@@ -62,8 +64,8 @@ impl Options {
     /// This is intended primarily to help with debugging API requests.
     ///
     /// ```
-    /// use deepgram::manage::keys::options::Options;
-    /// let options = Options::builder("API Key", ["member"])
+    /// use deepgram::manage::{keys::options::Options, scopes::Scope};
+    /// let options = Options::builder("API Key", [Scope::Member])
     ///     .tag(["my-tag", "another-tag"])
     ///     .build();
     /// assert_eq!(
@@ -78,11 +80,11 @@ impl Options {
 
 impl OptionsBuilder {
     /// Construct a new [`OptionsBuilder`].
-    pub fn new<'a>(comment: impl Into<String>, scopes: impl IntoIterator<Item = &'a str>) -> Self {
+    pub fn new(comment: impl Into<String>, scopes: impl IntoIterator<Item = Scope>) -> Self {
         Self(Options {
             comment: comment.into(),
             tags: Vec::new(),
-            scopes: scopes.into_iter().map(String::from).collect(),
+            scopes: scopes.into_iter().collect(),
             expiration: None,
         })
     }
@@ -95,13 +97,13 @@ impl OptionsBuilder {
     /// # Examples
     ///
     /// ```
-    /// # use deepgram::manage::keys::options::Options;
+    /// # use deepgram::manage::{keys::options::Options, scopes::Scope};
     /// #
-    /// let options1 = Options::builder("Old comment", ["member"])
+    /// let options1 = Options::builder("Old comment", [Scope::Member])
     ///     .comment("New comment")
     ///     .build();
     ///
-    /// let options2 = Options::builder("New comment", ["member"]).build();
+    /// let options2 = Options::builder("New comment", [Scope::Member]).build();
     ///
     /// assert_eq!(options1, options2);
     /// ```
@@ -117,22 +119,22 @@ impl OptionsBuilder {
     /// # Examples
     ///
     /// ```
-    /// # use deepgram::manage::keys::options::Options;
+    /// # use deepgram::manage::{keys::options::Options, scopes::Scope};
     /// #
-    /// let options = Options::builder("New Key", ["member"])
+    /// let options = Options::builder("New Key", [Scope::Member])
     ///     .tag(["Tag 1", "Tag 2"])
     ///     .build();
     /// ```
     ///
     /// ```
-    /// # use deepgram::manage::keys::options::Options;
+    /// # use deepgram::manage::{keys::options::Options, scopes::Scope};
     /// #
-    /// let options1 = Options::builder("New Key", ["member"])
+    /// let options1 = Options::builder("New Key", [Scope::Member])
     ///     .tag(["Tag 1"])
     ///     .tag(vec!["Tag 2"])
     ///     .build();
     ///
-    /// let options2 = Options::builder("New Key", ["member"])
+    /// let options2 = Options::builder("New Key", [Scope::Member])
     ///     .tag(["Tag 1", "Tag 2"])
     ///     .build();
     ///
@@ -150,58 +152,66 @@ impl OptionsBuilder {
     /// # Examples
     ///
     /// ```
-    /// # use deepgram::manage::keys::options::Options;
+    /// # use deepgram::manage::{keys::options::Options, scopes::Scope};
     /// #
-    /// let options = Options::builder("New Key", ["member"])
-    ///     .scopes(["admin"])
+    /// let options = Options::builder("New Key", [Scope::Member])
+    ///     .scopes([Scope::Admin])
     ///     .build();
     /// ```
     ///
     /// ```
-    /// # use deepgram::manage::keys::options::Options;
+    /// # use deepgram::manage::{keys::options::Options, scopes::Scope};
     /// #
-    /// let options1 = Options::builder("New Key", ["member"])
-    ///     .scopes(["admin"])
+    /// let options1 = Options::builder("New Key", [Scope::Member])
+    ///     .scopes([Scope::Admin])
     ///     .build();
     ///
-    /// let options2 = Options::builder("New Key", ["member", "admin"]).build();
+    /// let options2 = Options::builder("New Key", [Scope::Member, Scope::Admin]).build();
     ///
     /// assert_eq!(options1, options2);
     /// ```
-    pub fn scopes<'a>(mut self, scopes: impl IntoIterator<Item = &'a str>) -> Self {
-        self.0.scopes.extend(scopes.into_iter().map(String::from));
+    pub fn scopes(mut self, scopes: impl IntoIterator<Item = Scope>) -> Self {
+        self.0.scopes.extend(scopes);
         self
     }
 
     /// Set the expiration date.
     ///
+    /// Takes a [`Timestamp`], either checked from a string such as
+    /// `"2038-01-19"` with [`Timestamp::parse`] or converted from a
+    /// [`SystemTime`](std::time::SystemTime).
+    ///
     /// This will unset the time to live in seconds.
     ///
     /// # Examples
     ///
     /// ```
-    /// # use deepgram::manage::keys::options::Options;
+    /// # use deepgram::common::timestamp::Timestamp;
+    /// # use deepgram::manage::{keys::options::Options, scopes::Scope};
     /// #
-    /// let options = Options::builder("New Key", ["member"])
-    ///     .expiration_date("2038-01-19")
+    /// let options = Options::builder("New Key", [Scope::Member])
+    ///     .expiration_date(Timestamp::parse("2038-01-19")?)
     ///     .build();
+    /// # Ok::<(), deepgram::DeepgramError>(())
     /// ```
     ///
     /// ```
-    /// # use deepgram::manage::keys::options::Options;
+    /// # use deepgram::common::timestamp::Timestamp;
+    /// # use deepgram::manage::{keys::options::Options, scopes::Scope};
     /// #
-    /// let options1 = Options::builder("New Key", ["member"])
+    /// let options1 = Options::builder("New Key", [Scope::Member])
     ///     .time_to_live_in_seconds(7776000)
-    ///     .expiration_date("2038-01-19")
+    ///     .expiration_date(Timestamp::parse("2038-01-19")?)
     ///     .build();
     ///
-    /// let options2 = Options::builder("New Key", ["member"])
-    ///     .expiration_date("2038-01-19")
+    /// let options2 = Options::builder("New Key", [Scope::Member])
+    ///     .expiration_date(Timestamp::parse("2038-01-19")?)
     ///     .build();
     ///
     /// assert_eq!(options1, options2);
+    /// # Ok::<(), deepgram::DeepgramError>(())
     /// ```
-    pub fn expiration_date(mut self, expiration_date: impl Into<String>) -> Self {
+    pub fn expiration_date(mut self, expiration_date: impl Into<Timestamp>) -> Self {
         self.0.expiration = Some(Expiration::ExpirationDate(expiration_date.into()));
         self
     }
@@ -213,26 +223,28 @@ impl OptionsBuilder {
     /// # Examples
     ///
     /// ```
-    /// # use deepgram::manage::keys::options::Options;
+    /// # use deepgram::manage::{keys::options::Options, scopes::Scope};
     /// #
-    /// let options = Options::builder("New Key", ["member"])
+    /// let options = Options::builder("New Key", [Scope::Member])
     ///     .time_to_live_in_seconds(7776000)
     ///     .build();
     /// ```
     ///
     /// ```
-    /// # use deepgram::manage::keys::options::Options;
+    /// # use deepgram::common::timestamp::Timestamp;
+    /// # use deepgram::manage::{keys::options::Options, scopes::Scope};
     /// #
-    /// let options1 = Options::builder("New Key", ["member"])
-    ///     .expiration_date("2038-01-19")
+    /// let options1 = Options::builder("New Key", [Scope::Member])
+    ///     .expiration_date(Timestamp::parse("2038-01-19")?)
     ///     .time_to_live_in_seconds(7776000)
     ///     .build();
     ///
-    /// let options2 = Options::builder("New Key", ["member"])
+    /// let options2 = Options::builder("New Key", [Scope::Member])
     ///     .time_to_live_in_seconds(7776000)
     ///     .build();
     ///
     /// assert_eq!(options1, options2);
+    /// # Ok::<(), deepgram::DeepgramError>(())
     /// ```
     pub fn time_to_live_in_seconds(mut self, time_to_live_in_seconds: usize) -> Self {
         self.0.expiration = Some(Expiration::TimeToLiveInSeconds(time_to_live_in_seconds));
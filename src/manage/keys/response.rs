@@ -1,8 +1,13 @@
 //! Deepgram keys API response types.
 
+use std::time::SystemTime;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::common::timestamp::Timestamp;
+use crate::manage::scopes::Scope;
+
 /// Success message.
 ///
 /// See the [Deepgram API Reference][api] for more info.
@@ -78,16 +83,30 @@ pub struct ApiKey {
     pub comment: String,
 
     #[allow(missing_docs)]
-    pub scopes: Vec<String>,
+    pub scopes: Vec<Scope>,
 
     #[allow(missing_docs)]
     pub tags: Option<Vec<String>>,
 
     #[allow(missing_docs)]
-    pub created: String,
+    pub created: Timestamp,
 
     #[allow(missing_docs)]
-    pub expiration_date: Option<String>,
+    pub expiration_date: Option<Timestamp>,
+}
+
+impl ApiKey {
+    /// When the key was created, or [`None`] if [`ApiKey::created`] could
+    /// not be parsed.
+    pub fn created_at(&self) -> Option<SystemTime> {
+        self.created.to_system_time()
+    }
+
+    /// When the key expires, or [`None`] if it never does or
+    /// [`ApiKey::expiration_date`] could not be parsed.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.expiration_date.as_ref()?.to_system_time()
+    }
 }
 
 /// Returned by [`Keys::create`](super::Keys::create).
@@ -108,14 +127,27 @@ pub struct NewApiKey {
     pub comment: String,
 
     #[allow(missing_docs)]
-    pub scopes: Vec<String>,
+    pub scopes: Vec<Scope>,
 
     #[allow(missing_docs)]
     pub tags: Option<Vec<String>>,
 
     #[allow(missing_docs)]
-    pub created: String,
+    pub created: Timestamp,
 
     #[allow(missing_docs)]
-    pub expiration_date: Option<String>,
+    pub expiration_date: Option<Timestamp>,
+}
+
+/// Returned by [`Keys::rotate`](super::Keys::rotate).
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub struct RotatedKey {
+    /// The key that was replaced, which has now been deleted unless
+    /// [`DeepgramError::KeyRotationIncomplete`](crate::DeepgramError::KeyRotationIncomplete)
+    /// was returned.
+    pub old_api_key: ApiKey,
+
+    /// The ID of the replacement key.
+    pub new_api_key_id: Uuid,
 }
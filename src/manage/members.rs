@@ -4,7 +4,7 @@
 //!
 //! [api]: https://developers.deepgram.com/api-reference/#members
 
-use crate::{send_and_translate_response, Deepgram};
+use crate::{send_and_translate_management_response, Deepgram};
 
 use response::Message;
 
@@ -67,12 +67,9 @@ impl Members<'_> {
     /// # }
     /// ```
     pub async fn list_members(&self, project_id: &str) -> crate::Result<response::Members> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/members",
-            project_id,
-        );
+        let url = self.0.manage_url(["projects", project_id, "members"]);
 
-        send_and_translate_response(self.0.client.get(url)).await
+        send_and_translate_management_response(self.0, self.0.client.get(url)).await
     }
 
     /// Remove the specified member from the specified project.
@@ -110,11 +107,10 @@ impl Members<'_> {
     /// # }
     /// ```
     pub async fn remove_member(&self, project_id: &str, member_id: &str) -> crate::Result<Message> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/members/{}",
-            project_id, member_id,
-        );
+        let url = self
+            .0
+            .manage_url(["projects", project_id, "members", member_id]);
 
-        send_and_translate_response(self.0.client.delete(url)).await
+        send_and_translate_management_response(self.0, self.0.client.delete(url)).await
     }
 }
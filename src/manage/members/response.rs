@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::manage::scopes::Scope;
+
 /// Success message.
 ///
 /// See the [Deepgram API Reference][api] for more info.
@@ -45,7 +47,7 @@ pub struct Member {
     pub last_name: Option<String>,
 
     #[allow(missing_docs)]
-    pub scopes: Vec<String>,
+    pub scopes: Vec<Scope>,
 
     #[allow(missing_docs)]
     pub email: String,
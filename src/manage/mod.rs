@@ -1,9 +1,85 @@
 //! Manage module
 
+pub mod auth;
 pub mod billing;
 pub mod invitations;
 pub mod keys;
 pub mod members;
+pub mod project_scoped;
 pub mod projects;
+pub mod reconcile;
 pub mod scopes;
 pub mod usage;
+
+use url::Url;
+
+use crate::Deepgram;
+
+impl Deepgram {
+    /// The URL of the management API path made of `segments`, each
+    /// percent-encoded, under `v1/` on the primary base URL.
+    pub(crate) fn manage_url<I>(&self, segments: I) -> Url
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        // Resolved like `listen_url` and `speak_url`, which join onto the
+        // directory of the base URL.
+        let mut url = self
+            .endpoints
+            .primary()
+            .join("./")
+            .expect("base URLs can be joined onto");
+        url.path_segments_mut()
+            .expect("base URLs can be a base")
+            .pop_if_empty()
+            .push("v1")
+            .extend(segments);
+        url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        routing::{Endpoint, Router, RoutingPolicy},
+        test_server::serve,
+        Deepgram,
+    };
+
+    #[test]
+    fn manage_url() {
+        let dg = Deepgram::new("token").unwrap();
+        assert_eq!(
+            dg.manage_url(["projects", "abc", "keys"]).as_str(),
+            "https://api.deepgram.com/v1/projects/abc/keys"
+        );
+    }
+
+    #[test]
+    fn manage_url_custom_host() {
+        let dg = Deepgram::with_base_url("http://localhost:8888/abc/").unwrap();
+        assert_eq!(
+            dg.manage_url(["projects", "abc", "keys"]).as_str(),
+            "http://localhost:8888/abc/v1/projects/abc/keys"
+        );
+    }
+
+    #[tokio::test]
+    async fn routes_by_policy() {
+        let eu = serve("200 OK", r#"{"projects":[]}"#).await;
+        let router = Router::new([
+            // Nothing listens on port 1 of the loopback address.
+            Endpoint::new("us", "http://127.0.0.1:1/")
+                .unwrap()
+                .in_region("us"),
+            Endpoint::new("eu", eu).unwrap().in_region("eu"),
+        ])
+        .unwrap()
+        .policy(RoutingPolicy::Region("eu".to_owned()));
+        let dg = Deepgram::with_router(router).unwrap();
+
+        let projects = dg.projects().list().await.unwrap();
+        assert!(projects.projects.is_empty());
+    }
+}
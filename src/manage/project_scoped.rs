@@ -0,0 +1,520 @@
+//! A client bound to a single Deepgram Project.
+//!
+//! See [`ProjectScoped`].
+
+use std::{error::Error, future::Future, time::Duration};
+
+use crate::{
+    manage::{
+        billing::{
+            response::{Balance, Balances},
+            watcher::BalanceWatcherBuilder,
+        },
+        invitations::{self, response::Invites},
+        keys::{
+            self,
+            response::{MemberAndApiKey, MembersAndApiKeys, NewApiKey, RotatedKey},
+        },
+        members, projects,
+        scopes::{self, Scope},
+        usage::{
+            get_fields_options, get_usage_options, list_requests_options,
+            response::{Fields, Request, Requests, UsageSummary},
+        },
+    },
+    Deepgram,
+};
+
+/// A [`Deepgram`] client bound to a single project, so that project
+/// management calls no longer take a `project_id`.
+///
+/// Constructed using [`Deepgram::for_project`], or
+/// [`Deepgram::for_current_project`] to use the project of the API key.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::env;
+/// #
+/// # use deepgram::{Deepgram, DeepgramError};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), DeepgramError> {
+/// # let deepgram_api_key =
+/// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+/// #
+/// let project = Deepgram::new(&deepgram_api_key)?
+///     .for_current_project()
+///     .await?;
+///
+/// let keys = project.keys().list().await?;
+/// let balances = project.billing().list_balance().await?;
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProjectScoped {
+    deepgram: Deepgram,
+    project_id: String,
+}
+
+/// The project's keys. See [`Keys`](keys::Keys).
+///
+/// Constructed using [`ProjectScoped::keys`].
+#[derive(Debug, Clone)]
+pub struct ProjectKeys<'a>(&'a ProjectScoped);
+
+/// The project's members. See [`Members`](members::Members).
+///
+/// Constructed using [`ProjectScoped::members`].
+#[derive(Debug, Clone)]
+pub struct ProjectMembers<'a>(&'a ProjectScoped);
+
+/// The scopes of the project's members. See [`Scopes`](scopes::Scopes).
+///
+/// Constructed using [`ProjectScoped::scopes`].
+#[derive(Debug, Clone)]
+pub struct ProjectScopes<'a>(&'a ProjectScoped);
+
+/// The project's invitations. See [`Invitations`](invitations::Invitations).
+///
+/// Constructed using [`ProjectScoped::invitations`].
+#[derive(Debug, Clone)]
+pub struct ProjectInvitations<'a>(&'a ProjectScoped);
+
+/// The project's usage. See [`Usage`](super::usage::Usage).
+///
+/// Constructed using [`ProjectScoped::usage`].
+#[derive(Debug, Clone)]
+pub struct ProjectUsage<'a>(&'a ProjectScoped);
+
+/// The project's balances. See [`Billing`](super::billing::Billing).
+///
+/// Constructed using [`ProjectScoped::billing`].
+#[derive(Debug, Clone)]
+pub struct ProjectBilling<'a>(&'a ProjectScoped);
+
+impl Deepgram {
+    /// Construct a new [`ProjectScoped`] bound to the specified project.
+    pub fn for_project(&self, project_id: impl Into<String>) -> ProjectScoped {
+        ProjectScoped {
+            deepgram: self.clone(),
+            project_id: project_id.into(),
+        }
+    }
+
+    /// Construct a new [`ProjectScoped`] bound to the project of the API key
+    /// or temporary token, as found by [`Deepgram::whoami`].
+    pub async fn for_current_project(&self) -> crate::Result<ProjectScoped> {
+        let details = self.whoami().await?;
+        Ok(self.for_project(details.project_uuid.to_string()))
+    }
+}
+
+impl ProjectScoped {
+    /// The ID of the project.
+    pub fn project_id(&self) -> &str {
+        &self.project_id
+    }
+
+    /// The underlying [`Deepgram`] client.
+    pub fn deepgram(&self) -> &Deepgram {
+        &self.deepgram
+    }
+
+    /// Get the project. See [`Projects::get`](projects::Projects::get).
+    pub async fn get(&self) -> crate::Result<projects::response::Project> {
+        self.deepgram.projects().get(&self.project_id).await
+    }
+
+    /// Update the project. See [`Projects::update`](projects::Projects::update).
+    pub async fn update(
+        &self,
+        options: &projects::options::Options,
+    ) -> crate::Result<projects::response::Message> {
+        self.deepgram
+            .projects()
+            .update(&self.project_id, options)
+            .await
+    }
+
+    /// Delete the project. See [`Projects::delete`](projects::Projects::delete).
+    pub async fn delete(&self) -> crate::Result<projects::response::Message> {
+        self.deepgram.projects().delete(&self.project_id).await
+    }
+
+    /// Construct a new [`ProjectKeys`].
+    pub fn keys(&self) -> ProjectKeys<'_> {
+        ProjectKeys(self)
+    }
+
+    /// Construct a new [`ProjectMembers`].
+    pub fn members(&self) -> ProjectMembers<'_> {
+        ProjectMembers(self)
+    }
+
+    /// Construct a new [`ProjectScopes`].
+    pub fn scopes(&self) -> ProjectScopes<'_> {
+        ProjectScopes(self)
+    }
+
+    /// Construct a new [`ProjectInvitations`].
+    pub fn invitations(&self) -> ProjectInvitations<'_> {
+        ProjectInvitations(self)
+    }
+
+    /// Construct a new [`ProjectUsage`].
+    pub fn usage(&self) -> ProjectUsage<'_> {
+        ProjectUsage(self)
+    }
+
+    /// Construct a new [`ProjectBilling`].
+    pub fn billing(&self) -> ProjectBilling<'_> {
+        ProjectBilling(self)
+    }
+}
+
+impl ProjectKeys<'_> {
+    /// See [`Keys::list`](keys::Keys::list).
+    pub async fn list(&self) -> crate::Result<MembersAndApiKeys> {
+        self.0.deepgram.keys().list(&self.0.project_id).await
+    }
+
+    /// See [`Keys::get`](keys::Keys::get).
+    pub async fn get(&self, key_id: &str) -> crate::Result<MemberAndApiKey> {
+        self.0.deepgram.keys().get(&self.0.project_id, key_id).await
+    }
+
+    /// See [`Keys::create`](keys::Keys::create).
+    pub async fn create(&self, options: &keys::options::Options) -> crate::Result<NewApiKey> {
+        self.0
+            .deepgram
+            .keys()
+            .create(&self.0.project_id, options)
+            .await
+    }
+
+    /// See [`Keys::delete`](keys::Keys::delete).
+    pub async fn delete(&self, key_id: &str) -> crate::Result<keys::response::Message> {
+        self.0
+            .deepgram
+            .keys()
+            .delete(&self.0.project_id, key_id)
+            .await
+    }
+
+    /// See [`Keys::rotate`](keys::Keys::rotate).
+    pub async fn rotate<S, F, E>(
+        &self,
+        key_id: &str,
+        grace_period: Duration,
+        sink: S,
+    ) -> crate::Result<RotatedKey>
+    where
+        S: FnOnce(NewApiKey) -> F,
+        F: Future<Output = Result<(), E>>,
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        self.0
+            .deepgram
+            .keys()
+            .rotate(&self.0.project_id, key_id, grace_period, sink)
+            .await
+    }
+
+    /// See [`Keys::expiring_within`](keys::Keys::expiring_within).
+    pub async fn expiring_within(&self, within: Duration) -> crate::Result<Vec<MemberAndApiKey>> {
+        self.0
+            .deepgram
+            .keys()
+            .expiring_within(&self.0.project_id, within)
+            .await
+    }
+}
+
+impl ProjectMembers<'_> {
+    /// See [`Members::list_members`](members::Members::list_members).
+    pub async fn list_members(&self) -> crate::Result<members::response::Members> {
+        self.0
+            .deepgram
+            .members()
+            .list_members(&self.0.project_id)
+            .await
+    }
+
+    /// See [`Members::remove_member`](members::Members::remove_member).
+    pub async fn remove_member(
+        &self,
+        member_id: &str,
+    ) -> crate::Result<members::response::Message> {
+        self.0
+            .deepgram
+            .members()
+            .remove_member(&self.0.project_id, member_id)
+            .await
+    }
+}
+
+impl ProjectScopes<'_> {
+    /// See [`Scopes::get_scope`](scopes::Scopes::get_scope).
+    pub async fn get_scope(&self, member_id: &str) -> crate::Result<scopes::response::Scopes> {
+        self.0
+            .deepgram
+            .scopes()
+            .get_scope(&self.0.project_id, member_id)
+            .await
+    }
+
+    /// See [`Scopes::update_scope`](scopes::Scopes::update_scope).
+    pub async fn update_scope(
+        &self,
+        member_id: &str,
+        scope: &Scope,
+    ) -> crate::Result<scopes::response::Message> {
+        self.0
+            .deepgram
+            .scopes()
+            .update_scope(&self.0.project_id, member_id, scope)
+            .await
+    }
+}
+
+impl ProjectInvitations<'_> {
+    /// See [`Invitations::list_invitations`](invitations::Invitations::list_invitations).
+    pub async fn list_invitations(&self) -> crate::Result<Invites> {
+        self.0
+            .deepgram
+            .invitations()
+            .list_invitations(&self.0.project_id)
+            .await
+    }
+
+    /// See [`Invitations::send_invitation`](invitations::Invitations::send_invitation).
+    pub async fn send_invitation(
+        &self,
+        options: &invitations::options::Options,
+    ) -> crate::Result<invitations::response::Message> {
+        self.0
+            .deepgram
+            .invitations()
+            .send_invitation(&self.0.project_id, options)
+            .await
+    }
+
+    /// See [`Invitations::delete_invitation`](invitations::Invitations::delete_invitation).
+    pub async fn delete_invitation(
+        &self,
+        email: &str,
+    ) -> crate::Result<invitations::response::Message> {
+        self.0
+            .deepgram
+            .invitations()
+            .delete_invitation(&self.0.project_id, email)
+            .await
+    }
+
+    /// See [`Invitations::leave_project`](invitations::Invitations::leave_project).
+    pub async fn leave_project(&self) -> crate::Result<invitations::response::Message> {
+        self.0
+            .deepgram
+            .invitations()
+            .leave_project(&self.0.project_id)
+            .await
+    }
+}
+
+impl ProjectUsage<'_> {
+    /// See [`Usage::list_requests`](super::usage::Usage::list_requests).
+    pub async fn list_requests(
+        &self,
+        options: &list_requests_options::Options,
+    ) -> crate::Result<Requests> {
+        self.0
+            .deepgram
+            .usage()
+            .list_requests(&self.0.project_id, options)
+            .await
+    }
+
+    /// See [`Usage::get_request`](super::usage::Usage::get_request).
+    pub async fn get_request(&self, request_id: &str) -> crate::Result<Request> {
+        self.0
+            .deepgram
+            .usage()
+            .get_request(&self.0.project_id, request_id)
+            .await
+    }
+
+    /// See [`Usage::get_usage`](super::usage::Usage::get_usage).
+    pub async fn get_usage(
+        &self,
+        options: &get_usage_options::Options,
+    ) -> crate::Result<UsageSummary> {
+        self.0
+            .deepgram
+            .usage()
+            .get_usage(&self.0.project_id, options)
+            .await
+    }
+
+    /// See [`Usage::get_fields`](super::usage::Usage::get_fields).
+    pub async fn get_fields(&self, options: &get_fields_options::Options) -> crate::Result<Fields> {
+        self.0
+            .deepgram
+            .usage()
+            .get_fields(&self.0.project_id, options)
+            .await
+    }
+}
+
+impl ProjectBilling<'_> {
+    /// See [`Billing::list_balance`](super::billing::Billing::list_balance).
+    pub async fn list_balance(&self) -> crate::Result<Balances> {
+        self.0
+            .deepgram
+            .billing()
+            .list_balance(&self.0.project_id)
+            .await
+    }
+
+    /// See [`Billing::get_balance`](super::billing::Billing::get_balance).
+    pub async fn get_balance(&self, balance_id: &str) -> crate::Result<Balance> {
+        self.0
+            .deepgram
+            .billing()
+            .get_balance(&self.0.project_id, balance_id)
+            .await
+    }
+
+    /// See [`Billing::watch`](super::billing::Billing::watch).
+    pub fn watch(&self) -> BalanceWatcherBuilder {
+        self.0
+            .deepgram
+            .billing()
+            .watch([self.0.project_id.as_str()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use futures::StreamExt;
+
+    use crate::{
+        manage::{
+            invitations, keys, projects,
+            scopes::Scope,
+            usage::{get_fields_options, get_usage_options, list_requests_options},
+        },
+        test_server::serve_with,
+        Deepgram,
+    };
+
+    #[tokio::test]
+    async fn passes_the_project_id() {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let url = serve_with({
+            let requests = requests.clone();
+            move |request_line| {
+                let target = request_line.split(' ').nth(1).unwrap_or_default();
+                let path = target.split('?').next().unwrap_or_default();
+                requests.lock().unwrap().push(path.to_owned());
+                ("500 Internal Server Error", String::new())
+            }
+        })
+        .await;
+        let dg = Deepgram::with_base_url_and_api_key(url, "token").unwrap();
+        let project = dg.for_project("abc");
+
+        let _ = project.get().await;
+        let _ = project
+            .update(
+                &projects::options::Options::builder()
+                    .name("Renamed")
+                    .build(),
+            )
+            .await;
+        let _ = project.delete().await;
+
+        let keys = project.keys();
+        let _ = keys.list().await;
+        let _ = keys.get("key").await;
+        let _ = keys
+            .create(&keys::options::Options::builder("CI", [Scope::Member]).build())
+            .await;
+        let _ = keys.delete("key").await;
+        let _ = keys
+            .rotate("key", Duration::ZERO, |_| async {
+                Ok::<_, std::io::Error>(())
+            })
+            .await;
+        let _ = keys.expiring_within(Duration::ZERO).await;
+
+        let _ = project.members().list_members().await;
+        let _ = project.members().remove_member("member").await;
+        let _ = project.scopes().get_scope("member").await;
+        let _ = project.scopes().update_scope("member", &Scope::Admin).await;
+
+        let invitations = project.invitations();
+        let _ = invitations.list_invitations().await;
+        let _ = invitations
+            .send_invitation(
+                &invitations::options::Options::builder("jane@example.com", Scope::Member).build(),
+            )
+            .await;
+        let _ = invitations.delete_invitation("jane@example.com").await;
+        let _ = invitations.leave_project().await;
+
+        let usage = project.usage();
+        let _ = usage
+            .list_requests(&list_requests_options::Options::builder().build())
+            .await;
+        let _ = usage.get_request("request").await;
+        let _ = usage
+            .get_usage(&get_usage_options::Options::builder().build())
+            .await;
+        let _ = usage
+            .get_fields(&get_fields_options::Options::builder().build())
+            .await;
+
+        let _ = project.billing().list_balance().await;
+        let _ = project.billing().get_balance("balance").await;
+        let mut watcher = project.billing().watch().start().unwrap();
+        let _ = watcher.next().await;
+
+        assert_eq!(
+            *requests.lock().unwrap(),
+            vec![
+                "/v1/projects/abc",
+                "/v1/projects/abc",
+                "/v1/projects/abc",
+                "/v1/projects/abc/keys",
+                "/v1/projects/abc/keys/key",
+                "/v1/projects/abc/keys",
+                "/v1/projects/abc/keys/key",
+                "/v1/projects/abc/keys/key",
+                "/v1/projects/abc/keys",
+                "/v1/projects/abc/members",
+                "/v1/projects/abc/members/member",
+                "/v1/projects/abc/members/member/scopes",
+                "/v1/projects/abc/members/member/scopes",
+                "/v1/projects/abc/invites",
+                "/v1/projects/abc/invites",
+                "/v1/projects/abc/invites/jane@example.com",
+                "/v1/projects/abc/leave",
+                "/v1/projects/abc/requests",
+                "/v1/projects/abc/requests/request",
+                "/v1/projects/abc/usage",
+                "/v1/projects/abc/usage/fields",
+                "/v1/projects/abc/balances",
+                "/v1/projects/abc/balances/balance",
+                "/v1/projects/abc/balances",
+            ]
+        );
+    }
+}
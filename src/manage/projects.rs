@@ -4,7 +4,7 @@
 //!
 //! [api]: https://developers.deepgram.com/api-reference/#projects
 
-use crate::{send_and_translate_response, Deepgram};
+use crate::{send_and_translate_management_response, Deepgram};
 
 use options::{Options, SerializableOptions};
 
@@ -70,9 +70,9 @@ impl Projects<'_> {
     /// # }
     /// ```
     pub async fn list(&self) -> crate::Result<response::Projects> {
-        let request = self.0.client.get("https://api.deepgram.com/v1/projects");
+        let request = self.0.client.get(self.0.manage_url(["projects"]));
 
-        send_and_translate_response(request).await
+        send_and_translate_management_response(self.0, request).await
     }
 
     /// Get a specific project.
@@ -107,9 +107,9 @@ impl Projects<'_> {
     /// # }
     /// ```
     pub async fn get(&self, project_id: &str) -> crate::Result<Project> {
-        let url = format!("https://api.deepgram.com/v1/projects/{}", project_id);
+        let url = self.0.manage_url(["projects", project_id]);
 
-        send_and_translate_response(self.0.client.get(url)).await
+        send_and_translate_management_response(self.0, self.0.client.get(url)).await
     }
 
     /// Update the specified project.
@@ -149,14 +149,14 @@ impl Projects<'_> {
     /// # }
     /// ```
     pub async fn update(&self, project_id: &str, options: &Options) -> crate::Result<Message> {
-        let url = format!("https://api.deepgram.com/v1/projects/{}", project_id);
+        let url = self.0.manage_url(["projects", project_id]);
         let request = self
             .0
             .client
             .patch(url)
             .json(&SerializableOptions::from(options));
 
-        send_and_translate_response(request).await
+        send_and_translate_management_response(self.0, request).await
     }
 
     /// Delete the specified project.
@@ -191,9 +191,9 @@ impl Projects<'_> {
     /// # }
     /// ```
     pub async fn delete(&self, project_id: &str) -> crate::Result<Message> {
-        let url = format!("https://api.deepgram.com/v1/projects/{}", project_id);
+        let url = self.0.manage_url(["projects", project_id]);
         let request = self.0.client.delete(url);
 
-        send_and_translate_response(request).await
+        send_and_translate_management_response(self.0, request).await
     }
 }
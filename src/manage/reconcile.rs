@@ -0,0 +1,252 @@
+//! Reconcile Deepgram Projects with a declarative description of their
+//! members and keys.
+//!
+//! Describe each project with a [`DesiredProject`], usually deserialized
+//! from a file as a [`DesiredState`](desired::DesiredState). Compare it with
+//! the live project using [`Reconcile::plan`], review the returned
+//! [`Plan`], and make the changes with [`Reconcile::apply`].
+
+use desired::DesiredProject;
+use plan::{Applied, ApplyMode, Change, Live, Plan};
+
+use crate::{
+    manage::{invitations, keys, projects},
+    Deepgram, DeepgramError,
+};
+
+pub mod desired;
+pub mod plan;
+
+/// Reconcile Deepgram Projects with a declarative description of their
+/// members and keys.
+///
+/// Constructed using [`Deepgram::reconcile`].
+#[derive(Debug, Clone)]
+pub struct Reconcile<'a>(&'a Deepgram);
+
+impl Deepgram {
+    /// Construct a new [`Reconcile`] from a [`Deepgram`].
+    pub fn reconcile(&self) -> Reconcile<'_> {
+        self.into()
+    }
+}
+
+impl<'a> From<&'a Deepgram> for Reconcile<'a> {
+    /// Construct a new [`Reconcile`] from a [`Deepgram`].
+    fn from(deepgram: &'a Deepgram) -> Self {
+        Self(deepgram)
+    }
+}
+
+impl Reconcile<'_> {
+    /// Compare a project with its desired state and plan the changes needed
+    /// to reconcile them.
+    ///
+    /// Nothing is changed until the plan is passed to [`Reconcile::apply`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::env;
+    /// #
+    /// # use deepgram::{
+    /// #     manage::reconcile::{desired::DesiredState, plan::ApplyMode},
+    /// #     Deepgram, DeepgramError,
+    /// # };
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let state: DesiredState = serde_json::from_str(&std::fs::read_to_string("projects.json")?)?;
+    ///
+    /// for project in &state.projects {
+    ///     let plan = dg_client.reconcile().plan(project).await?;
+    ///     println!("{plan}");
+    ///
+    ///     let applied = dg_client.reconcile().apply(&plan, ApplyMode::DryRun).await?;
+    ///     assert!(applied.new_keys.is_empty());
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn plan(&self, desired: &DesiredProject) -> crate::Result<Plan> {
+        let project_id = desired.project_id.as_str();
+        let live = Live {
+            project: self.0.projects().get(project_id).await?,
+            members: self.0.members().list_members(project_id).await?.members,
+            invites: self
+                .0
+                .invitations()
+                .list_invitations(project_id)
+                .await?
+                .invites,
+            keys: self.0.keys().list(project_id).await?.api_keys,
+        };
+
+        Ok(Plan {
+            project_id: desired.project_id.clone(),
+            changes: plan::diff(desired, &live),
+        })
+    }
+
+    /// Apply the changes in a plan, in order.
+    ///
+    /// With [`ApplyMode::DryRun`], nothing is changed and the changes that
+    /// would have been made are returned.
+    ///
+    /// If a change fails, later changes are not applied and
+    /// [`DeepgramError::PartiallyApplied`] is returned with the changes that
+    /// were made and the secrets of any keys that were created. Planning again
+    /// picks up the remaining changes.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::env;
+    /// #
+    /// # use deepgram::{
+    /// #     manage::{
+    /// #         reconcile::{
+    /// #             desired::{DesiredKey, DesiredProject},
+    /// #             plan::ApplyMode,
+    /// #         },
+    /// #         scopes::Scope,
+    /// #     },
+    /// #     Deepgram, DeepgramError,
+    /// # };
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// # let project_id =
+    /// #     env::var("DEEPGRAM_PROJECT_ID").expect("DEEPGRAM_PROJECT_ID environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let desired = DesiredProject::new(project_id)
+    ///     .member("ops@example.com", Scope::Admin)
+    ///     .key(DesiredKey::new("ingest", [Scope::Member]).tags(["prod"]));
+    ///
+    /// let plan = dg_client.reconcile().plan(&desired).await?;
+    /// let applied = dg_client.reconcile().apply(&plan, ApplyMode::Apply).await?;
+    ///
+    /// for key in applied.new_keys {
+    ///     println!("{}: {}", key.comment, key.key);
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn apply(&self, plan: &Plan, mode: ApplyMode) -> crate::Result<Applied> {
+        let mut applied = Applied {
+            project_id: plan.project_id.clone(),
+            dry_run: mode == ApplyMode::DryRun,
+            changes: Vec::new(),
+            new_keys: Vec::new(),
+        };
+
+        for change in &plan.changes {
+            if !applied.dry_run {
+                if let Err(err) = self
+                    .apply_change(&plan.project_id, change, &mut applied)
+                    .await
+                {
+                    return Err(DeepgramError::PartiallyApplied {
+                        applied: Box::new(applied),
+                        source: Box::new(err),
+                    });
+                }
+            }
+            applied.changes.push(change.clone());
+        }
+
+        Ok(applied)
+    }
+
+    async fn apply_change(
+        &self,
+        project_id: &str,
+        change: &Change,
+        applied: &mut Applied,
+    ) -> crate::Result<()> {
+        match change {
+            Change::UpdateProject { name, company } => {
+                let mut options = projects::options::Options::builder();
+                if let Some(name) = name {
+                    options = options.name(name);
+                }
+                if let Some(company) = company {
+                    options = options.company(company);
+                }
+                self.0
+                    .projects()
+                    .update(project_id, &options.build())
+                    .await?;
+            }
+            Change::SendInvitation { email, scope } => {
+                let options = invitations::options::Options::builder(email, scope.clone()).build();
+                self.0
+                    .invitations()
+                    .send_invitation(project_id, &options)
+                    .await?;
+            }
+            Change::DeleteInvitation { email } => {
+                self.0
+                    .invitations()
+                    .delete_invitation(project_id, email)
+                    .await?;
+            }
+            Change::UpdateMemberScope {
+                member_id, scope, ..
+            } => {
+                self.0
+                    .scopes()
+                    .update_scope(project_id, &member_id.to_string(), scope)
+                    .await?;
+            }
+            Change::RemoveMember { member_id, .. } => {
+                self.0
+                    .members()
+                    .remove_member(project_id, &member_id.to_string())
+                    .await?;
+            }
+            Change::CreateKey { key } => {
+                let options = key_options(key);
+                applied
+                    .new_keys
+                    .push(self.0.keys().create(project_id, &options).await?);
+            }
+            Change::ReplaceKey { api_key_id, key } => {
+                let options = key_options(key);
+                applied
+                    .new_keys
+                    .push(self.0.keys().create(project_id, &options).await?);
+                self.0
+                    .keys()
+                    .delete(project_id, &api_key_id.to_string())
+                    .await?;
+            }
+            Change::DeleteKey { api_key_id, .. } => {
+                self.0
+                    .keys()
+                    .delete(project_id, &api_key_id.to_string())
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn key_options(key: &desired::DesiredKey) -> keys::options::Options {
+    keys::options::Options::builder(&key.comment, key.scopes.iter().cloned())
+        .tag(key.tags.iter().map(String::as_str))
+        .build()
+}
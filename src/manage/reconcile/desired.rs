@@ -0,0 +1,165 @@
+//! The desired state of Deepgram Projects, usually loaded from a file.
+//!
+//! See [`Reconcile`](super::Reconcile).
+
+use serde::{Deserialize, Serialize};
+
+use crate::manage::scopes::Scope;
+
+/// The desired state of a set of Deepgram Projects.
+///
+/// Can be deserialized from any serde format, such as JSON:
+///
+/// ```
+/// # use deepgram::manage::{reconcile::desired::DesiredState, scopes::Scope};
+/// let state: DesiredState = serde_json::from_str(r#"{
+///     "projects": [{
+///         "project_id": "a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8",
+///         "name": "Support",
+///         "members": [{ "email": "ops@example.com", "scope": "admin" }],
+///         "keys": [{ "comment": "ingest", "scopes": ["member"], "tags": ["prod"] }],
+///         "prune": true
+///     }]
+/// }"#).unwrap();
+///
+/// assert_eq!(state.projects[0].members[0].scope, Scope::Admin);
+/// ```
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DesiredState {
+    #[allow(missing_docs)]
+    #[serde(default)]
+    pub projects: Vec<DesiredProject>,
+}
+
+/// The desired state of a single project.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DesiredProject {
+    /// The ID of an existing project.
+    pub project_id: String,
+
+    /// The project name, or [`None`] to leave it unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The company name, or [`None`] to leave it unchanged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub company: Option<String>,
+
+    /// Members who should belong to the project. Missing members are invited.
+    #[serde(default)]
+    pub members: Vec<DesiredMember>,
+
+    /// API keys that should exist, identified by their comment.
+    #[serde(default)]
+    pub keys: Vec<DesiredKey>,
+
+    /// Whether to remove members, invitations and keys that are not listed.
+    /// Owners are never removed.
+    #[serde(default)]
+    pub prune: bool,
+}
+
+/// A member of a [`DesiredProject`].
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DesiredMember {
+    /// Compared case-insensitively with the emails of existing members.
+    pub email: String,
+
+    #[allow(missing_docs)]
+    pub scope: Scope,
+}
+
+/// An API key of a [`DesiredProject`].
+///
+/// API keys cannot be changed once created, so a key whose scopes or tags
+/// differ is replaced with a new one.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct DesiredKey {
+    /// Identifies the key, so should be unique within the project.
+    pub comment: String,
+
+    #[allow(missing_docs)]
+    pub scopes: Vec<Scope>,
+
+    #[allow(missing_docs)]
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl DesiredState {
+    /// Construct a new [`DesiredState`].
+    pub fn new(projects: impl IntoIterator<Item = DesiredProject>) -> Self {
+        Self {
+            projects: projects.into_iter().collect(),
+        }
+    }
+}
+
+impl DesiredProject {
+    /// Construct a new [`DesiredProject`] with no members or keys.
+    pub fn new(project_id: impl Into<String>) -> Self {
+        Self {
+            project_id: project_id.into(),
+            name: None,
+            company: None,
+            members: Vec::new(),
+            keys: Vec::new(),
+            prune: false,
+        }
+    }
+
+    /// Set the project name.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the company name.
+    pub fn company(mut self, company: impl Into<String>) -> Self {
+        self.company = Some(company.into());
+        self
+    }
+
+    /// Add a member.
+    pub fn member(mut self, email: impl Into<String>, scope: Scope) -> Self {
+        self.members.push(DesiredMember {
+            email: email.into(),
+            scope,
+        });
+        self
+    }
+
+    /// Add an API key.
+    pub fn key(mut self, key: DesiredKey) -> Self {
+        self.keys.push(key);
+        self
+    }
+
+    /// Set whether to remove members, invitations and keys that are not
+    /// listed.
+    pub fn prune(mut self, prune: bool) -> Self {
+        self.prune = prune;
+        self
+    }
+}
+
+impl DesiredKey {
+    /// Construct a new [`DesiredKey`] with no tags.
+    pub fn new(comment: impl Into<String>, scopes: impl IntoIterator<Item = Scope>) -> Self {
+        Self {
+            comment: comment.into(),
+            scopes: scopes.into_iter().collect(),
+            tags: Vec::new(),
+        }
+    }
+
+    /// Add tags.
+    pub fn tags<'a>(mut self, tags: impl IntoIterator<Item = &'a str>) -> Self {
+        self.tags.extend(tags.into_iter().map(String::from));
+        self
+    }
+}
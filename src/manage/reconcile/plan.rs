@@ -0,0 +1,439 @@
+//! The changes needed to reconcile a project with its desired state.
+//!
+//! See [`Reconcile`](super::Reconcile).
+
+use std::{collections::HashSet, fmt, hash::Hash};
+
+use uuid::Uuid;
+
+use super::desired::{DesiredKey, DesiredProject};
+use crate::manage::{
+    invitations::response::Invite,
+    keys::response::{MemberAndApiKey, NewApiKey},
+    members::response::Member,
+    projects::response::Project,
+    scopes::Scope,
+};
+
+/// Returned by [`Reconcile::plan`](super::Reconcile::plan).
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub struct Plan {
+    #[allow(missing_docs)]
+    pub project_id: String,
+
+    /// The changes in the order they will be applied.
+    pub changes: Vec<Change>,
+}
+
+/// A single change within a [`Plan`].
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Change {
+    /// Update the project name and company. Fields that are already up to
+    /// date are [`None`].
+    UpdateProject {
+        #[allow(missing_docs)]
+        name: Option<String>,
+
+        #[allow(missing_docs)]
+        company: Option<String>,
+    },
+
+    /// Invite someone who is not yet a member.
+    SendInvitation {
+        #[allow(missing_docs)]
+        email: String,
+
+        #[allow(missing_docs)]
+        scope: Scope,
+    },
+
+    /// Cancel a pending invitation.
+    DeleteInvitation {
+        #[allow(missing_docs)]
+        email: String,
+    },
+
+    /// Change the scope of an existing member.
+    UpdateMemberScope {
+        #[allow(missing_docs)]
+        member_id: Uuid,
+
+        #[allow(missing_docs)]
+        email: String,
+
+        #[allow(missing_docs)]
+        scope: Scope,
+    },
+
+    /// Remove a member from the project.
+    RemoveMember {
+        #[allow(missing_docs)]
+        member_id: Uuid,
+
+        #[allow(missing_docs)]
+        email: String,
+    },
+
+    /// Create a new API key.
+    CreateKey {
+        #[allow(missing_docs)]
+        key: DesiredKey,
+    },
+
+    /// Create a new API key and delete the existing one, whose scopes or
+    /// tags differ.
+    ReplaceKey {
+        #[allow(missing_docs)]
+        api_key_id: Uuid,
+
+        #[allow(missing_docs)]
+        key: DesiredKey,
+    },
+
+    /// Delete an API key.
+    DeleteKey {
+        #[allow(missing_docs)]
+        api_key_id: Uuid,
+
+        #[allow(missing_docs)]
+        comment: String,
+    },
+}
+
+/// Returned by [`Reconcile::apply`](super::Reconcile::apply).
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub struct Applied {
+    #[allow(missing_docs)]
+    pub project_id: String,
+
+    /// Whether this was a dry run, in which case nothing was changed.
+    pub dry_run: bool,
+
+    /// The changes that were applied, or would have been in a dry run.
+    pub changes: Vec<Change>,
+
+    /// Keys created by [`Change::CreateKey`] and [`Change::ReplaceKey`].
+    ///
+    /// This is the only chance to read their secrets.
+    pub new_keys: Vec<NewApiKey>,
+}
+
+/// How [`Reconcile::apply`](super::Reconcile::apply) should apply a plan.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[non_exhaustive]
+pub enum ApplyMode {
+    /// Make every change in the plan.
+    Apply,
+
+    /// Change nothing, only report what would be changed.
+    DryRun,
+}
+
+/// The live state of a project.
+pub(super) struct Live {
+    pub(super) project: Project,
+    pub(super) members: Vec<Member>,
+    pub(super) invites: Vec<Invite>,
+    pub(super) keys: Vec<MemberAndApiKey>,
+}
+
+impl Plan {
+    /// Whether the project already matches its desired state.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "project {}: up to date", self.project_id);
+        }
+
+        write!(f, "project {}:", self.project_id)?;
+        for change in &self.changes {
+            write!(f, "\n  - {change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::UpdateProject { name, company } => {
+                f.write_str("update project")?;
+                if let Some(name) = name {
+                    write!(f, " name to {name:?}")?;
+                }
+                if let Some(company) = company {
+                    write!(f, " company to {company:?}")?;
+                }
+                Ok(())
+            }
+            Change::SendInvitation { email, scope } => write!(f, "invite {email} as {scope}"),
+            Change::DeleteInvitation { email } => write!(f, "cancel invitation to {email}"),
+            Change::UpdateMemberScope { email, scope, .. } => {
+                write!(f, "change scope of {email} to {scope}")
+            }
+            Change::RemoveMember { email, .. } => write!(f, "remove member {email}"),
+            Change::CreateKey { key } => write!(f, "create key {:?}", key.comment),
+            Change::ReplaceKey { api_key_id, key } => {
+                write!(f, "replace key {:?} ({api_key_id})", key.comment)
+            }
+            Change::DeleteKey {
+                api_key_id,
+                comment,
+            } => write!(f, "delete key {comment:?} ({api_key_id})"),
+        }
+    }
+}
+
+/// The changes that take `live` to `desired`.
+pub(super) fn diff(desired: &DesiredProject, live: &Live) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    let name = desired
+        .name
+        .clone()
+        .filter(|name| *name != live.project.name);
+    let company = desired
+        .company
+        .clone()
+        .filter(|company| live.project.company.as_ref() != Some(company));
+    if name.is_some() || company.is_some() {
+        changes.push(Change::UpdateProject { name, company });
+    }
+
+    let same_email = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+    for wanted in &desired.members {
+        if let Some(member) = live
+            .members
+            .iter()
+            .find(|member| same_email(&member.email, &wanted.email))
+        {
+            if !member.scopes.contains(&wanted.scope) {
+                changes.push(Change::UpdateMemberScope {
+                    member_id: member.member_id,
+                    email: member.email.clone(),
+                    scope: wanted.scope.clone(),
+                });
+            }
+            continue;
+        }
+
+        let invite = live
+            .invites
+            .iter()
+            .find(|invite| same_email(&invite.email, &wanted.email));
+        match invite {
+            Some(invite) if invite.scope == wanted.scope => {}
+            Some(invite) => {
+                changes.push(Change::DeleteInvitation {
+                    email: invite.email.clone(),
+                });
+                changes.push(Change::SendInvitation {
+                    email: wanted.email.clone(),
+                    scope: wanted.scope.clone(),
+                });
+            }
+            None => changes.push(Change::SendInvitation {
+                email: wanted.email.clone(),
+                scope: wanted.scope.clone(),
+            }),
+        }
+    }
+
+    if desired.prune {
+        let wanted = |email: &str| {
+            desired
+                .members
+                .iter()
+                .any(|wanted| same_email(&wanted.email, email))
+        };
+        for invite in &live.invites {
+            if !wanted(&invite.email) {
+                changes.push(Change::DeleteInvitation {
+                    email: invite.email.clone(),
+                });
+            }
+        }
+        for member in &live.members {
+            if !wanted(&member.email) && !member.scopes.contains(&Scope::Owner) {
+                changes.push(Change::RemoveMember {
+                    member_id: member.member_id,
+                    email: member.email.clone(),
+                });
+            }
+        }
+    }
+
+    let mut claimed = vec![false; live.keys.len()];
+    for wanted in &desired.keys {
+        let existing = live
+            .keys
+            .iter()
+            .enumerate()
+            .find(|(i, key)| !claimed[*i] && key.api_key.comment == wanted.comment);
+        match existing {
+            Some((i, key)) => {
+                claimed[i] = true;
+                let tags = key.api_key.tags.as_deref().unwrap_or_default();
+                if !same_items(&key.api_key.scopes, &wanted.scopes)
+                    || !same_items(tags, &wanted.tags)
+                {
+                    changes.push(Change::ReplaceKey {
+                        api_key_id: key.api_key.api_key_id,
+                        key: wanted.clone(),
+                    });
+                }
+            }
+            None => changes.push(Change::CreateKey {
+                key: wanted.clone(),
+            }),
+        }
+    }
+
+    if desired.prune {
+        for (key, _) in live
+            .keys
+            .iter()
+            .zip(&claimed)
+            .filter(|(_, claimed)| !**claimed)
+        {
+            changes.push(Change::DeleteKey {
+                api_key_id: key.api_key.api_key_id,
+                comment: key.api_key.comment.clone(),
+            });
+        }
+    }
+
+    changes
+}
+
+/// Whether `a` and `b` contain the same items, ignoring order and repeats.
+fn same_items<T: Eq + Hash>(a: &[T], b: &[T]) -> bool {
+    a.iter().collect::<HashSet<_>>() == b.iter().collect::<HashSet<_>>()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{diff, Change, Live};
+    use crate::{
+        common::timestamp::Timestamp,
+        manage::{
+            invitations::response::Invite,
+            keys::response::{self, ApiKey, MemberAndApiKey},
+            members::response::Member,
+            projects::response::Project,
+            reconcile::desired::{DesiredKey, DesiredProject},
+            scopes::Scope,
+        },
+    };
+
+    fn member(member_id: u128, email: &str, scope: Scope) -> Member {
+        Member {
+            member_id: Uuid::from_u128(member_id),
+            first_name: None,
+            last_name: None,
+            scopes: vec![scope],
+            email: email.to_owned(),
+        }
+    }
+
+    fn invite(email: &str) -> Invite {
+        Invite {
+            email: email.to_owned(),
+            scope: Scope::Member,
+        }
+    }
+
+    fn key(api_key_id: u128, comment: &str, tags: Option<Vec<String>>) -> MemberAndApiKey {
+        MemberAndApiKey {
+            member: response::Member {
+                member_id: Uuid::from_u128(0xa),
+                first_name: None,
+                last_name: None,
+                email: "owner@example.com".to_owned(),
+            },
+            api_key: ApiKey {
+                api_key_id: Uuid::from_u128(api_key_id),
+                comment: comment.to_owned(),
+                scopes: vec![Scope::Member],
+                tags,
+                created: Timestamp::from_raw("2024-01-01T00:00:00Z".to_owned()),
+                expiration_date: None,
+            },
+        }
+    }
+
+    fn live() -> Live {
+        Live {
+            project: Project {
+                project_id: Uuid::from_u128(1),
+                name: "Support".to_owned(),
+                company: Some("Example".to_owned()),
+            },
+            members: vec![
+                member(0xa, "owner@example.com", Scope::Owner),
+                member(0xb, "dev@example.com", Scope::Member),
+                member(0xc, "former@example.com", Scope::Member),
+            ],
+            invites: vec![invite("new@example.com"), invite("stale@example.com")],
+            keys: vec![
+                key(0x100, "ingest", Some(vec!["prod".to_owned()])),
+                key(0x200, "legacy", None),
+            ],
+        }
+    }
+
+    #[test]
+    fn matching_state_has_no_changes() {
+        let desired = DesiredProject::new("00000000-0000-0000-0000-000000000001")
+            .name("Support")
+            .member("OWNER@example.com", Scope::Owner)
+            .member("dev@example.com", Scope::Member)
+            .member("new@example.com", Scope::Member)
+            .key(DesiredKey::new("ingest", [Scope::Member]).tags(["prod"]));
+
+        assert_eq!(diff(&desired, &live()), vec![]);
+    }
+
+    #[test]
+    fn plans_changes() {
+        let desired = DesiredProject::new("00000000-0000-0000-0000-000000000001")
+            .company("Example Inc")
+            .member("owner@example.com", Scope::Owner)
+            .member("dev@example.com", Scope::Admin)
+            .member("new@example.com", Scope::Admin)
+            .member("hire@example.com", Scope::Member)
+            .key(DesiredKey::new("ingest", [Scope::Member]).tags(["prod", "eu"]))
+            .key(DesiredKey::new("reports", [Scope::UsageRead]))
+            .prune(true);
+
+        let changes: Vec<String> = diff(&desired, &live())
+            .iter()
+            .map(Change::to_string)
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                "update project company to \"Example Inc\"",
+                "change scope of dev@example.com to admin",
+                "cancel invitation to new@example.com",
+                "invite new@example.com as admin",
+                "invite hire@example.com as member",
+                "cancel invitation to stale@example.com",
+                "remove member former@example.com",
+                "replace key \"ingest\" (00000000-0000-0000-0000-000000000100)",
+                "create key \"reports\"",
+                "delete key \"legacy\" (00000000-0000-0000-0000-000000000200)",
+            ]
+        );
+    }
+}
@@ -4,14 +4,200 @@
 //!
 //! [api]: https://developers.deepgram.com/api-reference/#scopes
 
-use serde::Serialize;
+use std::{convert::Infallible, fmt, str::FromStr};
 
-use crate::{send_and_translate_response, Deepgram};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{send_and_translate_management_response, Deepgram};
 
 use response::Message;
 
 pub mod response;
 
+/// A permission granted to a member or API key within a project.
+///
+/// The roles [`Scope::Owner`], [`Scope::Admin`] and [`Scope::Member`] grant
+/// groups of the finer-grained scopes. Scopes this version of the SDK does
+/// not know about are kept in [`Scope::Other`].
+///
+/// See the [Deepgram Roles docs][docs] for more info.
+///
+/// [docs]: https://developers.deepgram.com/docs/working-with-roles
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[non_exhaustive]
+pub enum Scope {
+    #[allow(missing_docs)]
+    Member,
+
+    #[allow(missing_docs)]
+    Admin,
+
+    #[allow(missing_docs)]
+    Owner,
+
+    /// `project:read`
+    ProjectRead,
+
+    /// `project:write`
+    ProjectWrite,
+
+    /// `keys:read`
+    KeysRead,
+
+    /// `keys:write`
+    KeysWrite,
+
+    /// `members:read`
+    MembersRead,
+
+    /// `members:write`
+    MembersWrite,
+
+    /// `usage:read`
+    UsageRead,
+
+    /// `usage:write`
+    UsageWrite,
+
+    /// `billing:read`
+    BillingRead,
+
+    /// `billing:write`
+    BillingWrite,
+
+    /// Any other scope, as sent to or received from Deepgram.
+    Other(String),
+}
+
+impl Scope {
+    /// The scope as Deepgram spells it, such as `"keys:read"`.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Scope::Member => "member",
+            Scope::Admin => "admin",
+            Scope::Owner => "owner",
+            Scope::ProjectRead => "project:read",
+            Scope::ProjectWrite => "project:write",
+            Scope::KeysRead => "keys:read",
+            Scope::KeysWrite => "keys:write",
+            Scope::MembersRead => "members:read",
+            Scope::MembersWrite => "members:write",
+            Scope::UsageRead => "usage:read",
+            Scope::UsageWrite => "usage:write",
+            Scope::BillingRead => "billing:read",
+            Scope::BillingWrite => "billing:write",
+            Scope::Other(scope) => scope,
+        }
+    }
+
+    /// Whether this scope grants `required`.
+    ///
+    /// Every scope grants itself, and a `:write` scope grants the `:read`
+    /// scope of the same resource. [`Scope::Owner`] grants every scope,
+    /// [`Scope::Admin`] every scope but [`Scope::Owner`], and
+    /// [`Scope::Member`] grants reading the project and its usage and
+    /// managing API keys.
+    ///
+    /// This mirrors Deepgram's documented roles, but Deepgram remains the
+    /// authority on what a request is allowed to do.
+    ///
+    /// ```
+    /// use deepgram::manage::scopes::Scope;
+    ///
+    /// assert!(Scope::Admin.grants(&Scope::UsageRead));
+    /// assert!(Scope::KeysWrite.grants(&Scope::KeysRead));
+    /// assert!(!Scope::Member.grants(&Scope::MembersWrite));
+    /// ```
+    pub fn grants(&self, required: &Scope) -> bool {
+        if self == required {
+            return true;
+        }
+
+        match self {
+            Scope::Owner => true,
+            Scope::Admin => *required != Scope::Owner,
+            Scope::Member => matches!(
+                required,
+                Scope::ProjectRead | Scope::KeysRead | Scope::KeysWrite | Scope::UsageRead
+            ),
+            _ => match (
+                self.as_str().split_once(':'),
+                required.as_str().split_once(':'),
+            ) {
+                (Some((resource, "write")), Some((required_resource, "read"))) => {
+                    resource == required_resource
+                }
+                _ => false,
+            },
+        }
+    }
+
+    /// Whether any of `scopes` [grants](Self::grants) `required`, such as
+    /// to check the scopes of an API key before using it.
+    ///
+    /// ```
+    /// use deepgram::manage::scopes::Scope;
+    ///
+    /// let scopes = [Scope::Member, Scope::UsageWrite];
+    /// assert!(Scope::any_grants(&scopes, &Scope::UsageRead));
+    /// assert!(!Scope::any_grants(&scopes, &Scope::BillingRead));
+    /// ```
+    pub fn any_grants<'a>(scopes: impl IntoIterator<Item = &'a Scope>, required: &Scope) -> bool {
+        scopes.into_iter().any(|scope| scope.grants(required))
+    }
+}
+
+impl FromStr for Scope {
+    type Err = Infallible;
+
+    fn from_str(scope: &str) -> Result<Self, Self::Err> {
+        Ok(match scope {
+            "member" => Scope::Member,
+            "admin" => Scope::Admin,
+            "owner" => Scope::Owner,
+            "project:read" => Scope::ProjectRead,
+            "project:write" => Scope::ProjectWrite,
+            "keys:read" => Scope::KeysRead,
+            "keys:write" => Scope::KeysWrite,
+            "members:read" => Scope::MembersRead,
+            "members:write" => Scope::MembersWrite,
+            "usage:read" => Scope::UsageRead,
+            "usage:write" => Scope::UsageWrite,
+            "billing:read" => Scope::BillingRead,
+            "billing:write" => Scope::BillingWrite,
+            _ => Scope::Other(scope.to_owned()),
+        })
+    }
+}
+
+impl From<String> for Scope {
+    fn from(scope: String) -> Self {
+        match scope.parse() {
+            Ok(Scope::Other(_)) => Scope::Other(scope),
+            Ok(scope) => scope,
+            Err(never) => match never {},
+        }
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Scope {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Scope {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Scope::from)
+    }
+}
+
 /// Manage the permissions of a Deepgram Project.
 ///
 /// Constructed using [`Deepgram::scopes`].
@@ -76,12 +262,11 @@ impl Scopes<'_> {
         project_id: &str,
         member_id: &str,
     ) -> crate::Result<response::Scopes> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/members/{}/scopes ",
-            project_id, member_id
-        );
+        let url = self
+            .0
+            .manage_url(["projects", project_id, "members", member_id, "scopes"]);
 
-        send_and_translate_response(self.0.client.get(url)).await
+        send_and_translate_management_response(self.0, self.0.client.get(url)).await
     }
 
     /// Update the specified project scopes assigned to the specified member.
@@ -95,7 +280,7 @@ impl Scopes<'_> {
     /// ```no_run
     /// # use std::env;
     /// #
-    /// # use deepgram::{Deepgram, DeepgramError};
+    /// # use deepgram::{manage::scopes::Scope, Deepgram, DeepgramError};
     /// #
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), DeepgramError> {
@@ -112,7 +297,7 @@ impl Scopes<'_> {
     ///
     /// dg_client
     ///     .scopes()
-    ///     .update_scope(&project_id, &member_id, "member")
+    ///     .update_scope(&project_id, &member_id, &Scope::Member)
     ///     .await?;
     /// #
     /// # Ok(())
@@ -122,19 +307,53 @@ impl Scopes<'_> {
         &self,
         project_id: &str,
         member_id: &str,
-        scope: &str,
+        scope: &Scope,
     ) -> crate::Result<Message> {
         #[derive(Serialize)]
-        struct Scope<'a> {
-            scope: &'a str,
+        struct UpdateScope<'a> {
+            scope: &'a Scope,
         }
 
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/members/{}/scopes",
-            project_id, member_id
+        let url = self
+            .0
+            .manage_url(["projects", project_id, "members", member_id, "scopes"]);
+        let request = self.0.client.put(url).json(&UpdateScope { scope });
+
+        send_and_translate_management_response(self.0, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scope;
+
+    #[test]
+    fn round_trips_through_strings() {
+        let scopes: Vec<Scope> =
+            serde_json::from_str(r#"["admin","keys:write","usage:raed"]"#).unwrap();
+        assert_eq!(
+            scopes,
+            vec![
+                Scope::Admin,
+                Scope::KeysWrite,
+                Scope::Other("usage:raed".to_owned())
+            ]
         );
-        let request = self.0.client.put(url).json(&Scope { scope });
+        assert_eq!(
+            serde_json::to_string(&scopes).unwrap(),
+            r#"["admin","keys:write","usage:raed"]"#
+        );
+    }
 
-        send_and_translate_response(request).await
+    #[test]
+    fn roles_grant_lower_scopes() {
+        assert!(Scope::Owner.grants(&Scope::Other("anything".to_owned())));
+        assert!(!Scope::Admin.grants(&Scope::Owner));
+        assert!(Scope::Member.grants(&Scope::KeysWrite));
+        assert!(!Scope::Member.grants(&Scope::Admin));
+        assert!(Scope::UsageWrite.grants(&Scope::UsageRead));
+        assert!(!Scope::UsageRead.grants(&Scope::UsageWrite));
+        assert!(!Scope::UsageWrite.grants(&Scope::KeysRead));
+        assert!(Scope::Other("team:write".to_owned()).grants(&Scope::Other("team:read".to_owned())));
     }
 }
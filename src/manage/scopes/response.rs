@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::Scope;
+
 /// Success message.
 ///
 /// See the [Deepgram API Reference][api] for more info.
@@ -23,5 +25,5 @@ pub struct Message {
 #[non_exhaustive]
 pub struct Scopes {
     #[allow(missing_docs)]
-    pub scopes: Vec<String>,
+    pub scopes: Vec<Scope>,
 }
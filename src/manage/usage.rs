@@ -4,15 +4,20 @@
 //!
 //! [api]: https://developers.deepgram.com/api-reference/#usage
 
+use report::{GroupBy, UsageReport};
 use response::{Fields, Request, Requests, UsageSummary};
 
-use crate::{send_and_translate_response, Deepgram};
+use crate::{common::timestamp::Timestamp, send_and_translate_management_response, Deepgram};
 
 pub mod get_fields_options;
 pub mod get_usage_options;
 pub mod list_requests_options;
+pub mod report;
 pub mod response;
 
+/// The number of requests fetched per page by [`Usage::report`].
+const REPORT_PAGE_SIZE: usize = 100;
+
 /// Get the usage data of a Deepgram Project.
 ///
 /// Constructed using [`Deepgram::usage`].
@@ -78,17 +83,14 @@ impl Usage<'_> {
         project_id: &str,
         options: &list_requests_options::Options,
     ) -> crate::Result<Requests> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/requests",
-            project_id,
-        );
+        let url = self.0.manage_url(["projects", project_id, "requests"]);
         let request = self
             .0
             .client
             .get(url)
             .query(&list_requests_options::SerializableOptions::from(options));
 
-        send_and_translate_response(request).await
+        send_and_translate_management_response(self.0, request).await
     }
 
     /// Get the details of the specified request sent to the Deepgram API for the specified project.
@@ -129,12 +131,11 @@ impl Usage<'_> {
     /// # }
     /// ```
     pub async fn get_request(&self, project_id: &str, request_id: &str) -> crate::Result<Request> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/requests/{}",
-            project_id, request_id,
-        );
+        let url = self
+            .0
+            .manage_url(["projects", project_id, "requests", request_id]);
 
-        send_and_translate_response(self.0.client.get(url)).await
+        send_and_translate_management_response(self.0, self.0.client.get(url)).await
     }
 
     /// Get a summary of usage statistics.
@@ -177,14 +178,14 @@ impl Usage<'_> {
         project_id: &str,
         options: &get_usage_options::Options,
     ) -> crate::Result<UsageSummary> {
-        let url = format!("https://api.deepgram.com/v1/projects/{}/usage", project_id);
+        let url = self.0.manage_url(["projects", project_id, "usage"]);
         let request = self
             .0
             .client
             .get(url)
             .query(&get_usage_options::SerializableOptions::from(options));
 
-        send_and_translate_response(request).await
+        send_and_translate_management_response(self.0, request).await
     }
 
     /// Get the features, models, tags, languages, and processing method used for requests in the specified project.
@@ -227,16 +228,108 @@ impl Usage<'_> {
         project_id: &str,
         options: &get_fields_options::Options,
     ) -> crate::Result<Fields> {
-        let url = format!(
-            "https://api.deepgram.com/v1/projects/{}/usage/fields",
-            project_id,
-        );
+        let url = self
+            .0
+            .manage_url(["projects", project_id, "usage", "fields"]);
         let request = self
             .0
             .client
             .get(url)
             .query(&get_fields_options::SerializableOptions::from(options));
 
-        send_and_translate_response(request).await
+        send_and_translate_management_response(self.0, request).await
+    }
+
+    /// Build a report of the requests made between `start` and `end` in
+    /// every project the API key can access, grouped by `group_by`.
+    ///
+    /// Every page of [`Usage::list_requests`] is fetched for each project,
+    /// and model UUIDs are resolved to names with [`Usage::get_fields`].
+    /// The report can be exported with [`UsageReport::to_csv`] or
+    /// [`UsageReport::to_json`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::env;
+    /// #
+    /// # use deepgram::{
+    /// #     common::timestamp::Timestamp, manage::usage::report::GroupBy, Deepgram, DeepgramError,
+    /// # };
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let report = dg_client
+    ///     .usage()
+    ///     .report(
+    ///         Timestamp::parse("2024-01-01")?,
+    ///         Timestamp::parse("2024-02-01")?,
+    ///         GroupBy::Tag,
+    ///     )
+    ///     .await?;
+    /// std::fs::write("chargeback-2024-01.csv", report.to_csv())?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn report(
+        &self,
+        start: impl Into<Timestamp>,
+        end: impl Into<Timestamp>,
+        group_by: GroupBy,
+    ) -> crate::Result<UsageReport> {
+        let (start, end) = (start.into(), end.into());
+        let mut projects = self.0.projects().list().await?.projects;
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut rows = Vec::new();
+        for project in &projects {
+            let project_id = project.project_id.to_string();
+
+            let mut requests = Vec::new();
+            for page in 0.. {
+                let options = list_requests_options::Options::builder()
+                    .start(start.clone())
+                    .end(end.clone())
+                    .page(page)
+                    .limit(REPORT_PAGE_SIZE)
+                    .build();
+                let page = self.list_requests(&project_id, &options).await?.requests;
+                let last_page = page.len() < REPORT_PAGE_SIZE;
+                requests.extend(page);
+                if last_page {
+                    break;
+                }
+            }
+
+            let fields = if group_by == GroupBy::Model {
+                let options = get_fields_options::Options::builder()
+                    .start(start.clone())
+                    .end(end.clone())
+                    .build();
+                self.get_fields(&project_id, &options).await?
+            } else {
+                Fields {
+                    tags: Vec::new(),
+                    models: Vec::new(),
+                    processing_methods: Vec::new(),
+                    features: Vec::new(),
+                }
+            };
+
+            rows.extend(report::aggregate(project, &requests, &fields, group_by));
+        }
+
+        Ok(UsageReport {
+            start,
+            end,
+            group_by,
+            rows,
+        })
     }
 }
@@ -6,6 +6,8 @@
 
 use serde::{ser::SerializeSeq, Serialize};
 
+use crate::common::timestamp::Timestamp;
+
 /// Used as a parameter for [`Usage::get_usage`](super::Usage::get_usage).
 ///
 /// See the [Deepgram API Reference][api] for more info.
@@ -13,8 +15,8 @@ use serde::{ser::SerializeSeq, Serialize};
 /// [api]: https://developers.deepgram.com/api-reference/#usage-summary
 #[derive(Debug, PartialEq, Clone)]
 pub struct Options {
-    start: Option<String>,
-    end: Option<String>,
+    start: Option<Timestamp>,
+    end: Option<Timestamp>,
     accessor: Option<String>,
     tags: Vec<String>,
     methods: Vec<Method>,
@@ -109,32 +111,42 @@ impl OptionsBuilder {
 
     /// Set the time range start date.
     ///
+    /// Takes a [`Timestamp`], either checked from a string such as
+    /// `"2024-01-31"` with [`Timestamp::parse`] or converted from a
+    /// [`SystemTime`](std::time::SystemTime).
+    ///
     /// # Examples
     ///
     /// ```
+    /// # use deepgram::common::timestamp::Timestamp;
     /// # use deepgram::manage::usage::get_usage_options::Options;
     /// #
     /// let options = Options::builder()
-    ///     .start("1970-01-01")
+    ///     .start(Timestamp::parse("1970-01-01")?)
     ///     .build();
+    /// # Ok::<(), deepgram::DeepgramError>(())
     /// ```
-    pub fn start(mut self, start: impl Into<String>) -> Self {
+    pub fn start(mut self, start: impl Into<Timestamp>) -> Self {
         self.0.start = Some(start.into());
         self
     }
 
     /// Set the time range end date.
     ///
+    /// See [`OptionsBuilder::start`] for the accepted formats.
+    ///
     /// # Examples
     ///
     /// ```
+    /// # use deepgram::common::timestamp::Timestamp;
     /// # use deepgram::manage::usage::get_usage_options::Options;
     /// #
     /// let options = Options::builder()
-    ///     .end("2038-01-19")
+    ///     .end(Timestamp::parse("2038-01-19")?)
     ///     .build();
+    /// # Ok::<(), deepgram::DeepgramError>(())
     /// ```
-    pub fn end(mut self, end: impl Into<String>) -> Self {
+    pub fn end(mut self, end: impl Into<Timestamp>) -> Self {
         self.0.end = Some(end.into());
         self
     }
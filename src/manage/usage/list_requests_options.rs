@@ -6,6 +6,8 @@
 
 use serde::Serialize;
 
+use crate::common::timestamp::Timestamp;
+
 /// Used as a parameter for [`Usage::list_requests`](super::Usage::list_requests).
 ///
 /// See the [Deepgram API Reference][api] for more info.
@@ -13,8 +15,9 @@ use serde::Serialize;
 /// [api]: https://developers.deepgram.com/api-reference/#usage-all
 #[derive(Debug, PartialEq, Clone)]
 pub struct Options {
-    start: Option<String>,
-    end: Option<String>,
+    start: Option<Timestamp>,
+    end: Option<Timestamp>,
+    page: Option<usize>,
     limit: Option<usize>,
     status: Option<Status>,
 }
@@ -39,10 +42,13 @@ pub struct OptionsBuilder(Options);
 #[derive(Serialize)]
 pub(crate) struct SerializableOptions<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
-    start: &'a Option<String>,
+    start: &'a Option<Timestamp>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end: &'a Option<Timestamp>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    end: &'a Option<String>,
+    page: Option<usize>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     limit: Option<usize>,
@@ -63,13 +69,15 @@ impl Options {
     /// This is intended primarily to help with debugging API requests.
     ///
     /// ```
-    /// use deepgram::manage::usage::list_requests_options::Options;
+    /// use deepgram::{common::timestamp::Timestamp, manage::usage::list_requests_options::Options};
+    ///
     /// let options = Options::builder()
-    ///     .start("2024-04-10T00:00:00Z")
-    ///     .end("2024-10-10")
+    ///     .start(Timestamp::parse("2024-04-10T00:00:00Z")?)
+    ///     .end(Timestamp::parse("2024-10-10")?)
     ///     .limit(100)
     ///     .build();
-    /// assert_eq!(&options.urlencoded().unwrap(), "start=2024-04-10T00%3A00%3A00Z&end=2024-10-10&limit=100")
+    /// assert_eq!(&options.urlencoded().unwrap(), "start=2024-04-10T00%3A00%3A00Z&end=2024-10-10&limit=100");
+    /// # Ok::<(), deepgram::DeepgramError>(())
     /// ```
     ///
     pub fn urlencoded(&self) -> Result<String, serde_urlencoded::ser::Error> {
@@ -83,6 +91,7 @@ impl OptionsBuilder {
         Self(Options {
             start: None,
             end: None,
+            page: None,
             limit: None,
             status: None,
         })
@@ -90,36 +99,62 @@ impl OptionsBuilder {
 
     /// Set the time range start date.
     ///
+    /// Takes a [`Timestamp`], either checked from a string such as
+    /// `"2024-01-31"` with [`Timestamp::parse`] or converted from a
+    /// [`SystemTime`](std::time::SystemTime).
+    ///
     /// # Examples
     ///
     /// ```
+    /// # use deepgram::common::timestamp::Timestamp;
     /// # use deepgram::manage::usage::list_requests_options::Options;
     /// #
     /// let options1 = Options::builder()
-    ///     .start("1970-01-01")
+    ///     .start(Timestamp::parse("1970-01-01")?)
     ///     .build();
+    /// # Ok::<(), deepgram::DeepgramError>(())
     /// ```
-    pub fn start(mut self, start: impl Into<String>) -> Self {
+    pub fn start(mut self, start: impl Into<Timestamp>) -> Self {
         self.0.start = Some(start.into());
         self
     }
 
     /// Set the time range end date.
     ///
+    /// See [`OptionsBuilder::start`] for the accepted formats.
+    ///
     /// # Examples
     ///
     /// ```
+    /// # use deepgram::common::timestamp::Timestamp;
     /// # use deepgram::manage::usage::list_requests_options::Options;
     /// #
     /// let options1 = Options::builder()
-    ///     .end("2038-01-19")
+    ///     .end(Timestamp::parse("2038-01-19")?)
     ///     .build();
+    /// # Ok::<(), deepgram::DeepgramError>(())
     /// ```
-    pub fn end(mut self, end: impl Into<String>) -> Self {
+    pub fn end(mut self, end: impl Into<Timestamp>) -> Self {
         self.0.end = Some(end.into());
         self
     }
 
+    /// Set the page of results to return, starting from `0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use deepgram::manage::usage::list_requests_options::Options;
+    /// #
+    /// let options1 = Options::builder()
+    ///     .page(2)
+    ///     .build();
+    /// ```
+    pub fn page(mut self, page: usize) -> Self {
+        self.0.page = Some(page);
+        self
+    }
+
     /// Set the maximum number of results to return per page.
     ///
     /// # Examples
@@ -170,6 +205,7 @@ impl<'a> From<&'a Options> for SerializableOptions<'a> {
         let Options {
             start,
             end,
+            page,
             limit,
             status,
         } = options;
@@ -177,6 +213,7 @@ impl<'a> From<&'a Options> for SerializableOptions<'a> {
         Self {
             start,
             end,
+            page: *page,
             limit: *limit,
             status: match status {
                 Some(Status::Succeeded) => Some("succeeded"),
@@ -0,0 +1,327 @@
+//! Usage reports across projects, such as for monthly chargeback.
+//!
+//! See [`Usage::report`](super::Usage::report).
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::response::{Fields, Request};
+use crate::{common::timestamp::Timestamp, manage::projects::response::Project};
+
+/// What the rows of a [`UsageReport`] are grouped by.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum GroupBy {
+    /// Group by request tag. Requests with several tags count towards each
+    /// of them, with their hours and cost split evenly between them.
+    /// Untagged requests are grouped under an empty tag.
+    Tag,
+
+    /// Group by model name. Requests that used several models count towards
+    /// each of them, with their hours and cost split evenly between them.
+    Model,
+
+    /// Group by processing method, such as `sync`, `async` or `streaming`.
+    Method,
+
+    /// Group by the ID of the API key that made the request.
+    ApiKey,
+
+    /// One row per project.
+    Project,
+}
+
+/// Returned by [`Usage::report`](super::Usage::report).
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct UsageReport {
+    #[allow(missing_docs)]
+    pub start: Timestamp,
+
+    #[allow(missing_docs)]
+    pub end: Timestamp,
+
+    #[allow(missing_docs)]
+    pub group_by: GroupBy,
+
+    /// One row per project and group, sorted by project name and then group.
+    pub rows: Vec<ReportRow>,
+}
+
+/// The usage of a single group within a project.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct ReportRow {
+    #[allow(missing_docs)]
+    pub project_id: Uuid,
+
+    #[allow(missing_docs)]
+    pub project_name: String,
+
+    /// The tag, model name, method or API key ID of the group, depending on
+    /// [`UsageReport::group_by`]. Empty when grouping by project.
+    pub group: String,
+
+    /// The number of requests. A request with several tags or models is
+    /// counted in each of their rows.
+    pub requests: usize,
+
+    /// The hours of audio processed, split evenly between the groups of a
+    /// request, so that the rows add up to the total.
+    pub hours: f64,
+
+    /// The cost in US dollars, split like [`hours`](Self::hours). Requests
+    /// without a cost count as zero.
+    pub usd: f64,
+}
+
+impl UsageReport {
+    /// The total cost of every request in US dollars.
+    pub fn total_usd(&self) -> f64 {
+        self.rows.iter().map(|row| row.usd).sum()
+    }
+
+    /// The report as CSV, with a header row.
+    ///
+    /// ```
+    /// # use deepgram::manage::usage::report::UsageReport;
+    /// # fn example(report: &UsageReport) -> std::io::Result<()> {
+    /// std::fs::write("usage.csv", report.to_csv())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("project_id,project_name,group,requests,hours,usd\n");
+        for row in &self.rows {
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{}",
+                row.project_id,
+                csv_field(&row.project_name),
+                csv_field(&row.group),
+                row.requests,
+                row.hours,
+                row.usd
+            );
+        }
+        csv
+    }
+
+    /// The report as JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Group the `requests` of `project` into report rows.
+pub(super) fn aggregate(
+    project: &Project,
+    requests: &[Request],
+    fields: &Fields,
+    group_by: GroupBy,
+) -> Vec<ReportRow> {
+    let model_names: BTreeMap<Uuid, &str> = fields
+        .models
+        .iter()
+        .map(|model| (model.model_id, model.name.as_str()))
+        .collect();
+
+    let mut groups: BTreeMap<String, ReportRow> = BTreeMap::new();
+    for request in requests {
+        let details = request
+            .response
+            .as_ref()
+            .and_then(|response| response.details.as_ref());
+
+        let mut keys: Vec<String> = match (group_by, details) {
+            (GroupBy::Project, _) => vec![String::new()],
+            (GroupBy::ApiKey, _) => vec![request.api_key_id.to_string()],
+            (GroupBy::Tag, Some(details)) if !details.tags.is_empty() => details.tags.clone(),
+            (GroupBy::Tag, _) => vec![String::new()],
+            (GroupBy::Model, Some(details)) if !details.models.is_empty() => details
+                .models
+                .iter()
+                .map(|model| {
+                    model_names
+                        .get(model)
+                        .map_or_else(|| model.to_string(), |name| (*name).to_owned())
+                })
+                .collect(),
+            (GroupBy::Method, Some(details)) => vec![details.method.clone()],
+            (GroupBy::Model | GroupBy::Method, _) => vec![String::new()],
+        };
+
+        keys.sort_unstable();
+        keys.dedup();
+
+        // Split the request between its groups, so each is only charged once.
+        let share = 1.0 / keys.len() as f64;
+        for key in keys {
+            let row = groups.entry(key.clone()).or_insert_with(|| ReportRow {
+                project_id: project.project_id,
+                project_name: project.name.clone(),
+                group: key,
+                requests: 0,
+                hours: 0.0,
+                usd: 0.0,
+            });
+            row.requests += 1;
+            if let Some(details) = details {
+                row.hours += details.duration / 3600.0 * share;
+                row.usd += details.usd.unwrap_or(0.0) * share;
+            }
+        }
+    }
+
+    groups.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{aggregate, GroupBy, ReportRow, UsageReport};
+    use crate::{
+        common::timestamp::Timestamp,
+        manage::{
+            projects::response::Project,
+            usage::response::{Config, Details, Fields, ModelDetail, Request, Response},
+        },
+    };
+
+    const BASE_MODEL: &str = "00000000-0000-0000-0000-00000000000b";
+    const NOVA_MODEL: &str = "00000000-0000-0000-0000-00000000000a";
+
+    fn request(method: &str, tags: &[&str], models: &[&str], usd: f64) -> Request {
+        Request {
+            request_id: Uuid::nil(),
+            created: Timestamp::from_raw("2024-01-01T00:00:00Z".to_owned()),
+            path: "/v1/listen".to_owned(),
+            api_key_id: Uuid::from_u128(1),
+            response: Some(Response {
+                details: Some(Details {
+                    usd: Some(usd),
+                    duration: 1800.0,
+                    total_audio: 1800.0,
+                    channels: 1,
+                    streams: 1,
+                    models: models.iter().map(|model| model.parse().unwrap()).collect(),
+                    method: method.to_owned(),
+                    tags: tags.iter().map(|&tag| tag.to_owned()).collect(),
+                    features: Vec::new(),
+                    config: Config {
+                        alternatives: None,
+                        diarize: None,
+                        keywords: None,
+                        language: None,
+                        model: None,
+                        tier: None,
+                        multichannel: None,
+                        ner: None,
+                        numerals: None,
+                        profanity_filter: None,
+                        punctuate: None,
+                        redact: None,
+                        search: None,
+                        utterances: None,
+                    },
+                }),
+                message: None,
+                code: 200,
+                completed: Timestamp::from_raw("2024-01-01T00:00:01Z".to_owned()),
+            }),
+            callback: None,
+        }
+    }
+
+    fn fixtures() -> (Project, Vec<Request>, Fields) {
+        let project = Project {
+            project_id: Uuid::from_u128(2),
+            name: "Support, EU".to_owned(),
+            company: None,
+        };
+        let requests = vec![
+            request("sync", &["billing"], &[NOVA_MODEL], 0.25),
+            request(
+                "streaming",
+                &["billing", "qa"],
+                &[NOVA_MODEL, BASE_MODEL],
+                0.5,
+            ),
+            request("sync", &[], &[BASE_MODEL], 1.0),
+        ];
+        let fields = Fields {
+            tags: Vec::new(),
+            models: vec![ModelDetail {
+                name: "nova-2".to_owned(),
+                language: "en".to_owned(),
+                version: "2024-01-01".to_owned(),
+                model_id: NOVA_MODEL.parse().unwrap(),
+            }],
+            processing_methods: Vec::new(),
+            features: Vec::new(),
+        };
+        (project, requests, fields)
+    }
+
+    fn summary(rows: &[ReportRow]) -> Vec<(&str, usize, f64)> {
+        rows.iter()
+            .map(|row| (row.group.as_str(), row.requests, row.usd))
+            .collect()
+    }
+
+    #[test]
+    fn groups_requests() {
+        let (project, requests, fields) = fixtures();
+
+        assert_eq!(
+            summary(&aggregate(&project, &requests, &fields, GroupBy::Tag)),
+            vec![("", 1, 1.0), ("billing", 2, 0.5), ("qa", 1, 0.25)]
+        );
+        let rows = aggregate(&project, &requests, &fields, GroupBy::Model);
+        assert_eq!(
+            summary(&rows),
+            vec![(BASE_MODEL, 2, 1.25), ("nova-2", 2, 0.5)]
+        );
+        assert_eq!(rows.iter().map(|row| row.hours).sum::<f64>(), 1.5);
+        assert_eq!(
+            summary(&aggregate(&project, &requests, &fields, GroupBy::Method)),
+            vec![("streaming", 1, 0.5), ("sync", 2, 1.25)]
+        );
+
+        let rows = aggregate(&project, &requests, &fields, GroupBy::Project);
+        assert_eq!(summary(&rows), vec![("", 3, 1.75)]);
+        assert_eq!(rows[0].hours, 1.5);
+    }
+
+    #[test]
+    fn exports_csv() {
+        let (project, requests, fields) = fixtures();
+        let report = UsageReport {
+            start: Timestamp::parse("2024-01-01").unwrap(),
+            end: Timestamp::parse("2024-02-01").unwrap(),
+            group_by: GroupBy::Method,
+            rows: aggregate(&project, &requests, &fields, GroupBy::Method),
+        };
+
+        assert_eq!(report.total_usd(), 1.75);
+        assert_eq!(
+            report.to_csv(),
+            "project_id,project_name,group,requests,hours,usd\n\
+             00000000-0000-0000-0000-000000000002,\"Support, EU\",streaming,1,0.5,0.5\n\
+             00000000-0000-0000-0000-000000000002,\"Support, EU\",sync,2,1,1.25\n"
+        );
+    }
+}
@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::common::timestamp::Timestamp;
+
 /// Returned by [`Usage::list_requests`](super::Usage::list_requests).
 ///
 /// See the [Deepgram API Reference][api] for more info.
@@ -33,7 +35,7 @@ pub struct Request {
     pub request_id: Uuid,
 
     #[allow(missing_docs)]
-    pub created: String,
+    pub created: Timestamp,
 
     #[allow(missing_docs)]
     pub path: String,
@@ -66,7 +68,7 @@ pub struct Response {
     pub code: i16,
 
     #[allow(missing_docs)]
-    pub completed: String,
+    pub completed: Timestamp,
 }
 
 /// Details about the request.
@@ -174,7 +176,7 @@ pub struct Callback {
     pub code: Option<i16>,
 
     #[allow(missing_docs)]
-    pub completed: Option<String>,
+    pub completed: Option<Timestamp>,
 }
 
 /// Returned by [`Usage::get_usage`](super::Usage::get_usage).
@@ -186,10 +188,10 @@ pub struct Callback {
 #[non_exhaustive]
 pub struct UsageSummary {
     #[allow(missing_docs)]
-    pub start: String,
+    pub start: Timestamp,
 
     #[allow(missing_docs)]
-    pub end: String,
+    pub end: Timestamp,
 
     #[allow(missing_docs)]
     pub resolution: Resolution,
@@ -224,10 +226,10 @@ pub struct Resolution {
 #[non_exhaustive]
 pub struct Result {
     #[allow(missing_docs)]
-    pub start: String,
+    pub start: Timestamp,
 
     #[allow(missing_docs)]
-    pub end: String,
+    pub end: Timestamp,
 
     #[allow(missing_docs)]
     pub hours: f64,
@@ -0,0 +1,206 @@
+//! Route requests between several Deepgram endpoints.
+//!
+//! A [`Deepgram`] client can be given a [`Router`] of named [`Endpoint`]s,
+//! such as Deepgram's regional APIs or self-hosted instances, and a
+//! [`RoutingPolicy`] that picks which endpoint serves each request.
+//!
+//! Transcription, text to speech, management and self-hosted status
+//! requests, and the establishment of websocket sessions, are routed. A
+//! request that fails to connect or gets a server error is retried against
+//! the next endpoint the policy allows, and the endpoint that failed is tried
+//! last for a while. Management requests that change something, and requests
+//! whose audio is streamed from a reader, are only retried if they failed to
+//! connect. Requests that time out are never retried, since they may have
+//! been applied.
+
+use reqwest::{Request, Response};
+use url::Url;
+
+use crate::{
+    endpoints::{Endpoints, RetryServerErrors},
+    Deepgram, DeepgramError,
+};
+
+/// A named Deepgram endpoint.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Endpoint {
+    name: String,
+    url: Url,
+    region: Option<String>,
+}
+
+/// How a [`Router`] picks the endpoint for each request.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+#[non_exhaustive]
+pub enum RoutingPolicy {
+    /// Use the first healthy endpoint, in the order they were added.
+    #[default]
+    Priority,
+
+    /// Rotate between healthy endpoints on every request.
+    RoundRobin,
+
+    /// Use only endpoints in this region, in the order they were added.
+    ///
+    /// Requests never leave the region, even if all of its endpoints are
+    /// unhealthy.
+    Region(String),
+}
+
+/// A set of endpoints and the policy used to route requests between them.
+///
+/// Used with [`Deepgram::with_router`].
+///
+/// # Examples
+///
+/// ```
+/// # use deepgram::{routing::{Endpoint, Router, RoutingPolicy}, Deepgram, DeepgramError};
+/// #
+/// # fn main() -> Result<(), DeepgramError> {
+/// let router = Router::new([
+///     Endpoint::new("eu", "https://api.eu.deepgram.com")?.in_region("eu"),
+///     Endpoint::new("onprem", "http://deepgram.internal:8080")?.in_region("eu"),
+///     Endpoint::new("us", "https://api.deepgram.com")?.in_region("us"),
+/// ])?
+/// .policy(RoutingPolicy::Region("eu".to_owned()));
+///
+/// let dg_client = Deepgram::with_router_and_api_key(router, "apikey12345")?;
+///
+/// // Route one client's requests differently without losing shared health.
+/// let anywhere = dg_client.with_policy(RoutingPolicy::Priority);
+/// #
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Router {
+    endpoints: Vec<Endpoint>,
+    policy: RoutingPolicy,
+}
+
+impl Endpoint {
+    /// Construct a new [`Endpoint`].
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`DeepgramError::InvalidUrl`] if `url` is not a valid base
+    /// URL.
+    pub fn new<U>(name: impl Into<String>, url: U) -> crate::Result<Self>
+    where
+        U: TryInto<Url>,
+        U::Error: std::fmt::Debug,
+    {
+        let url = url.try_into().map_err(|_| DeepgramError::InvalidUrl)?;
+        if url.cannot_be_a_base() {
+            return Err(DeepgramError::InvalidUrl);
+        }
+
+        Ok(Self {
+            name: name.into(),
+            url,
+            region: None,
+        })
+    }
+
+    /// Set the region, for use with [`RoutingPolicy::Region`].
+    pub fn in_region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// The name of the endpoint.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The base URL of the endpoint.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// The region of the endpoint, if set.
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    /// An endpoint named after the host of `url`, which must be a valid base
+    /// URL.
+    pub(crate) fn from_url(url: Url) -> Self {
+        Self {
+            name: url.host_str().unwrap_or_default().to_owned(),
+            url,
+            region: None,
+        }
+    }
+}
+
+impl Router {
+    /// Construct a new [`Router`] with [`RoutingPolicy::Priority`].
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`DeepgramError::NoEndpoints`] if `endpoints` is empty.
+    pub fn new(endpoints: impl IntoIterator<Item = Endpoint>) -> crate::Result<Self> {
+        let endpoints: Vec<Endpoint> = endpoints.into_iter().collect();
+        if endpoints.is_empty() {
+            return Err(DeepgramError::NoEndpoints);
+        }
+
+        Ok(Self {
+            endpoints,
+            policy: RoutingPolicy::default(),
+        })
+    }
+
+    /// Set the default policy for clients using this router.
+    pub fn policy(mut self, policy: RoutingPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub(crate) fn into_parts(self) -> (Endpoints, RoutingPolicy) {
+        (Endpoints::new(self.endpoints), self.policy)
+    }
+}
+
+impl Deepgram {
+    /// A clone of this client that routes requests with `policy`.
+    ///
+    /// The clone shares the health of the endpoints with this client.
+    pub fn with_policy(&self, policy: RoutingPolicy) -> Self {
+        Self {
+            policy,
+            ..self.clone()
+        }
+    }
+
+    /// Execute `request` against the endpoint chosen by the routing policy,
+    /// failing over to the others it allows.
+    pub(crate) async fn execute_routed(
+        &self,
+        request: Request,
+        retry_server_errors: RetryServerErrors,
+    ) -> crate::Result<Response> {
+        self.endpoints
+            .execute(&self.policy, &self.client, request, retry_server_errors)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Endpoint, Router};
+    use crate::{Deepgram, DeepgramError};
+
+    #[test]
+    fn requires_an_endpoint() {
+        assert!(matches!(
+            Router::new(Vec::<Endpoint>::new()),
+            Err(DeepgramError::NoEndpoints)
+        ));
+        assert!(matches!(
+            Deepgram::with_base_urls(Vec::<&str>::new()),
+            Err(DeepgramError::NoEndpoints)
+        ));
+    }
+}
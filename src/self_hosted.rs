@@ -0,0 +1,181 @@
+//! Check the status of a self-hosted Deepgram deployment.
+//!
+//! See the [Deepgram self-hosted docs][docs] for more info.
+//!
+//! [docs]: https://developers.deepgram.com/docs/self-hosted-status-endpoint
+
+use futures::future;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    send_and_translate_response, send_and_translate_response_routed, Deepgram, DeepgramError,
+};
+
+static STATUS_URL_PATH: &str = "v1/status";
+
+/// Check the status of a self-hosted Deepgram deployment.
+///
+/// Constructed using [`Deepgram::self_hosted`].
+///
+/// See the [Deepgram self-hosted docs][docs] for more info.
+///
+/// [docs]: https://developers.deepgram.com/docs/self-hosted-status-endpoint
+#[derive(Debug, Clone)]
+pub struct SelfHosted<'a>(&'a Deepgram);
+
+/// Returned by [`SelfHosted::status`].
+///
+/// See the [Deepgram self-hosted docs][docs] for more info.
+///
+/// [docs]: https://developers.deepgram.com/docs/self-hosted-status-endpoint
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Status {
+    /// Whether the API can reach an Engine, such as `Connected`.
+    pub engine_connection_status: String,
+}
+
+/// The health of one base URL, returned by [`SelfHosted::health`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct EndpointHealth {
+    #[allow(missing_docs)]
+    pub url: Url,
+
+    /// The status reported by the endpoint, or why it could not be fetched.
+    pub status: crate::Result<Status>,
+}
+
+impl Deepgram {
+    /// Construct a new [`SelfHosted`] from a [`Deepgram`].
+    pub fn self_hosted(&self) -> SelfHosted<'_> {
+        self.into()
+    }
+}
+
+impl<'a> From<&'a Deepgram> for SelfHosted<'a> {
+    /// Construct a new [`SelfHosted`] from a [`Deepgram`].
+    fn from(deepgram: &'a Deepgram) -> Self {
+        Self(deepgram)
+    }
+}
+
+impl SelfHosted<'_> {
+    /// Get the status of the deployment, failing over between base URLs like
+    /// other requests.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use deepgram::{Deepgram, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// let dg_client = Deepgram::with_base_url("http://deepgram.internal:8080")?;
+    ///
+    /// let status = dg_client.self_hosted().status().await?;
+    /// assert!(status.is_connected());
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn status(&self) -> crate::Result<Status> {
+        let url = self
+            .0
+            .endpoints
+            .primary()
+            .join(STATUS_URL_PATH)
+            .map_err(|_| DeepgramError::InvalidUrl)?;
+
+        send_and_translate_response_routed(self.0, self.0.client.get(url)).await
+    }
+
+    /// Check the status of every base URL.
+    ///
+    /// Requests prefer healthy base URLs, so calling this periodically keeps
+    /// failover up to date without waiting for requests to fail.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use deepgram::{Deepgram, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// let dg_client = Deepgram::with_base_urls([
+    ///     "http://deepgram-a.internal:8080",
+    ///     "http://deepgram-b.internal:8080",
+    /// ])?;
+    ///
+    /// for endpoint in dg_client.self_hosted().health().await {
+    ///     if !endpoint.is_healthy() {
+    ///         eprintln!("{} is down: {:?}", endpoint.url, endpoint.status);
+    ///     }
+    /// }
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn health(&self) -> Vec<EndpointHealth> {
+        let checks = self.0.endpoints.urls().map(|url| async move {
+            let status = match url.join(STATUS_URL_PATH) {
+                Ok(status_url) => send_and_translate_response(self.0.client.get(status_url)).await,
+                Err(_) => Err(DeepgramError::InvalidUrl),
+            };
+            let health = EndpointHealth {
+                url: url.clone(),
+                status,
+            };
+            self.0.endpoints.mark(url, health.is_healthy());
+            health
+        });
+
+        future::join_all(checks).await
+    }
+}
+
+impl Status {
+    /// Whether the API is connected to an Engine and can serve requests.
+    pub fn is_connected(&self) -> bool {
+        self.engine_connection_status
+            .eq_ignore_ascii_case("connected")
+    }
+}
+
+impl EndpointHealth {
+    /// Whether the endpoint responded and is connected to an Engine.
+    pub fn is_healthy(&self) -> bool {
+        matches!(&self.status, Ok(status) if status.is_connected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_server::serve, Deepgram};
+
+    #[tokio::test]
+    async fn checks_every_endpoint() {
+        let connected = serve("200 OK", r#"{"engine_connection_status":"Connected"}"#).await;
+        let disconnected = serve("200 OK", r#"{"engine_connection_status":"Disconnected"}"#).await;
+        let dg = Deepgram::with_base_urls([
+            "http://127.0.0.1:1/",
+            disconnected.as_str(),
+            connected.as_str(),
+        ])
+        .unwrap();
+
+        let health: Vec<bool> = dg
+            .self_hosted()
+            .health()
+            .await
+            .iter()
+            .map(|endpoint| endpoint.is_healthy())
+            .collect();
+        assert_eq!(health, vec![false, false, true]);
+
+        // Only the last endpoint is healthy, so it is tried first.
+        let status = dg.self_hosted().status().await.unwrap();
+        assert!(status.is_connected());
+    }
+}
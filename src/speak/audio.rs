@@ -0,0 +1,627 @@
+//! Synthesized audio returned by the text to speech API.
+//!
+//! [`Speak::speak_to_stream`](crate::Speak::speak_to_stream) returns an
+//! [`AudioStream`] of the raw response body together with the format that was
+//! negotiated from the request [`Options`] and the response headers. For PCM
+//! encodings it can be turned into a [`SampleStream`] of decoded samples.
+//!
+//! See the [Deepgram Audio Output docs][docs] for more info.
+//!
+//! [docs]: https://developers.deepgram.com/docs/tts-media-output-settings
+
+use std::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use anyhow::anyhow;
+use bytes::{Buf, Bytes, BytesMut};
+use futures::{stream::BoxStream, Stream};
+
+use crate::{
+    common::g711::{alaw_decode, mulaw_decode},
+    DeepgramError, Result,
+};
+
+use super::options::{Container, Encoding, Options};
+
+/// A stream of synthesized audio, exactly as returned by Deepgram.
+///
+/// Use [`AudioStream::samples`] to decode linear16, mulaw or alaw audio into
+/// 16-bit samples.
+pub struct AudioStream {
+    format: NegotiatedFormat,
+    body: BoxStream<'static, Result<Bytes>>,
+}
+
+impl fmt::Debug for AudioStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AudioStream")
+            .field("encoding", &self.format.encoding)
+            .field("container", &self.format.container)
+            .field("sample_rate", &self.format.sample_rate)
+            .field("content_type", &self.format.content_type)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The output format of a request, as far as it can be told from the request
+/// [`Options`] and the response `Content-Type`.
+#[derive(Debug, Clone)]
+pub(super) struct NegotiatedFormat {
+    pub(super) encoding: Encoding,
+    pub(super) container: Container,
+    pub(super) sample_rate: Option<u32>,
+    pub(super) content_type: Option<String>,
+}
+
+impl NegotiatedFormat {
+    pub(super) fn new(options: &Options, content_type: Option<&str>) -> Self {
+        let content_type = content_type.map(|content_type| {
+            content_type
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_ascii_lowercase()
+        });
+
+        let encoding = options
+            .encoding()
+            .cloned()
+            .unwrap_or(match content_type.as_deref() {
+                Some("audio/wav" | "audio/wave" | "audio/x-wav") => Encoding::Linear16,
+                Some("audio/ogg" | "audio/opus") => Encoding::Opus,
+                Some("audio/flac") => Encoding::Flac,
+                Some("audio/aac") => Encoding::Aac,
+                // Deepgram's default output is MP3.
+                _ => Encoding::Mp3,
+            });
+
+        let container =
+            options
+                .container()
+                .cloned()
+                .unwrap_or(match (content_type.as_deref(), &encoding) {
+                    (Some("audio/wav" | "audio/wave" | "audio/x-wav"), _) => Container::Wav,
+                    (Some("audio/ogg"), _) => Container::Ogg,
+                    (_, Encoding::Linear16 | Encoding::Mulaw | Encoding::Alaw) => Container::Wav,
+                    (_, Encoding::Opus) => Container::Ogg,
+                    _ => Container::None,
+                });
+
+        let sample_rate = options.sample_rate().or(match encoding {
+            Encoding::Linear16 => Some(24000),
+            Encoding::Mulaw | Encoding::Alaw => Some(8000),
+            _ => None,
+        });
+
+        Self {
+            encoding,
+            container,
+            sample_rate,
+            content_type,
+        }
+    }
+}
+
+impl AudioStream {
+    pub(crate) fn new(
+        options: &Options,
+        content_type: Option<&str>,
+        body: BoxStream<'static, Result<Bytes>>,
+    ) -> Self {
+        Self {
+            format: NegotiatedFormat::new(options, content_type),
+            body,
+        }
+    }
+
+    /// The encoding of the audio.
+    pub fn encoding(&self) -> &Encoding {
+        &self.format.encoding
+    }
+
+    /// The container wrapping the audio.
+    pub fn container(&self) -> &Container {
+        &self.format.container
+    }
+
+    /// The sample rate of the audio, if it is known for the encoding.
+    pub fn sample_rate(&self) -> Option<u32> {
+        self.format.sample_rate
+    }
+
+    /// The number of audio channels. Synthesized speech is always mono.
+    pub fn channels(&self) -> u16 {
+        1
+    }
+
+    /// The `Content-Type` of the response, without parameters.
+    pub fn content_type(&self) -> Option<&str> {
+        self.format.content_type.as_deref()
+    }
+
+    /// Decode the audio into a stream of 16-bit samples.
+    ///
+    /// A WAV header is stripped when the audio is in a [`Container::Wav`];
+    /// any format information in it takes precedence over the requested options.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeepgramError::UnsupportedAudioFormat`] unless the audio is
+    /// linear16, mulaw or alaw, in a WAV container or with no container.
+    pub fn samples(self) -> Result<SampleStream> {
+        let sample_format = match &self.format.encoding {
+            Encoding::Linear16 => WavSampleFormat::Linear16,
+            Encoding::Mulaw => WavSampleFormat::Mulaw,
+            Encoding::Alaw => WavSampleFormat::Alaw,
+            encoding => {
+                return Err(DeepgramError::UnsupportedAudioFormat(format!(
+                    "{} audio cannot be decoded into samples",
+                    encoding.as_str()
+                )))
+            }
+        };
+        let strip_header = match &self.format.container {
+            Container::Wav => true,
+            Container::None => false,
+            container => {
+                return Err(DeepgramError::UnsupportedAudioFormat(format!(
+                    "audio in a {} container cannot be decoded into samples",
+                    container.as_str()
+                )))
+            }
+        };
+
+        Ok(SampleStream {
+            channels: self.channels(),
+            body: self.body,
+            sample_format,
+            sample_rate: self.format.sample_rate.unwrap_or(24000),
+            header_pending: strip_header,
+            buf: BytesMut::new(),
+        })
+    }
+}
+
+impl Stream for AudioStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.body.as_mut().poll_next(cx)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum WavSampleFormat {
+    Linear16,
+    Mulaw,
+    Alaw,
+}
+
+impl WavSampleFormat {
+    fn bytes_per_sample(self) -> usize {
+        match self {
+            WavSampleFormat::Linear16 => 2,
+            WavSampleFormat::Mulaw | WavSampleFormat::Alaw => 1,
+        }
+    }
+
+    fn format_tag(self) -> u16 {
+        match self {
+            WavSampleFormat::Linear16 => 1,
+            WavSampleFormat::Alaw => 6,
+            WavSampleFormat::Mulaw => 7,
+        }
+    }
+}
+
+/// A stream of decoded 16-bit samples.
+///
+/// Every item holds a whole number of interleaved frames, regardless of how
+/// the response body was split up on the wire.
+///
+/// Constructed using [`AudioStream::samples`].
+pub struct SampleStream {
+    body: BoxStream<'static, Result<Bytes>>,
+    sample_format: WavSampleFormat,
+    sample_rate: u32,
+    channels: u16,
+    header_pending: bool,
+    buf: BytesMut,
+}
+
+impl fmt::Debug for SampleStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SampleStream")
+            .field("sample_format", &self.sample_format)
+            .field("sample_rate", &self.sample_rate)
+            .field("channels", &self.channels)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SampleStream {
+    /// The sample rate of the audio.
+    ///
+    /// When the audio is in a WAV container this reflects the WAV header
+    /// once the first item has been received.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The number of interleaved channels in each item.
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Parse the WAV header at the start of `buf`, if it is complete.
+    fn parse_header(&mut self) -> Result<bool> {
+        match parse_wav_header(&self.buf)? {
+            WavHeader::Incomplete => Ok(false),
+            WavHeader::Missing => {
+                self.header_pending = false;
+                Ok(true)
+            }
+            WavHeader::Complete {
+                format,
+                data_offset,
+                ..
+            } => {
+                if let Some(format) = format {
+                    self.sample_format = format.sample_format;
+                    self.sample_rate = format.sample_rate;
+                    self.channels = format.channels;
+                }
+                self.buf.advance(data_offset);
+                self.header_pending = false;
+                Ok(true)
+            }
+        }
+    }
+
+    fn take_frames(&mut self) -> Option<Vec<i16>> {
+        let frame_size = self.sample_format.bytes_per_sample() * self.channels as usize;
+        let complete = self.buf.len() - self.buf.len() % frame_size;
+        if complete == 0 {
+            return None;
+        }
+        let data = self.buf.split_to(complete);
+
+        Some(match self.sample_format {
+            WavSampleFormat::Linear16 => data
+                .chunks_exact(2)
+                .map(|bytes| i16::from_le_bytes([bytes[0], bytes[1]]))
+                .collect(),
+            WavSampleFormat::Mulaw => data.iter().map(|byte| mulaw_decode(*byte)).collect(),
+            WavSampleFormat::Alaw => data.iter().map(|byte| alaw_decode(*byte)).collect(),
+        })
+    }
+}
+
+impl Stream for SampleStream {
+    type Item = Result<Vec<i16>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        loop {
+            if this.header_pending {
+                match this.parse_header() {
+                    Ok(_) => {}
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                }
+            }
+            if !this.header_pending {
+                if let Some(frames) = this.take_frames() {
+                    return Poll::Ready(Some(Ok(frames)));
+                }
+            }
+
+            match this.body.as_mut().poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Ok(chunk))) => this.buf.extend_from_slice(&chunk),
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) if this.header_pending && !this.buf.is_empty() => {
+                    this.buf.clear();
+                    return Poll::Ready(Some(Err(DeepgramError::UnexpectedServerResponse(
+                        anyhow!("audio ended before the end of the WAV header"),
+                    ))));
+                }
+                // A trailing partial frame cannot be decoded and is dropped.
+                Poll::Ready(None) => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct WavFormat {
+    pub(super) sample_format: WavSampleFormat,
+    pub(super) sample_rate: u32,
+    pub(super) channels: u16,
+}
+
+pub(super) enum WavHeader {
+    Incomplete,
+    Missing,
+    Complete {
+        format: Option<WavFormat>,
+        data_offset: usize,
+        /// [`None`] when the size was left unspecified for streaming.
+        data_len: Option<usize>,
+    },
+}
+
+/// Write a canonical 44 byte WAV header for `data_len` bytes of audio.
+///
+/// Errors if `data_len` is too large for a WAV file.
+pub(super) fn write_wav_header(format: &WavFormat, data_len: usize) -> Result<Vec<u8>> {
+    let too_long = || {
+        DeepgramError::UnsupportedAudioFormat(format!(
+            "{data_len} bytes of audio is too long for a WAV file"
+        ))
+    };
+    let data_len = u32::try_from(data_len).map_err(|_| too_long())?;
+    let riff_len = data_len.checked_add(36).ok_or_else(too_long)?;
+    let block_align =
+        (format.sample_format.bytes_per_sample() as u16).saturating_mul(format.channels);
+    let bits_per_sample = format.sample_format.bytes_per_sample() as u16 * 8;
+
+    let mut header = Vec::with_capacity(44);
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&riff_len.to_le_bytes());
+    header.extend_from_slice(b"WAVEfmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&format.sample_format.format_tag().to_le_bytes());
+    header.extend_from_slice(&format.channels.to_le_bytes());
+    header.extend_from_slice(&format.sample_rate.to_le_bytes());
+    header.extend_from_slice(
+        &format
+            .sample_rate
+            .saturating_mul(block_align as u32)
+            .to_le_bytes(),
+    );
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&bits_per_sample.to_le_bytes());
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&data_len.to_le_bytes());
+    Ok(header)
+}
+
+pub(super) fn parse_wav_header(buf: &[u8]) -> Result<WavHeader> {
+    if buf.len() < 12 {
+        return Ok(if b"RIFF".starts_with(&buf[..buf.len().min(4)]) {
+            WavHeader::Incomplete
+        } else {
+            WavHeader::Missing
+        });
+    }
+    if &buf[..4] != b"RIFF" || &buf[8..12] != b"WAVE" {
+        return Ok(WavHeader::Missing);
+    }
+
+    let mut format = None;
+    let mut offset = 12;
+    while buf.len() >= offset + 8 {
+        let id = &buf[offset..offset + 4];
+        let size = u32::from_le_bytes([
+            buf[offset + 4],
+            buf[offset + 5],
+            buf[offset + 6],
+            buf[offset + 7],
+        ]) as usize;
+        let body = offset + 8;
+
+        if id == b"data" {
+            return Ok(WavHeader::Complete {
+                format,
+                data_offset: body,
+                data_len: (size != 0 && size != u32::MAX as usize).then_some(size),
+            });
+        }
+        if buf.len() < body + size {
+            break;
+        }
+        if id == b"fmt " && size >= 16 {
+            let field = |at: usize| u16::from_le_bytes([buf[body + at], buf[body + at + 1]]);
+            let sample_format = match (field(0), field(14)) {
+                (1, 16) => WavSampleFormat::Linear16,
+                (6, 8) => WavSampleFormat::Alaw,
+                (7, 8) => WavSampleFormat::Mulaw,
+                (tag, bits) => {
+                    return Err(DeepgramError::UnsupportedAudioFormat(format!(
+                        "WAV format tag {tag} with {bits} bits per sample"
+                    )))
+                }
+            };
+            let channels = field(2);
+            if channels == 0 {
+                return Err(DeepgramError::UnsupportedAudioFormat(
+                    "WAV header with zero channels".to_string(),
+                ));
+            }
+            format = Some(WavFormat {
+                sample_format,
+                channels,
+                sample_rate: u32::from_le_bytes([
+                    buf[body + 4],
+                    buf[body + 5],
+                    buf[body + 6],
+                    buf[body + 7],
+                ]),
+            });
+        }
+        // Chunks are padded to an even length.
+        offset = body + size + (size & 1);
+    }
+
+    Ok(WavHeader::Incomplete)
+}
+
+#[cfg(feature = "speak-playback")]
+pub use self::playback::SampleSource;
+
+#[cfg(feature = "speak-playback")]
+mod playback {
+    use std::{sync::mpsc, time::Duration};
+
+    use futures::StreamExt;
+
+    use super::SampleStream;
+    use crate::Result;
+
+    impl SampleStream {
+        /// Convert the stream into a [`rodio::Source`] that can be played with a
+        /// [`rodio::Sink`].
+        ///
+        /// Samples are fetched by a background task on the current tokio
+        /// runtime. Playback ends early if the stream returns an error after
+        /// the first item.
+        ///
+        /// # Errors
+        ///
+        /// Returns the error of the first item, if any.
+        pub async fn into_source(mut self) -> Result<SampleSource> {
+            // Wait for the first item so that a WAV header has been applied
+            // before the format is reported to rodio.
+            let first = self.next().await.transpose()?.unwrap_or_default();
+            let (channels, sample_rate) = (self.channels, self.sample_rate);
+
+            let (tx, rx) = mpsc::channel();
+            tokio::spawn(async move {
+                while let Some(Ok(samples)) = self.next().await {
+                    if tx.send(samples).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            Ok(SampleSource {
+                rx,
+                current: first.into_iter(),
+                channels,
+                sample_rate,
+            })
+        }
+    }
+
+    /// A [`rodio::Source`] playing a [`SampleStream`].
+    ///
+    /// Constructed using [`SampleStream::into_source`].
+    #[derive(Debug)]
+    pub struct SampleSource {
+        rx: mpsc::Receiver<Vec<i16>>,
+        current: std::vec::IntoIter<i16>,
+        channels: u16,
+        sample_rate: u32,
+    }
+
+    impl Iterator for SampleSource {
+        type Item = i16;
+
+        fn next(&mut self) -> Option<i16> {
+            loop {
+                if let Some(sample) = self.current.next() {
+                    return Some(sample);
+                }
+                self.current = self.rx.recv().ok()?.into_iter();
+            }
+        }
+    }
+
+    impl rodio::Source for SampleSource {
+        fn current_frame_len(&self) -> Option<usize> {
+            None
+        }
+
+        fn channels(&self) -> u16 {
+            self.channels
+        }
+
+        fn sample_rate(&self) -> u32 {
+            self.sample_rate
+        }
+
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures::{stream, StreamExt, TryStreamExt};
+
+    use super::{parse_wav_header, AudioStream};
+    use crate::{
+        speak::options::{Container, Encoding, Options},
+        DeepgramError,
+    };
+
+    fn wav_header(sample_rate: u32) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend_from_slice(b"RIFF\xff\xff\xff\xffWAVEfmt ");
+        header.extend_from_slice(&16u32.to_le_bytes());
+        header.extend_from_slice(&1u16.to_le_bytes());
+        header.extend_from_slice(&1u16.to_le_bytes());
+        header.extend_from_slice(&sample_rate.to_le_bytes());
+        header.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+        header.extend_from_slice(&2u16.to_le_bytes());
+        header.extend_from_slice(&16u16.to_le_bytes());
+        header.extend_from_slice(b"data\xff\xff\xff\xff");
+        header
+    }
+
+    #[tokio::test]
+    async fn strips_wav_header_and_aligns_samples() {
+        let mut body = wav_header(16000);
+        for sample in [1i16, -2, 3, -4, 5] {
+            body.extend_from_slice(&sample.to_le_bytes());
+        }
+        // Split at awkward points, including inside the header and inside a sample.
+        let chunks: Vec<crate::Result<Bytes>> = [&body[..7], &body[7..45], &body[45..]]
+            .iter()
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect();
+
+        let options = Options::builder()
+            .encoding(Encoding::Linear16)
+            .container(Container::Wav)
+            .build();
+        let audio = AudioStream::new(&options, Some("audio/wav"), stream::iter(chunks).boxed());
+        assert_eq!(audio.sample_rate(), Some(24000));
+
+        let mut samples = audio.samples().unwrap();
+        let mut decoded = Vec::new();
+        while let Some(frame) = samples.try_next().await.unwrap() {
+            decoded.extend(frame);
+        }
+        assert_eq!(decoded, vec![1, -2, 3, -4, 5]);
+        assert_eq!(samples.sample_rate(), 16000);
+    }
+
+    #[test]
+    fn rejects_zero_channels() {
+        let mut header = wav_header(16000);
+        header[22..24].copy_from_slice(&0u16.to_le_bytes());
+        assert!(matches!(
+            parse_wav_header(&header),
+            Err(DeepgramError::UnsupportedAudioFormat(_))
+        ));
+    }
+
+    #[test]
+    fn negotiates_format_from_content_type() {
+        let audio = AudioStream::new(
+            &Options::builder().build(),
+            Some("audio/mpeg"),
+            stream::empty().boxed(),
+        );
+        assert_eq!(audio.encoding(), &Encoding::Mp3);
+        assert_eq!(audio.container(), &Container::None);
+        assert!(audio.samples().is_err());
+    }
+}
@@ -0,0 +1,302 @@
+//! Splitting long text into requests and joining the synthesized audio.
+//!
+//! Used by [`Speak::speak_long`](crate::Speak::speak_long).
+
+use bytes::{Bytes, BytesMut};
+
+use crate::{DeepgramError, Result};
+
+use super::{
+    audio::{parse_wav_header, write_wav_header, NegotiatedFormat, WavHeader},
+    ogg,
+    options::{Container, Encoding},
+};
+
+/// The maximum number of characters accepted by a single text to speech request.
+///
+/// See the [Deepgram Text to Speech docs][docs] for more info.
+///
+/// [docs]: https://developers.deepgram.com/docs/tts-rest
+pub const MAX_TEXT_LENGTH: usize = 2000;
+
+/// Split `text` into pieces of at most `max_chars` characters.
+///
+/// Text is split at sentence boundaries where possible, then at clause
+/// boundaries (commas, semicolons, colons and dashes), then between words.
+/// Only a single word longer than `max_chars` is split mid-word. Leading and
+/// trailing whitespace is trimmed from every piece, and empty pieces are dropped.
+///
+/// ```
+/// use deepgram::speak::chunking::split_text;
+///
+/// let chunks = split_text("One sentence. Another, longer sentence.", 20);
+/// assert_eq!(chunks, vec!["One sentence.", "Another,", "longer sentence."]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `max_chars` is zero.
+pub fn split_text(text: &str, max_chars: usize) -> Vec<String> {
+    assert!(max_chars > 0, "max_chars must be greater than zero");
+
+    let mut pieces = Vec::new();
+    split_into(text, max_chars, 0, &mut pieces);
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+    for piece in pieces {
+        let piece_chars = piece.chars().count();
+        if current_chars + piece_chars > max_chars && !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+            current_chars = 0;
+        }
+        current.push_str(piece);
+        current_chars += piece_chars;
+    }
+    chunks.push(current);
+
+    chunks
+        .iter()
+        .map(|chunk| chunk.trim())
+        .filter(|chunk| !chunk.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '…' | '\n')
+}
+
+fn is_clause_end(c: char) -> bool {
+    matches!(c, ',' | ';' | ':' | '—' | '–' | ')')
+}
+
+fn is_word_end(c: char) -> bool {
+    !c.is_whitespace()
+}
+
+const BOUNDARIES: [fn(char) -> bool; 3] = [is_sentence_end, is_clause_end, is_word_end];
+
+fn split_into<'t>(text: &'t str, max_chars: usize, level: usize, out: &mut Vec<&'t str>) {
+    if text.chars().count() <= max_chars {
+        out.push(text);
+        return;
+    }
+
+    match BOUNDARIES.get(level) {
+        Some(is_boundary) => {
+            for piece in split_after(text, *is_boundary) {
+                split_into(piece, max_chars, level + 1, out);
+            }
+        }
+        None => {
+            let mut rest = text;
+            while !rest.is_empty() {
+                let end = rest
+                    .char_indices()
+                    .nth(max_chars)
+                    .map_or(rest.len(), |(index, _)| index);
+                out.push(&rest[..end]);
+                rest = &rest[end..];
+            }
+        }
+    }
+}
+
+/// Split after every boundary character that is followed by whitespace,
+/// keeping the whitespace with the preceding piece.
+fn split_after(text: &str, is_boundary: fn(char) -> bool) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+    while let Some((_, c)) = chars.next() {
+        let followed_by_space = !matches!(chars.peek(), Some((_, next)) if !next.is_whitespace());
+        if is_boundary(c) && followed_by_space {
+            while let Some((_, next)) = chars.peek() {
+                if !next.is_whitespace() {
+                    break;
+                }
+                chars.next();
+            }
+            let end = chars.peek().map_or(text.len(), |(index, _)| *index);
+            pieces.push(&text[start..end]);
+            start = end;
+        }
+    }
+    if start < text.len() {
+        pieces.push(&text[start..]);
+    }
+    pieces
+}
+
+/// Join the audio of consecutive requests into a single file.
+///
+/// WAV audio is rewritten with a single header covering all of the samples,
+/// and Ogg Opus audio is rewritten into a single logical stream. Audio
+/// without a container is concatenated as-is, which is valid for raw PCM, MP3
+/// and AAC. Other formats cannot be joined and are an error unless the text
+/// fit in a single request.
+pub(super) fn join_audio(format: &NegotiatedFormat, chunks: &[Bytes]) -> Result<Bytes> {
+    if let [chunk] = chunks {
+        return Ok(chunk.clone());
+    }
+    can_join(format)?;
+
+    match (&format.container, &format.encoding) {
+        (Container::Wav, _) => join_wav(chunks),
+        (Container::Ogg, _) => ogg::join_opus(chunks),
+        _ => Ok(chunks.concat().into()),
+    }
+}
+
+/// Check that audio in `format` can be joined by [`join_audio`].
+///
+/// # Errors
+///
+/// Errors with [`DeepgramError::UnsupportedAudioFormat`] if it cannot.
+pub(super) fn can_join(format: &NegotiatedFormat) -> Result<()> {
+    match (&format.container, &format.encoding) {
+        (Container::Wav, _) | (Container::Ogg, Encoding::Opus) => Ok(()),
+        (Container::None, Encoding::Flac | Encoding::Opus)
+        | (Container::Ogg | Container::CustomContainer(_), _) => {
+            Err(DeepgramError::UnsupportedAudioFormat(format!(
+                "{} audio in a {} container cannot be joined",
+                format.encoding.as_str(),
+                format.container.as_str()
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn join_wav(chunks: &[Bytes]) -> Result<Bytes> {
+    let mut wav_format = None;
+    let mut data = BytesMut::new();
+
+    for chunk in chunks {
+        let WavHeader::Complete {
+            format,
+            data_offset,
+            data_len,
+        } = parse_wav_header(chunk)?
+        else {
+            return Err(DeepgramError::UnexpectedServerResponse(anyhow::anyhow!(
+                "expected WAV audio"
+            )));
+        };
+
+        match (&wav_format, format) {
+            (None, Some(format)) => wav_format = Some(format),
+            (Some(expected), Some(format)) if *expected != format => {
+                return Err(DeepgramError::UnexpectedServerResponse(anyhow::anyhow!(
+                    "WAV audio of consecutive requests has different formats"
+                )))
+            }
+            _ => {}
+        }
+
+        let end = data_len.map_or(chunk.len(), |len| (data_offset + len).min(chunk.len()));
+        data.extend_from_slice(&chunk[data_offset..end]);
+    }
+
+    let format = wav_format.ok_or(DeepgramError::UnexpectedServerResponse(anyhow::anyhow!(
+        "WAV audio is missing a format chunk"
+    )))?;
+
+    let mut joined = BytesMut::from(&write_wav_header(&format, data.len())?[..]);
+    joined.extend_from_slice(&data);
+    Ok(joined.freeze())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::{join_audio, join_wav, split_text};
+    use crate::{
+        speak::{
+            audio::{
+                parse_wav_header, write_wav_header, NegotiatedFormat, WavFormat, WavHeader,
+                WavSampleFormat,
+            },
+            options::{Container, Encoding, Options},
+        },
+        DeepgramError,
+    };
+
+    #[test]
+    fn splits_at_the_coarsest_boundary_that_fits() {
+        let text = "First sentence here. Second one; with a clause. Third!";
+        assert_eq!(
+            split_text(text, 30),
+            vec![
+                "First sentence here.",
+                "Second one; with a clause.",
+                "Third!"
+            ]
+        );
+        assert_eq!(
+            split_text(text, 15),
+            vec![
+                "First sentence",
+                "here.",
+                "Second one;",
+                "with a clause.",
+                "Third!"
+            ]
+        );
+        assert_eq!(split_text("abcdefgh", 3), vec!["abc", "def", "gh"]);
+        assert!(split_text(text, 15)
+            .iter()
+            .all(|chunk| chunk.chars().count() <= 15));
+    }
+
+    #[test]
+    fn joins_wav_under_one_header() {
+        let format = WavFormat {
+            sample_format: WavSampleFormat::Linear16,
+            sample_rate: 16000,
+            channels: 1,
+        };
+        let chunk = |data: &[u8]| {
+            let mut chunk = write_wav_header(&format, data.len()).unwrap();
+            chunk.extend_from_slice(data);
+            Bytes::from(chunk)
+        };
+
+        let joined = join_wav(&[chunk(&[1, 2, 3, 4]), chunk(&[5, 6])]).unwrap();
+        let WavHeader::Complete {
+            format: Some(joined_format),
+            data_offset,
+            data_len,
+        } = parse_wav_header(&joined).unwrap()
+        else {
+            panic!("joined audio is not WAV");
+        };
+        assert_eq!(joined_format, format);
+        assert_eq!(data_len, Some(6));
+        assert_eq!(&joined[data_offset..], &[1, 2, 3, 4, 5, 6]);
+
+        assert!(write_wav_header(&format, u32::MAX as usize).is_err());
+    }
+
+    #[test]
+    fn refuses_to_join_flac_without_a_container() {
+        let options = Options::builder()
+            .encoding(Encoding::Flac)
+            .container(Container::None)
+            .build();
+        let format = NegotiatedFormat::new(&options, Some("audio/flac"));
+        let chunks = [
+            Bytes::from_static(b"fLaC one"),
+            Bytes::from_static(b"fLaC two"),
+        ];
+
+        assert!(matches!(
+            join_audio(&format, &chunks),
+            Err(DeepgramError::UnsupportedAudioFormat(_))
+        ));
+        assert_eq!(join_audio(&format, &chunks[..1]).unwrap(), chunks[0]);
+    }
+}
@@ -1,4 +1,8 @@
 //! Speak module
 
+pub mod audio;
+pub mod chunking;
+mod ogg;
 pub mod options;
+pub mod response;
 pub mod rest;
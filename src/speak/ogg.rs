@@ -0,0 +1,330 @@
+//! Joining Ogg Opus streams into a single logical stream.
+//!
+//! See [RFC 3533](https://www.rfc-editor.org/rfc/rfc3533) for the Ogg page
+//! format and [RFC 7845](https://www.rfc-editor.org/rfc/rfc7845) for how
+//! Opus is carried in it.
+
+use bytes::{BufMut, Bytes, BytesMut};
+
+use crate::{DeepgramError, Result};
+
+const CAPTURE_PATTERN: &[u8] = b"OggS";
+const HEADER_LEN: usize = 27;
+
+const CONTINUED: u8 = 0x01;
+const BEGINNING_OF_STREAM: u8 = 0x02;
+const END_OF_STREAM: u8 = 0x04;
+
+/// The granule position of a page on which no packet ends.
+const NO_GRANULE: u64 = u64::MAX;
+
+/// Opus streams start with an identification and a comment header packet.
+const HEADER_PACKETS: usize = 2;
+
+/// Join Ogg Opus streams into one, in order.
+///
+/// The headers of the first stream are kept and those of the others are
+/// dropped. Audio pages are renumbered into the first stream, with granule
+/// positions counting the samples of every stream before them. The end
+/// trimming of the last stream is kept; the decoder warm-up of later streams,
+/// a few milliseconds each, is played.
+///
+/// # Errors
+///
+/// Errors with [`DeepgramError::UnexpectedServerResponse`] if a stream is not
+/// valid Ogg Opus, or the streams have different channel counts.
+pub(super) fn join_opus(streams: &[Bytes]) -> Result<Bytes> {
+    let mut pages = Vec::new();
+    let mut serial = None;
+    let mut channels = None;
+    let mut samples = 0;
+    let mut end_trim = 0;
+
+    for (index, stream) in streams.iter().enumerate() {
+        let mut packets = 0;
+        let mut packet = Vec::new();
+        let mut stream_samples = 0;
+
+        for page in parse_pages(stream)? {
+            let in_headers = packets < HEADER_PACKETS;
+            let mut ends_packet = false;
+            for (lacing, segment) in page.segments() {
+                if in_headers || packet.len() < 2 {
+                    packet.extend_from_slice(segment);
+                }
+                if lacing < 255 {
+                    match packets {
+                        0 => {
+                            let stream_channels = opus_head_channels(&packet)?;
+                            if *channels.get_or_insert(stream_channels) != stream_channels {
+                                return Err(invalid("Opus streams have different channel counts"));
+                            }
+                        }
+                        1 => {}
+                        _ => {
+                            stream_samples += opus_samples(&packet)?;
+                            ends_packet = true;
+                        }
+                    }
+                    packets += 1;
+                    packet.clear();
+                }
+            }
+
+            if in_headers {
+                if packets > HEADER_PACKETS || (packets == HEADER_PACKETS && !packet.is_empty()) {
+                    return Err(invalid("Opus headers must end their page"));
+                }
+                if index == 0 {
+                    serial.get_or_insert(page.serial);
+                    pages.push(OutputPage {
+                        header_type: page.header_type,
+                        granule: page.granule,
+                        lacing: page.lacing,
+                        body: page.body,
+                    });
+                }
+                continue;
+            }
+
+            if index + 1 == streams.len() && page.granule != NO_GRANULE {
+                end_trim = stream_samples.saturating_sub(page.granule);
+            }
+            pages.push(OutputPage {
+                header_type: page.header_type & CONTINUED,
+                granule: if ends_packet {
+                    samples + stream_samples
+                } else {
+                    NO_GRANULE
+                },
+                lacing: page.lacing,
+                body: page.body,
+            });
+        }
+
+        if packets < HEADER_PACKETS {
+            return Err(invalid("Ogg stream is missing its Opus headers"));
+        }
+        samples += stream_samples;
+    }
+
+    let serial = serial.ok_or_else(|| invalid("no Ogg pages to join"))?;
+    if let Some(last) = pages.last_mut() {
+        last.header_type |= END_OF_STREAM;
+        if last.granule != NO_GRANULE {
+            last.granule = last.granule.saturating_sub(end_trim);
+        }
+    }
+
+    let mut joined = BytesMut::new();
+    for (sequence, page) in pages.iter().enumerate() {
+        page.write(&mut joined, serial, sequence as u32);
+    }
+    Ok(joined.freeze())
+}
+
+struct Page<'a> {
+    header_type: u8,
+    granule: u64,
+    serial: u32,
+    lacing: &'a [u8],
+    body: &'a [u8],
+}
+
+impl<'a> Page<'a> {
+    /// The lacing value and bytes of each segment.
+    fn segments(&self) -> impl Iterator<Item = (u8, &'a [u8])> {
+        let body = self.body;
+        self.lacing.iter().scan(0, move |offset, &lacing| {
+            let start = *offset;
+            *offset += lacing as usize;
+            Some((lacing, &body[start..*offset]))
+        })
+    }
+}
+
+struct OutputPage<'a> {
+    header_type: u8,
+    granule: u64,
+    lacing: &'a [u8],
+    body: &'a [u8],
+}
+
+impl OutputPage<'_> {
+    fn write(&self, out: &mut BytesMut, serial: u32, sequence: u32) {
+        let start = out.len();
+        out.put_slice(CAPTURE_PATTERN);
+        out.put_u8(0);
+        out.put_u8(self.header_type);
+        out.put_u64_le(self.granule);
+        out.put_u32_le(serial);
+        out.put_u32_le(sequence);
+        out.put_u32_le(0);
+        out.put_u8(self.lacing.len() as u8);
+        out.put_slice(self.lacing);
+        out.put_slice(self.body);
+
+        let crc = crc32(&out[start..]);
+        out[start + 22..start + 26].copy_from_slice(&crc.to_le_bytes());
+    }
+}
+
+fn parse_pages(mut data: &[u8]) -> Result<Vec<Page<'_>>> {
+    let mut pages = Vec::new();
+    while !data.is_empty() {
+        if data.len() < HEADER_LEN || !data.starts_with(CAPTURE_PATTERN) || data[4] != 0 {
+            return Err(invalid("invalid Ogg page header"));
+        }
+        let segments = data[26] as usize;
+        let lacing = data
+            .get(HEADER_LEN..HEADER_LEN + segments)
+            .ok_or_else(|| invalid("truncated Ogg page"))?;
+        let body_start = HEADER_LEN + segments;
+        let body_end = body_start + lacing.iter().map(|&len| len as usize).sum::<usize>();
+        let body = data
+            .get(body_start..body_end)
+            .ok_or_else(|| invalid("truncated Ogg page"))?;
+
+        pages.push(Page {
+            header_type: data[5] & (CONTINUED | BEGINNING_OF_STREAM | END_OF_STREAM),
+            granule: u64::from_le_bytes(data[6..14].try_into().unwrap()),
+            serial: u32::from_le_bytes(data[14..18].try_into().unwrap()),
+            lacing,
+            body,
+        });
+        data = &data[body_end..];
+    }
+    Ok(pages)
+}
+
+/// The channel count of an Opus identification header.
+fn opus_head_channels(packet: &[u8]) -> Result<u8> {
+    match packet {
+        [b'O', b'p', b'u', b's', b'H', b'e', b'a', b'd', _, channels, ..] => Ok(*channels),
+        _ => Err(invalid("missing OpusHead packet")),
+    }
+}
+
+/// The number of 48kHz samples an Opus packet decodes to.
+///
+/// See [RFC 6716, section 3.1](https://www.rfc-editor.org/rfc/rfc6716#section-3.1).
+fn opus_samples(packet: &[u8]) -> Result<u64> {
+    let Some((&toc, rest)) = packet.split_first() else {
+        return Ok(0);
+    };
+    let config = usize::from(toc >> 3);
+    let frame_samples = match config {
+        0..=11 => [480, 960, 1920, 2880][config % 4],
+        12..=15 => [480, 960][config % 2],
+        _ => [120, 240, 480, 960][config % 4],
+    };
+    let frames = match toc & 0x03 {
+        0 => 1,
+        1 | 2 => 2,
+        _ => rest
+            .first()
+            .map(|count| count & 0x3F)
+            .ok_or_else(|| invalid("truncated Opus packet"))?,
+    };
+    Ok(frame_samples * u64::from(frames))
+}
+
+/// The CRC used by Ogg pages: polynomial 0x04C11DB7, unreflected, with no
+/// initial or final XOR.
+fn crc32(data: &[u8]) -> u32 {
+    data.iter().fold(0, |crc, &byte| {
+        (0..8).fold(crc ^ (u32::from(byte) << 24), |crc, _| {
+            if crc & 0x8000_0000 == 0 {
+                crc << 1
+            } else {
+                (crc << 1) ^ 0x04C1_1DB7
+            }
+        })
+    })
+}
+
+fn invalid(message: &'static str) -> DeepgramError {
+    DeepgramError::UnexpectedServerResponse(anyhow::anyhow!(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{Bytes, BytesMut};
+
+    use super::{crc32, join_opus, parse_pages, OutputPage, BEGINNING_OF_STREAM, END_OF_STREAM};
+
+    /// An Ogg Opus stream whose audio packets are 20ms CELT frames, written
+    /// one packet per page.
+    fn stream(serial: u32, packets: usize, end_trim: u64) -> Bytes {
+        let head = b"OpusHead\x01\x01\x38\x01\x80\xbb\0\0\0\0\0";
+        let tags = b"OpusTags\0\0\0\0\0\0\0\0";
+        let audio = [0xF8, 0xFF, 0xFE];
+
+        let mut out = BytesMut::new();
+        let mut page = |header_type, granule, body: &[u8], sequence| {
+            OutputPage {
+                header_type,
+                granule,
+                lacing: &[body.len() as u8],
+                body,
+            }
+            .write(&mut out, serial, sequence);
+        };
+        page(BEGINNING_OF_STREAM, 0, head, 0);
+        page(0, 0, tags, 1);
+        for index in 0..packets {
+            let last = index + 1 == packets;
+            let granule = 960 * (index as u64 + 1) - if last { end_trim } else { 0 };
+            let header_type = if last { END_OF_STREAM } else { 0 };
+            page(header_type, granule, &audio, index as u32 + 2);
+        }
+        out.freeze()
+    }
+
+    #[test]
+    fn computes_the_ogg_crc() {
+        assert_eq!(crc32(b"123456789"), 0x89A1_897F);
+    }
+
+    #[test]
+    fn joins_into_one_logical_stream() {
+        let joined = join_opus(&[stream(7, 2, 100), stream(9, 3, 200)]).unwrap();
+        let pages = parse_pages(&joined).unwrap();
+
+        // The headers of the second stream are dropped.
+        assert_eq!(pages.len(), 2 + 2 + 3);
+        assert!(pages.iter().all(|page| page.serial == 7));
+        assert_eq!(
+            pages
+                .iter()
+                .map(|page| page.header_type)
+                .collect::<Vec<_>>(),
+            vec![BEGINNING_OF_STREAM, 0, 0, 0, 0, 0, END_OF_STREAM]
+        );
+        assert_eq!(
+            pages.iter().map(|page| page.granule).collect::<Vec<_>>(),
+            vec![0, 0, 960, 1920, 2880, 3840, 4800 - 200]
+        );
+
+        // Every page has a valid sequence number and CRC.
+        let mut offset = 0;
+        for (sequence, page) in pages.iter().enumerate() {
+            let len = 27 + page.lacing.len() + page.body.len();
+            let mut bytes = joined[offset..offset + len].to_vec();
+            assert_eq!(
+                u32::from_le_bytes(bytes[18..22].try_into().unwrap()),
+                sequence as u32
+            );
+            let crc = u32::from_le_bytes(bytes[22..26].try_into().unwrap());
+            bytes[22..26].fill(0);
+            assert_eq!(crc32(&bytes), crc);
+            offset += len;
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_streams() {
+        assert!(join_opus(&[stream(1, 1, 0), Bytes::from_static(b"OggS")]).is_err());
+        assert!(join_opus(&[stream(1, 1, 0), Bytes::from_static(b"ID3")]).is_err());
+    }
+}
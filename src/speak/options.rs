@@ -144,7 +144,7 @@ impl Container {
         match self {
             Container::Wav => "wav",
             Container::Ogg => "ogg",
-            Container::None => "nonne",
+            Container::None => "none",
             Container::CustomContainer(container) => container,
         }
     }
@@ -195,6 +195,18 @@ impl Options {
     pub fn urlencoded(&self) -> Result<String, serde_urlencoded::ser::Error> {
         serde_urlencoded::to_string(SerializableOptions(self))
     }
+
+    pub(crate) fn encoding(&self) -> Option<&Encoding> {
+        self.encoding.as_ref()
+    }
+
+    pub(crate) fn sample_rate(&self) -> Option<u32> {
+        self.sample_rate
+    }
+
+    pub(crate) fn container(&self) -> Option<&Container> {
+        self.container.as_ref()
+    }
 }
 
 impl OptionsBuilder {
@@ -0,0 +1,102 @@
+//! Deepgram text to speech API response types.
+
+use std::time::Duration;
+
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use uuid::Uuid;
+
+/// Returned by [`Speak::speak_to_file`](crate::Speak::speak_to_file) and
+/// [`Speak::speak_to_stream`](crate::Speak::speak_to_stream).
+///
+/// `audio` is the path the audio was saved to, or the
+/// [`AudioStream`](super::audio::AudioStream) it can be read from.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct SpeakResponse<A> {
+    #[allow(missing_docs)]
+    pub metadata: SpeakMetadata,
+
+    #[allow(missing_docs)]
+    pub audio: A,
+}
+
+/// Returned by [`Speak::speak_long`](crate::Speak::speak_long).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SpeakLongResponse {
+    /// One entry per request, in the order the audio was joined.
+    pub chunks: Vec<SpeakChunk>,
+
+    /// The audio of every request, joined into a single file.
+    pub audio: Bytes,
+}
+
+/// A single request made by [`Speak::speak_long`](crate::Speak::speak_long).
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub struct SpeakChunk {
+    /// The text synthesized by this request.
+    pub text: String,
+
+    #[allow(missing_docs)]
+    pub metadata: SpeakMetadata,
+
+    /// The number of bytes of audio returned, including any container header.
+    pub audio_len: usize,
+}
+
+/// Metadata about a text to speech request, taken from the response headers
+/// and timing of the request.
+///
+/// Fields are [`None`] when the header is missing or malformed, which may be
+/// the case for self-hosted deployments.
+///
+/// See the [Deepgram API Reference][api] for more info.
+///
+/// [api]: https://developers.deepgram.com/reference/text-to-speech-api
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+#[non_exhaustive]
+pub struct SpeakMetadata {
+    /// The number of characters billed for the request (`dg-char-count`).
+    pub char_count: Option<usize>,
+
+    /// The name of the voice model used (`dg-model-name`).
+    pub model_name: Option<String>,
+
+    /// The UUID of the voice model used (`dg-model-uuid`).
+    pub model_uuid: Option<Uuid>,
+
+    /// The Deepgram request ID (`dg-request-id`).
+    ///
+    /// A request ID needs to be provided to Deepgram as part of any support
+    /// or troubleshooting assistance related to a specific request.
+    pub request_id: Option<Uuid>,
+
+    /// The `Content-Type` of the audio.
+    pub content_type: Option<String>,
+
+    /// The time from sending the request to receiving the response headers,
+    /// before any audio is read.
+    pub latency: Duration,
+}
+
+impl SpeakMetadata {
+    pub(crate) fn from_headers(headers: &HeaderMap, latency: Duration) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+
+        Self {
+            char_count: header("dg-char-count").and_then(|value| value.parse().ok()),
+            model_name: header("dg-model-name").map(str::to_owned),
+            model_uuid: header("dg-model-uuid").and_then(|value| Uuid::parse_str(value).ok()),
+            request_id: header("dg-request-id").and_then(|value| Uuid::parse_str(value).ok()),
+            content_type: header(CONTENT_TYPE.as_str()).map(str::to_owned),
+            latency,
+        }
+    }
+}
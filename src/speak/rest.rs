@@ -1,62 +1,69 @@
 //! Rest TTS module
 
+use std::path::{Path, PathBuf};
+
 use bytes::Bytes;
-use futures::stream::{Stream, StreamExt};
-use reqwest::RequestBuilder;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use reqwest::Response;
 use serde_json::Value;
-use tokio::sync::mpsc;
-use tokio_stream::wrappers::ReceiverStream;
+use tokio::time::Instant;
 use url::Url;
 
-use crate::{DeepgramError, Speak};
+use crate::{
+    endpoints::RetryServerErrors,
+    instrumentation::{self, Instrument, Span},
+    DeepgramError, Speak,
+};
 
-use super::options::{Options, SerializableOptions};
+use super::{
+    audio::{AudioStream, NegotiatedFormat},
+    chunking::{can_join, join_audio, split_text, MAX_TEXT_LENGTH},
+    options::{Options, SerializableOptions},
+    response::{SpeakChunk, SpeakLongResponse, SpeakMetadata, SpeakResponse},
+};
 
 static DEEPGRAM_API_URL_SPEAK: &str = "v1/speak";
 
 impl<'a> Speak<'a> {
-    /// Sends a request to Deepgram to transcribe pre-recorded audio.
+    /// Sends a request to Deepgram to generate speech, saving the audio to `output_file`.
+    ///
+    /// See the [Deepgram API Reference][api] for more info.
+    ///
+    /// [api]: https://developers.deepgram.com/reference/text-to-speech-api
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::{env, path::Path};
+    /// #
+    /// # use deepgram::{speak::options::{Model, Options}, Deepgram, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let options = Options::builder().model(Model::AuraAsteriaEn).build();
+    ///
+    /// let response = dg_client
+    ///     .text_to_speech()
+    ///     .speak_to_file("Hello, world!", &options, Path::new("hello.mp3"))
+    ///     .await?;
+    ///
+    /// println!("Billed for {:?} characters", response.metadata.char_count);
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn speak_to_file(
         &self,
         text: &str,
         options: &Options,
-        output_file: &std::path::Path,
-    ) -> Result<(), DeepgramError> {
-        let payload = Value::Object(
-            [("text".to_string(), Value::String(text.to_string()))]
-                .iter()
-                .cloned()
-                .collect(),
-        );
-
-        let request_builder = self
-            .0
-            .client
-            .post(self.speak_url())
-            .query(&SerializableOptions(options))
-            .json(&payload);
-
-        self.send_and_save_response(request_builder, output_file)
-            .await
-    }
-
-    async fn send_and_save_response(
-        &self,
-        request_builder: RequestBuilder,
-        output_file: &std::path::Path,
-    ) -> Result<(), DeepgramError> {
-        let mut response = request_builder.send().await?;
-
-        if let Err(err) = response.error_for_status_ref() {
-            let status = response.status();
-            let error_text = response.text().await?;
-            eprintln!("Failed to generate speech: {}", status);
-            eprintln!("Error details: {}", error_text);
-            return Err(DeepgramError::DeepgramApiError {
-                body: error_text,
-                err,
-            });
-        }
+        output_file: &Path,
+    ) -> Result<SpeakResponse<PathBuf>, DeepgramError> {
+        let (mut response, metadata, ..) = self.send_speak_request(text, options).await?;
 
         // Create the output file
         let mut file = std::fs::File::create(output_file)?;
@@ -66,17 +73,133 @@ impl<'a> Speak<'a> {
             std::io::copy(&mut chunk.as_ref(), &mut file)?;
         }
 
-        println!("Audio saved to {:?}", output_file);
-
-        Ok(())
+        Ok(SpeakResponse {
+            metadata,
+            audio: output_file.to_path_buf(),
+        })
     }
 
-    /// Sends a request to Deepgram to transcribe pre-recorded audio.
+    /// Sends a request to Deepgram to generate speech, streaming the audio as it is received.
+    ///
+    /// Use [`AudioStream::samples`](super::audio::AudioStream::samples) to
+    /// decode PCM audio into samples.
     pub async fn speak_to_stream(
         &self,
         text: &str,
         options: &Options,
-    ) -> Result<impl Stream<Item = Bytes>, DeepgramError> {
+    ) -> Result<SpeakResponse<AudioStream>, DeepgramError> {
+        let (response, metadata, span, started) = self.send_speak_request(text, options).await?;
+
+        let mut first_chunk = true;
+        let body = response
+            .bytes_stream()
+            .map(move |chunk| {
+                if first_chunk && chunk.is_ok() {
+                    first_chunk = false;
+                    span.in_scope(|| {
+                        instrumentation::record_latency(started, "first audio chunk received")
+                    });
+                }
+                chunk.map_err(DeepgramError::from)
+            })
+            .boxed();
+        let audio = AudioStream::new(options, metadata.content_type.as_deref(), body);
+
+        Ok(SpeakResponse { metadata, audio })
+    }
+
+    /// Sends requests to Deepgram to generate speech for text longer than a
+    /// single request allows.
+    ///
+    /// The text is split with [`split_text`] into pieces of at most
+    /// [`MAX_TEXT_LENGTH`] characters, which are synthesized with up to
+    /// `max_concurrency` requests in flight at a time. The audio is joined in
+    /// order into a single file; see [`chunking`](super::chunking) for how
+    /// each container is handled.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`DeepgramError::UnsupportedAudioFormat`] before sending
+    /// any request if the text needs more than one request and audio in the
+    /// requested format cannot be joined, such as FLAC without a container.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::env;
+    /// #
+    /// # use deepgram::{speak::options::{Encoding, Model, Options}, Deepgram, DeepgramError};
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), DeepgramError> {
+    /// # let deepgram_api_key =
+    /// #     env::var("DEEPGRAM_API_KEY").expect("DEEPGRAM_API_KEY environmental variable");
+    /// #
+    /// let dg_client = Deepgram::new(&deepgram_api_key)?;
+    ///
+    /// let options = Options::builder()
+    ///     .model(Model::AuraAsteriaEn)
+    ///     .encoding(Encoding::Linear16)
+    ///     .build();
+    ///
+    /// let document = std::fs::read_to_string("chapter.txt")?;
+    /// let response = dg_client
+    ///     .text_to_speech()
+    ///     .speak_long(&document, &options, 4)
+    ///     .await?;
+    ///
+    /// std::fs::write("chapter.wav", &response.audio)?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn speak_long(
+        &self,
+        text: &str,
+        options: &Options,
+        max_concurrency: usize,
+    ) -> Result<SpeakLongResponse, DeepgramError> {
+        let pieces = split_text(text, MAX_TEXT_LENGTH);
+        if pieces.len() > 1 {
+            can_join(&NegotiatedFormat::new(options, None))?;
+        }
+
+        let responses: Vec<(SpeakChunk, Bytes)> = stream::iter(pieces)
+            .map(|text| async move {
+                let (response, metadata, ..) = self.send_speak_request(&text, options).await?;
+                let audio = response.bytes().await?;
+                let chunk = SpeakChunk {
+                    text,
+                    metadata,
+                    audio_len: audio.len(),
+                };
+                Ok::<_, DeepgramError>((chunk, audio))
+            })
+            .buffered(max_concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        let (chunks, audio): (Vec<_>, Vec<_>) = responses.into_iter().unzip();
+        let audio = match chunks.first() {
+            Some(first) => {
+                let format = NegotiatedFormat::new(options, first.metadata.content_type.as_deref());
+                join_audio(&format, &audio)?
+            }
+            None => Bytes::new(),
+        };
+
+        Ok(SpeakLongResponse { chunks, audio })
+    }
+
+    /// Sends the request and checks the response for an error, without reading the body.
+    ///
+    /// Also returns the response metadata, and the request span and the time
+    /// the request was sent, so that reading the body can be instrumented.
+    async fn send_speak_request(
+        &self,
+        text: &str,
+        options: &Options,
+    ) -> Result<(Response, SpeakMetadata, Span, Instant), DeepgramError> {
         let payload = Value::Object(
             [("text".to_string(), Value::String(text.to_string()))]
                 .iter()
@@ -84,65 +207,63 @@ impl<'a> Speak<'a> {
                 .collect(),
         );
 
-        let request_builder = self
+        let request = self
             .0
             .client
             .post(self.speak_url())
             .query(&SerializableOptions(options))
-            .json(&payload);
+            .json(&payload)
+            .build()?;
+        let span = instrumentation::request_span(&request);
 
-        self.send_and_stream_response(request_builder).await
-    }
+        async move {
+            let started = Instant::now();
+            let response = self
+                .0
+                .execute_routed(request, RetryServerErrors::Always)
+                .await?;
+            instrumentation::record_response(&response, started);
 
-    async fn send_and_stream_response(
-        &self,
-        request_builder: RequestBuilder,
-    ) -> Result<impl Stream<Item = Bytes>, DeepgramError> {
-        let response = request_builder.send().await?;
-
-        if let Err(err) = response.error_for_status_ref() {
-            let status = response.status();
-            let error_text = response.text().await?;
-            eprintln!("Failed to generate speech: {}", status);
-            eprintln!("Error details: {}", error_text);
-            return Err(DeepgramError::DeepgramApiError {
-                body: error_text,
-                err,
-            });
-        }
-
-        let (tx, rx) = mpsc::channel(1024);
-        let rx_stream = ReceiverStream::new(rx);
-
-        tokio::spawn(async move {
-            let mut stream = response.bytes_stream();
-
-            while let Some(chunk) = stream.next().await {
-                match chunk {
-                    Ok(data) => {
-                        if tx.send(data).await.is_err() {
-                            break;
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Error streaming response: {}", e);
-                        break;
-                    }
+            match response.error_for_status_ref() {
+                Ok(_) => {
+                    let metadata =
+                        SpeakMetadata::from_headers(response.headers(), started.elapsed());
+                    Ok((response, metadata, Span::current(), started))
+                }
+                Err(err) => {
+                    let body = response.text().await?;
+                    instrumentation::event!(WARN, error = %err, body = %body, "request failed");
+                    Err(DeepgramError::DeepgramApiError { body, err })
                 }
             }
-        });
-
-        Ok(rx_stream)
+        }
+        .instrument(span)
+        .await
     }
 
     fn speak_url(&self) -> Url {
-        self.0.base_url.join(DEEPGRAM_API_URL_SPEAK).unwrap()
+        self.0
+            .endpoints
+            .primary()
+            .join(DEEPGRAM_API_URL_SPEAK)
+            .unwrap()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Deepgram;
+    use std::time::Duration;
+
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use uuid::Uuid;
+
+    use crate::{
+        speak::{
+            options::{Encoding, Options},
+            response::SpeakMetadata,
+        },
+        Deepgram, DeepgramError,
+    };
 
     #[test]
     fn listen_url() {
@@ -152,4 +273,42 @@ mod tests {
             "https://api.deepgram.com/v1/speak"
         );
     }
+
+    #[test]
+    fn metadata_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("dg-char-count", HeaderValue::from_static("42"));
+        headers.insert("dg-model-name", HeaderValue::from_static("aura-asteria-en"));
+        headers.insert(
+            "dg-request-id",
+            HeaderValue::from_static("a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8"),
+        );
+        headers.insert("dg-model-uuid", HeaderValue::from_static("not-a-uuid"));
+        headers.insert("content-type", HeaderValue::from_static("audio/mpeg"));
+
+        let metadata = SpeakMetadata::from_headers(&headers, Duration::from_millis(250));
+        assert_eq!(metadata.char_count, Some(42));
+        assert_eq!(metadata.model_name.as_deref(), Some("aura-asteria-en"));
+        assert_eq!(
+            metadata.request_id,
+            Some(Uuid::parse_str("a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8").unwrap())
+        );
+        assert_eq!(metadata.model_uuid, None);
+        assert_eq!(metadata.content_type.as_deref(), Some("audio/mpeg"));
+        assert_eq!(metadata.latency, Duration::from_millis(250));
+    }
+
+    #[tokio::test]
+    async fn refuses_unjoinable_long_text_before_sending() {
+        // Nothing listens on port 1 of the loopback address.
+        let dg = Deepgram::with_base_url("http://127.0.0.1:1").unwrap();
+        let options = Options::builder().encoding(Encoding::Flac).build();
+        let text = "Hello there. ".repeat(200);
+
+        let result = dg.text_to_speech().speak_long(&text, &options, 1).await;
+        assert!(matches!(
+            result,
+            Err(DeepgramError::UnsupportedAudioFormat(_))
+        ));
+    }
 }
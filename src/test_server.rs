@@ -0,0 +1,39 @@
+//! A minimal HTTP server for tests.
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+use url::Url;
+
+/// Answer every request with `status` and the JSON `body`, and return the
+/// server's base URL.
+pub(crate) async fn serve(status: &'static str, body: &'static str) -> Url {
+    serve_with(move |_| (status, body.to_owned())).await
+}
+
+/// Answer every request with the status and JSON body `respond` returns for
+/// its request line, such as `GET /v1/projects HTTP/1.1`, and return the
+/// server's base URL.
+pub(crate) async fn serve_with<F>(respond: F) -> Url
+where
+    F: Fn(&str) -> (&'static str, String) + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut request = [0; 4096];
+            let len = socket.read(&mut request).await.unwrap();
+            let request = String::from_utf8_lossy(&request[..len]);
+            let (status, body) = respond(request.lines().next().unwrap_or_default());
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    Url::parse(&format!("http://{addr}/")).unwrap()
+}